@@ -35,6 +35,12 @@ impl<T: ProtocolData> WsSender<T> {
             log::error!(target: &self.log_target, "Can't send a message to ws outgoing client part: {}", err);
         }
     }
+
+    /// Returns `true` if the connection was closed and
+    /// the sender can't deliver messages anymore.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
 }
 
 /// The active status of a connection.
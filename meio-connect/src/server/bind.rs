@@ -6,10 +6,98 @@ use super::{HttpServer, HttpServerLink};
 use anyhow::Error;
 use async_trait::async_trait;
 use derive_more::From;
+use hyper::server::conn::AddrIncoming;
 use meio::prelude::{
     Action, ActionHandler, Context, Interact, Interaction, InteractionResponder, InteractionTask,
 };
 use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::net::TcpSocket;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+/// The default size of the queue of pending connections.
+pub const DEFAULT_BACKLOG: u32 = 1024;
+
+/// Socket-level options of the listener.
+#[derive(Debug, Clone)]
+pub(super) struct SocketConfig {
+    pub addr: SocketAddr,
+    pub backlog: u32,
+    pub reuse_addr: bool,
+    pub keep_alive: Option<Duration>,
+    /// The path of a Unix domain socket to listen on instead of TCP.
+    #[cfg(unix)]
+    pub uds: Option<PathBuf>,
+}
+
+/// The bound listener.
+pub(super) enum Listener {
+    Tcp(AddrIncoming),
+    #[cfg(unix)]
+    Uds(UnixListener),
+}
+
+impl SocketConfig {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            backlog: DEFAULT_BACKLOG,
+            reuse_addr: true,
+            keep_alive: None,
+            #[cfg(unix)]
+            uds: None,
+        }
+    }
+
+    /// Binds a listener with the options.
+    pub fn bind(&self) -> Result<Listener, Error> {
+        #[cfg(unix)]
+        if let Some(path) = &self.uds {
+            remove_stale_socket(path)?;
+            return Ok(Listener::Uds(UnixListener::bind(path)?));
+        }
+        let socket = if self.addr.is_ipv4() {
+            TcpSocket::new_v4()?
+        } else {
+            TcpSocket::new_v6()?
+        };
+        socket.set_reuseaddr(self.reuse_addr)?;
+        socket.bind(self.addr)?;
+        let listener = socket.listen(self.backlog)?;
+        let mut incoming = AddrIncoming::from_listener(listener)?;
+        incoming.set_keepalive(self.keep_alive);
+        Ok(Listener::Tcp(incoming))
+    }
+}
+
+/// Removes the socket file left by a server that wasn't finished properly.
+///
+/// Fails if the file is not a socket or another server still listens on it.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> Result<(), Error> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixStream;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => {
+            if UnixStream::connect(path).is_ok() {
+                let msg = format!("the socket {} is used by another server", path.display());
+                return Err(Error::msg(msg));
+            }
+            std::fs::remove_file(path)?;
+            Ok(())
+        }
+        Ok(_) => {
+            let msg = format!("the file {} exists and is not a socket", path.display());
+            Err(Error::msg(msg))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
 
 /// The interaction event for waiting for the address of the server.
 ///
@@ -23,6 +111,9 @@ impl Interaction for WaitForAddress {
 
 impl HttpServerLink {
     /// Creates a waiting task for server's address.
+    ///
+    /// The task fails for a server that listens on a Unix domain socket,
+    /// because it has no IP address.
     pub fn wait_for_address(&self) -> InteractionTask<WaitForAddress> {
         self.address.interact(WaitForAddress)
     }
@@ -35,6 +126,17 @@ impl ActionHandler<Interact<WaitForAddress>> for HttpServer {
         msg: Interact<WaitForAddress>,
         _ctx: &mut Context<Self>,
     ) -> Result<(), Error> {
+        #[cfg(unix)]
+        if let Some(path) = &self.socket.uds {
+            let err = Error::msg(format!(
+                "the server listens on the socket {}",
+                path.display()
+            ));
+            if let Err(err) = msg.responder.send(Err(err)) {
+                log::error!(target: &self.log_target, "Can't send address result {:?} to the listener.", err);
+            }
+            return Ok(());
+        }
         match &mut self.addr_state {
             AddrState::NotAssignedYet { listeners } => {
                 listeners.push(msg.responder);
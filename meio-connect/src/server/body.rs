@@ -0,0 +1,99 @@
+//! Streaming of request bodies.
+
+use super::route::{HttpError, Req, RequestData};
+use anyhow::Error;
+use futures::Stream;
+use hyper::body::{Bytes, HttpBody};
+use hyper::{Body, StatusCode};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The body of a request as a `Stream` of chunks.
+///
+/// Chunks are read from the connection only when the stream is polled,
+/// that's why a slow consumer slows down the client instead of buffering
+/// the whole body in memory. The stream yields an error if the client
+/// disconnected before the end of the body and finishes after it.
+pub struct BodyStream {
+    body: Body,
+    max_size: Option<usize>,
+    received: usize,
+    finished: bool,
+}
+
+impl BodyStream {
+    /// Wraps the `body`.
+    pub fn new(body: Body) -> Self {
+        Self {
+            body,
+            max_size: None,
+            received: 0,
+            finished: false,
+        }
+    }
+
+    /// Sets the maximal size of a body in bytes.
+    ///
+    /// The stream yields `413 Payload Too Large` error if the body is bigger.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// How many bytes were received.
+    pub fn received(&self) -> usize {
+        self.received
+    }
+}
+
+impl Stream for BodyStream {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        let item = match Pin::new(&mut self.body).poll_data(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(item) => item,
+        };
+        let item = match item {
+            Some(Ok(chunk)) => {
+                self.received += chunk.len();
+                match self.max_size {
+                    Some(max_size) if self.received > max_size => {
+                        let reason = format!("the body is larger than {} bytes", max_size);
+                        Some(Err(
+                            HttpError::new(StatusCode::PAYLOAD_TOO_LARGE, reason).into()
+                        ))
+                    }
+                    _ => Some(Ok(chunk)),
+                }
+            }
+            Some(Err(err)) => Some(Err(Error::msg(format!("body reading failed: {}", err)))),
+            None => None,
+        };
+        if !matches!(item, Some(Ok(_))) {
+            self.finished = true;
+        }
+        Poll::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            (0, Some(0))
+        } else {
+            (0, None)
+        }
+    }
+}
+
+impl<T: RequestData> Req<T> {
+    /// Takes the body of the request as a stream.
+    ///
+    /// The body of the request becomes empty after that.
+    pub fn body_stream(&mut self) -> BodyStream {
+        let body = std::mem::take(&mut self.body);
+        BodyStream::new(body)
+    }
+}
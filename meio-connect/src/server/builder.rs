@@ -0,0 +1,103 @@
+//! Builder of a server with socket-level options.
+
+use super::bind::DEFAULT_BACKLOG;
+#[cfg(feature = "tls")]
+use super::tls;
+use super::HttpServer;
+use anyhow::Error;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// Configures and creates an `HttpServer`.
+///
+/// The listener is bound when the server is started. If binding failed
+/// and retries are not enabled the server fails to start.
+#[derive(Debug)]
+pub struct HttpServerBuilder {
+    addr: String,
+    backlog: u32,
+    reuse_addr: bool,
+    keep_alive: Option<Duration>,
+    retry_interval: Option<u64>,
+    drain_timeout: Option<Duration>,
+    #[cfg(feature = "tls")]
+    tls: Option<tls::rustls::ServerConfig>,
+}
+
+impl HttpServerBuilder {
+    /// Creates a builder for the bind address like `127.0.0.1:8080`.
+    pub fn new(addr: impl ToString) -> Self {
+        Self {
+            addr: addr.to_string(),
+            backlog: DEFAULT_BACKLOG,
+            reuse_addr: true,
+            keep_alive: None,
+            retry_interval: None,
+            drain_timeout: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+
+    /// Sets the maximum size of the queue of pending connections.
+    pub fn backlog(mut self, backlog: u32) -> Self {
+        self.backlog = backlog;
+        self
+    }
+
+    /// Sets `SO_REUSEADDR` option of the listener (enabled by default).
+    pub fn reuse_addr(mut self, reuse_addr: bool) -> Self {
+        self.reuse_addr = reuse_addr;
+        self
+    }
+
+    /// Enables TCP keep-alive for accepted connections with the idle `time`.
+    pub fn keep_alive(mut self, time: Option<Duration>) -> Self {
+        self.keep_alive = time;
+        self
+    }
+
+    /// Retries to bind in `interval` seconds instead of failing the start.
+    pub fn retry_interval(mut self, interval: u64) -> Self {
+        self.retry_interval = Some(interval);
+        self
+    }
+
+    /// Sets how long in-flight requests can be finished after the interruption.
+    pub fn drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = Some(timeout);
+        self
+    }
+
+    /// Serves HTTPS with the `config`.
+    #[cfg(feature = "tls")]
+    pub fn tls(mut self, config: tls::rustls::ServerConfig) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
+    /// Validates the options and creates the server.
+    pub fn build(self) -> Result<HttpServer, Error> {
+        let addr: SocketAddr = self
+            .addr
+            .parse()
+            .map_err(|err| Error::msg(format!("invalid bind address {}: {}", self.addr, err)))?;
+        if self.backlog == 0 {
+            return Err(Error::msg("the backlog can't be zero"));
+        }
+        #[cfg(feature = "tls")]
+        let mut server = match self.tls {
+            Some(config) => HttpServer::with_tls(addr, self.retry_interval, config),
+            None => HttpServer::new(addr, self.retry_interval),
+        };
+        #[cfg(not(feature = "tls"))]
+        let mut server = HttpServer::new(addr, self.retry_interval);
+        server.socket.backlog = self.backlog;
+        server.socket.reuse_addr = self.reuse_addr;
+        server.socket.keep_alive = self.keep_alive;
+        if let Some(timeout) = self.drain_timeout {
+            server = server.with_drain_timeout(timeout);
+        }
+        Ok(server)
+    }
+}
@@ -0,0 +1,177 @@
+//! Extractors of typed values from requests.
+
+use super::route::{FromRequest, HttpError, Req, RequestData, Route, RouteResult};
+use anyhow::Error;
+use hyper::body::HttpBody;
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use meio::handlers::Interact;
+use meio::prelude::{ActionHandler, Actor, Address, InteractError};
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+use std::net::SocketAddr;
+
+/// Deserializes the query string of a request into a value of type `T`.
+///
+/// Matches requests to the exact `path` only. If the query can't
+/// be parsed the client gets `400 Bad Request` with the reason.
+pub struct Query<T> {
+    path: String,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> Query<T> {
+    /// Creates an extractor for the `path`.
+    pub fn new(path: impl ToString) -> Self {
+        Self {
+            path: path.to_string(),
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<T> FromRequest for Query<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    type Output = T;
+
+    fn from_request(&self, request: &Request<Body>) -> Option<Result<Self::Output, Error>> {
+        let uri = request.uri();
+        if uri.path() == self.path {
+            let query = uri.query().unwrap_or("");
+            let output = serde_urlencoded::from_str(query).map_err(|err| {
+                HttpError::bad_request(format!("invalid query parameters: {}", err)).into()
+            });
+            Some(output)
+        } else {
+            None
+        }
+    }
+}
+
+/// The default limit of a JSON body.
+pub const DEFAULT_JSON_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Deserializes the JSON body of a request into a value of type `T`.
+///
+/// Matches requests to the exact `path` only. Use it with `JsonRoute`,
+/// because the body has to be read before the request is handled.
+pub struct Json<T> {
+    path: String,
+    max_size: usize,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> Json<T> {
+    /// Creates an extractor for the `path`.
+    pub fn new(path: impl ToString) -> Self {
+        Self {
+            path: path.to_string(),
+            max_size: DEFAULT_JSON_LIMIT,
+            _output: PhantomData,
+        }
+    }
+
+    /// Sets the maximal size of a body in bytes.
+    ///
+    /// Bigger bodies are rejected with `413 Payload Too Large`.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+}
+
+impl<T> RequestData for Json<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    type Output = T;
+}
+
+/// The route that reads a JSON body and sends it to an `Actor` as `Req<Json<T>>`.
+///
+/// Malformed bodies are rejected with `400 Bad Request`.
+pub struct JsonRoute<T, A>
+where
+    A: Actor,
+{
+    extractor: Json<T>,
+    address: Address<A>,
+}
+
+impl<T, A> JsonRoute<T, A>
+where
+    A: Actor,
+{
+    /// Creates a new `Route` for JSON requests.
+    pub fn new(extractor: Json<T>, address: Address<A>) -> Self {
+        Self { extractor, address }
+    }
+}
+
+impl<T, A> Route for JsonRoute<T, A>
+where
+    T: DeserializeOwned + Send + 'static,
+    A: Actor + ActionHandler<Interact<Req<Json<T>>>>,
+{
+    fn try_route(&self, addr: &SocketAddr, request: Request<Body>) -> RouteResult {
+        if request.uri().path() != self.extractor.path {
+            return Err(request);
+        }
+        let addr = *addr;
+        let max_size = self.extractor.max_size;
+        let address = self.address.clone();
+        let fut = async move {
+            let content_length = request
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<usize>().ok());
+            if content_length.map(|len| len > max_size).unwrap_or(false) {
+                return Err(too_large(max_size).into());
+            }
+            let mut body = request.into_body();
+            let mut bytes = Vec::new();
+            while let Some(chunk) = body.data().await {
+                let chunk = chunk?;
+                if bytes.len() + chunk.len() > max_size {
+                    return Err(too_large(max_size).into());
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+            let data = serde_json::from_slice(&bytes)
+                .map_err(|err| HttpError::bad_request(format!("invalid json: {}", err)))?;
+            let msg = Req {
+                addr,
+                data,
+                body: Body::empty(),
+            };
+            address
+                .interact(msg)
+                .recv()
+                .await
+                .map_err(InteractError::into_error)
+        };
+        Ok(Box::pin(fut))
+    }
+}
+
+fn too_large(max_size: usize) -> HttpError {
+    let reason = format!("the body is larger than {} bytes", max_size);
+    HttpError::new(StatusCode::PAYLOAD_TOO_LARGE, reason)
+}
+
+/// The response with a JSON body.
+pub struct JsonResponse<T>(pub T);
+
+impl<T: Serialize> JsonResponse<T> {
+    /// Serializes the value into a response with `Content-Type: application/json`.
+    pub fn into_response(self) -> Result<Response<Body>, Error> {
+        let body = serde_json::to_vec(&self.0)?;
+        let response = Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.into())?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,161 @@
+//! Serving static files from a directory.
+
+use super::route::{HttpError, Route, RouteResult};
+use super::HttpServerLink;
+use anyhow::Error;
+use futures::stream;
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use percent_encoding::percent_decode_str;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+
+/// The size of chunks of a streamed file.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The file served for a directory.
+const INDEX_FILE: &str = "index.html";
+
+impl HttpServerLink {
+    /// Serves files from the `dir` under the `mount` path.
+    pub fn add_static_dir(&mut self, mount: &str, dir: PathBuf) -> Result<(), Error> {
+        self.add_route(StaticRoute::new(mount, dir))
+    }
+}
+
+/// The route that serves files from a directory.
+///
+/// Responds to `GET` and `HEAD` requests only. Paths that
+/// try to leave the directory are rejected and symlinks that
+/// lead outside of the directory are not followed.
+pub struct StaticRoute {
+    mount: String,
+    dir: PathBuf,
+}
+
+impl StaticRoute {
+    /// Creates a new route for files from the `dir` under the `mount` path.
+    pub fn new(mount: &str, dir: PathBuf) -> Self {
+        Self {
+            mount: mount.trim_end_matches('/').to_string(),
+            dir,
+        }
+    }
+
+    /// Returns the relative path of a file if the `path` is under the mount path.
+    fn relative<'a>(&self, path: &'a str) -> Option<&'a str> {
+        let rest = path.strip_prefix(&self.mount)?;
+        if rest.is_empty() || rest.starts_with('/') {
+            Some(rest.trim_start_matches('/'))
+        } else {
+            None
+        }
+    }
+}
+
+impl Route for StaticRoute {
+    fn try_route(&self, _addr: &SocketAddr, request: Request<Body>) -> RouteResult {
+        let method = request.method();
+        if method != Method::GET && method != Method::HEAD {
+            return Err(request);
+        }
+        let relative = match self.relative(request.uri().path()) {
+            Some(relative) => relative,
+            None => return Err(request),
+        };
+        let path = resolve(&self.dir, relative);
+        let dir = self.dir.clone();
+        let head = method == Method::HEAD;
+        let fut = async move {
+            let path = path.ok_or_else(|| HttpError::bad_request("invalid path"))?;
+            serve_file(&dir, path, head).await
+        };
+        Ok(Box::pin(fut))
+    }
+}
+
+/// Joins the decoded `relative` path to the `dir`.
+///
+/// Returns `None` if the path contains segments that
+/// could refer to files outside of the `dir`.
+fn resolve(dir: &Path, relative: &str) -> Option<PathBuf> {
+    let decoded = percent_decode_str(relative).decode_utf8().ok()?;
+    let mut path = dir.to_path_buf();
+    for segment in decoded.split('/').filter(|segment| !segment.is_empty()) {
+        if segment == ".." || segment == "." || segment.contains(['\\', '\0']) {
+            return None;
+        }
+        path.push(segment);
+    }
+    Some(path)
+}
+
+async fn serve_file(dir: &Path, mut path: PathBuf, head: bool) -> Result<Response<Body>, Error> {
+    let not_found = || HttpError::new(StatusCode::NOT_FOUND, "file not found");
+    let mut metadata = tokio::fs::metadata(&path).await.map_err(|_| not_found())?;
+    if metadata.is_dir() {
+        path.push(INDEX_FILE);
+        metadata = tokio::fs::metadata(&path).await.map_err(|_| not_found())?;
+    }
+    // Symlinks are resolved to check the file is still inside the `dir`.
+    let dir = tokio::fs::canonicalize(dir)
+        .await
+        .map_err(|_| not_found())?;
+    let real_path = tokio::fs::canonicalize(&path)
+        .await
+        .map_err(|_| not_found())?;
+    if !real_path.starts_with(&dir) {
+        return Err(not_found().into());
+    }
+    let file = File::open(&real_path).await.map_err(|_| not_found())?;
+    let body = if head {
+        Body::empty()
+    } else {
+        let chunks = stream::try_unfold(file, |mut file| async move {
+            let mut buf = vec![0; CHUNK_SIZE];
+            let len = file.read(&mut buf).await?;
+            if len == 0 {
+                Ok::<_, std::io::Error>(None)
+            } else {
+                buf.truncate(len);
+                Ok(Some((buf, file)))
+            }
+        });
+        Body::wrap_stream(chunks)
+    };
+    let response = Response::builder()
+        .header(CONTENT_TYPE, content_type(&path))
+        .header(CONTENT_LENGTH, metadata.len())
+        .body(body)?;
+    Ok(response)
+}
+
+/// Guesses the content type by the extension of a file.
+fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("html") | Some("htm") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") | Some("mjs") => "application/javascript",
+        Some("json") | Some("map") => "application/json",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("xml") => "application/xml",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("wasm") => "application/wasm",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,98 @@
+//! Health checks with the lame duck mode for draining by load balancers.
+
+use super::route::{Route, RouteResult};
+use super::{HttpServer, HttpServerLink};
+use anyhow::Error;
+use async_trait::async_trait;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use meio::prelude::{Action, ActionHandler, Context};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+impl HttpServerLink {
+    /// Adds the health check route to the `path`.
+    ///
+    /// The route responds with `200 OK`, but with `503 Service Unavailable`
+    /// in the lame duck mode. It's matched before all routes added with
+    /// `add_route`, even catch-all ones added earlier.
+    pub fn add_health_route(&mut self, path: &str) -> Result<(), Error> {
+        let msg = AddHealthRoute {
+            path: path.to_string(),
+        };
+        self.address.act(msg)?;
+        Ok(())
+    }
+
+    /// Turns on or off the lame duck mode.
+    ///
+    /// The server keeps serving all requests in the mode, but fails
+    /// health checks to let a load balancer stop routing to it before
+    /// the shutdown.
+    pub fn set_lame_duck(&mut self, enabled: bool) -> Result<(), Error> {
+        self.address.act(SetLameDuck(enabled))?;
+        Ok(())
+    }
+}
+
+struct AddHealthRoute {
+    path: String,
+}
+
+impl Action for AddHealthRoute {}
+
+#[async_trait]
+impl ActionHandler<AddHealthRoute> for HttpServer {
+    async fn handle(&mut self, msg: AddHealthRoute, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        let route = HealthRoute::new(msg.path, self.lame_duck.clone());
+        self.routing_table.insert_prior_route(Box::new(route)).await;
+        Ok(())
+    }
+}
+
+struct SetLameDuck(bool);
+
+impl Action for SetLameDuck {}
+
+#[async_trait]
+impl ActionHandler<SetLameDuck> for HttpServer {
+    async fn handle(&mut self, msg: SetLameDuck, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        log::info!(target: &self.log_target, "Lame duck mode: {}", msg.0);
+        self.lame_duck.store(msg.0, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// The route of health checks that fails in the lame duck mode.
+///
+/// The flag is shared with the server and is read without
+/// the actor's loop.
+pub struct HealthRoute {
+    path: String,
+    lame_duck: Arc<AtomicBool>,
+}
+
+impl HealthRoute {
+    pub(super) fn new(path: String, lame_duck: Arc<AtomicBool>) -> Self {
+        Self { path, lame_duck }
+    }
+}
+
+impl Route for HealthRoute {
+    fn try_route(&self, _addr: &SocketAddr, request: Request<Body>) -> RouteResult {
+        let method = request.method();
+        if (method != Method::GET && method != Method::HEAD) || request.uri().path() != self.path {
+            return Err(request);
+        }
+        let (status, body) = if self.lame_duck.load(Ordering::SeqCst) {
+            (StatusCode::SERVICE_UNAVAILABLE, "lame duck")
+        } else {
+            (StatusCode::OK, "OK")
+        };
+        let fut = async move {
+            let response = Response::builder().status(status).body(Body::from(body))?;
+            Ok(response)
+        };
+        Ok(Box::pin(fut))
+    }
+}
@@ -1,6 +1,8 @@
 //! The link to interact with a server instance.
 
 use super::{
+    middleware::{BoxedMiddleware, Middleware},
+    recover::{BoxedErrorHandler, ErrorHandler},
     route::{BoxedRoute, Route},
     HttpServer, HttpServerLink,
 };
@@ -17,7 +19,8 @@ impl HttpServerLink {
         let msg = AddRoute {
             route: Box::new(route),
         };
-        self.address.act(msg)
+        self.address.act(msg)?;
+        Ok(())
     }
 }
 
@@ -34,3 +37,67 @@ impl ActionHandler<AddRoute> for HttpServer {
         Ok(())
     }
 }
+
+impl HttpServerLink {
+    /// Adds a middleware that runs before the routing of every request.
+    ///
+    /// Middlewares run in the order they were added.
+    pub fn add_middleware<T>(&mut self, middleware: T) -> Result<(), Error>
+    where
+        T: Middleware,
+    {
+        let msg = AddMiddleware {
+            middleware: Box::new(middleware),
+        };
+        self.address.act(msg)?;
+        Ok(())
+    }
+}
+
+struct AddMiddleware {
+    pub middleware: BoxedMiddleware,
+}
+
+impl Action for AddMiddleware {}
+
+#[async_trait]
+impl ActionHandler<AddMiddleware> for HttpServer {
+    async fn handle(&mut self, msg: AddMiddleware, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        self.routing_table.insert_middleware(msg.middleware).await;
+        Ok(())
+    }
+}
+
+impl HttpServerLink {
+    /// Adds a handler that converts errors of routes into responses.
+    ///
+    /// Handlers are tried in the order they were added.
+    pub fn add_error_handler<T>(&mut self, handler: T) -> Result<(), Error>
+    where
+        T: ErrorHandler,
+    {
+        let msg = AddErrorHandler {
+            handler: Box::new(handler),
+        };
+        self.address.act(msg)?;
+        Ok(())
+    }
+}
+
+struct AddErrorHandler {
+    pub handler: BoxedErrorHandler,
+}
+
+impl Action for AddErrorHandler {}
+
+#[async_trait]
+impl ActionHandler<AddErrorHandler> for HttpServer {
+    async fn handle(
+        &mut self,
+        msg: AddErrorHandler,
+        _ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        self.routing_table.insert_error_handler(msg.handler).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+//! Cross-cutting processing of all requests.
+
+use hyper::{Body, Request, Response};
+use std::net::SocketAddr;
+
+/// A boxed middleware alias to hold them in the `RoutingTable`.
+pub type BoxedMiddleware = Box<dyn Middleware>;
+
+/// The processor of every request that runs before routes.
+pub trait Middleware: Send + Sync + 'static {
+    /// Inspects or modifies a request before the routing.
+    ///
+    /// Returns a response to reply with it immediately and
+    /// skip routes and the following middlewares.
+    fn on_request(&self, addr: &SocketAddr, request: &mut Request<Body>) -> Option<Response<Body>>;
+
+    /// Modifies a response before it's sent to a client.
+    ///
+    /// Called for short-circuited responses as well.
+    fn on_response(&self, _response: &mut Response<Body>) {}
+}
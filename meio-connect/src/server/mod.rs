@@ -1,13 +1,33 @@
 //! Actor for launch a server.
 
 pub mod bind;
+pub mod body;
+pub mod builder;
+pub mod extract;
+pub mod files;
+pub mod health;
 pub mod link;
+pub mod middleware;
+pub mod path;
+pub mod recover;
 pub mod route;
 mod routine;
+pub mod stats;
+#[cfg(feature = "tls")]
+pub mod tls;
 pub mod websocket;
 
 pub use bind::WaitForAddress;
-pub use route::{DirectPath, FromRequest, NoParameters, Req, WebRoute};
+pub use body::BodyStream;
+pub use builder::HttpServerBuilder;
+pub use extract::{Json, JsonResponse, JsonRoute, Query};
+pub use files::StaticRoute;
+pub use health::HealthRoute;
+pub use middleware::Middleware;
+pub use path::{PathParams, PathPattern};
+pub use recover::{ErrorHandler, MapError};
+pub use route::{DirectPath, FromRequest, HttpError, NoParameters, Req, RequestData, WebRoute};
+pub use stats::{GetStats, HttpStats};
 pub use websocket::{WsHandler, WsProcessor, WsReq, WsRoute};
 
 use anyhow::Error;
@@ -15,6 +35,11 @@ use async_trait::async_trait;
 use derive_more::From;
 use meio::prelude::{Actor, Address, Context, InterruptedBy, StartedBy};
 use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// The link to a HTTP server instance.
 #[derive(Debug, Clone, From)]
@@ -27,27 +52,79 @@ pub struct HttpServer {
     log_target: String,
     // TODO: Use submodules' types instead
     // (don't use plain fields from all modules)
-    addr: SocketAddr,
+    socket: bind::SocketConfig,
     addr_state: bind::AddrState,
     routing_table: route::RoutingTable,
     /// Interval (seconds) of retry if binding failed.
     retry_interval: Option<u64>,
+    /// How long in-flight requests can be finished after the interruption.
+    drain_timeout: Duration,
+    stats: Arc<stats::StatsCollector>,
+    /// Fails health checks if set.
+    lame_duck: Arc<AtomicBool>,
+    #[cfg(feature = "tls")]
+    tls: Option<Arc<tls::rustls::ServerConfig>>,
 }
 
 impl HttpServer {
     /// Creates a new server instance.
     /// It will be bind to `addr` and if binding failed will retry
     /// to bind again in `retry_interval` seconds.
+    ///
+    /// Use `HttpServerBuilder` to configure socket options.
     pub fn new(addr: SocketAddr, retry_interval: Option<u64>) -> Self {
         let log_target = format!("HttpServer::{}", addr);
         Self {
             log_target,
-            addr,
+            socket: bind::SocketConfig::new(addr),
             addr_state: bind::AddrState::default(),
             routing_table: route::RoutingTable::default(),
             retry_interval,
+            drain_timeout: routine::DEFAULT_DRAIN_TIMEOUT,
+            stats: Arc::default(),
+            lame_duck: Arc::default(),
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
+
+    /// Creates a new server instance that listens on a Unix domain socket.
+    ///
+    /// The socket file is removed when the server is finished. A stale socket
+    /// file left by a crashed server is removed before binding.
+    /// `WaitForAddress` fails for this server, because it has no IP address,
+    /// and routes get the unspecified address of the client.
+    #[cfg(unix)]
+    pub fn bind_uds(path: impl Into<PathBuf>, retry_interval: Option<u64>) -> Self {
+        let path = path.into();
+        let mut this = Self::new(SocketAddr::from(([0, 0, 0, 0], 0)), retry_interval);
+        this.log_target = format!("HttpServer::{}", path.display());
+        this.socket.uds = Some(path);
+        this
+    }
+
+    /// Creates a new server instance that serves HTTPS.
+    ///
+    /// Use `tls::load_config` to create the `config` from PEM files.
+    #[cfg(feature = "tls")]
+    pub fn with_tls(
+        addr: SocketAddr,
+        retry_interval: Option<u64>,
+        config: tls::rustls::ServerConfig,
+    ) -> Self {
+        let mut this = Self::new(addr, retry_interval);
+        this.log_target = format!("HttpsServer::{}", addr);
+        this.tls = Some(Arc::new(config));
+        this
+    }
+
+    /// Sets how long the server waits for in-flight requests
+    /// when it's interrupted. New connections are not accepted
+    /// during that time.
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
 }
 
 impl Actor for HttpServer {
@@ -61,7 +138,18 @@ impl Actor for HttpServer {
 #[async_trait]
 impl<T: Actor> StartedBy<T> for HttpServer {
     async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
-        self.start_http_listener(ctx);
+        // Binds here to fail the start if retries are not enabled.
+        match self.socket.bind() {
+            Ok(listener) => {
+                self.start_http_listener(Some(listener), ctx);
+            }
+            Err(err) => {
+                log::error!(target: &self.log_target, "Can't bind the listener: {}", err);
+                if !self.schedule_restart(ctx)? {
+                    return Err(err);
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -76,7 +164,24 @@ impl<T: Actor> InterruptedBy<T> for HttpServer {
 
 #[cfg(test)]
 mod tests {
-    use serde::Deserialize;
+    use super::route::{Route, RouteResult};
+    use super::{
+        BodyStream, ErrorHandler, FromRequest, HealthRoute, HttpError, HttpServer,
+        HttpServerBuilder, HttpServerLink, Json, JsonResponse, JsonRoute, MapError, Middleware,
+        PathParams, PathPattern, Query, Req, StaticRoute,
+    };
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::executor::block_on;
+    use hyper::{Body, Request, Response, StatusCode};
+    use meio::prelude::{Actor, AddressPair, Context, InteractionHandler, System};
+    use serde::{Deserialize, Serialize};
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::Notify;
+    use tokio::time::{sleep, timeout};
 
     #[derive(Default, Deserialize)]
     struct Index {}
@@ -96,4 +201,513 @@ mod tests {
         let api_query: ApiQuery = serde_qs::from_str("query=abc").unwrap();
         assert_eq!(api_query.query, "abc");
     }
+
+    #[test]
+    fn path_pattern() {
+        let pattern = PathPattern::new("/users/:id/posts/:post");
+        let captures = pattern.captures("/users/12/posts/abc").unwrap();
+        assert_eq!(captures, vec![("id", "12"), ("post", "abc")]);
+        assert!(pattern.captures("/users/12/posts").is_none());
+        assert!(pattern.captures("/users/12/posts/abc/more").is_none());
+        assert!(pattern.captures("/users//posts/abc").is_none());
+    }
+
+    #[derive(Deserialize)]
+    struct UserId {
+        id: u64,
+    }
+
+    #[test]
+    fn path_params() {
+        let extractor = PathParams::<UserId>::new("/users/:id");
+        let request = Request::get("/users/42").body(Body::empty()).unwrap();
+        let user = extractor.from_request(&request).unwrap().unwrap();
+        assert_eq!(user.id, 42);
+        let request = Request::get("/users/abc").body(Body::empty()).unwrap();
+        assert!(extractor.from_request(&request).unwrap().is_err());
+        let request = Request::get("/groups/42").body(Body::empty()).unwrap();
+        assert!(extractor.from_request(&request).is_none());
+    }
+
+    #[derive(Deserialize)]
+    struct Pagination {
+        page: u32,
+        limit: u32,
+    }
+
+    #[test]
+    fn query_extractor() {
+        let extractor = Query::<Pagination>::new("/items");
+        let request = Request::get("/items?page=2&limit=50")
+            .body(Body::empty())
+            .unwrap();
+        let pagination = extractor.from_request(&request).unwrap().unwrap();
+        assert_eq!((pagination.page, pagination.limit), (2, 50));
+        let request = Request::get("/items?page=two").body(Body::empty()).unwrap();
+        let err = extractor.from_request(&request).unwrap().err().unwrap();
+        let err = err.downcast::<HttpError>().unwrap();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        let request = Request::get("/other?page=2").body(Body::empty()).unwrap();
+        assert!(extractor.from_request(&request).is_none());
+    }
+
+    #[derive(Deserialize, Serialize)]
+    struct Item {
+        name: String,
+    }
+
+    struct ItemStore;
+
+    impl Actor for ItemStore {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "ItemStore"
+        }
+    }
+
+    #[async_trait]
+    impl InteractionHandler<Req<Json<Item>>> for ItemStore {
+        async fn handle(
+            &mut self,
+            msg: Req<Json<Item>>,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Response<Body>, Error> {
+            JsonResponse(msg.data).into_response()
+        }
+    }
+
+    fn json_error(route: &JsonRoute<Item, ItemStore>, body: &'static str) -> HttpError {
+        let addr = "127.0.0.1:0".parse().unwrap();
+        let request = Request::post("/items").body(Body::from(body)).unwrap();
+        let fut = route.try_route(&addr, request).ok().unwrap();
+        block_on(fut)
+            .err()
+            .unwrap()
+            .downcast::<HttpError>()
+            .unwrap()
+    }
+
+    #[test]
+    fn json_route() {
+        let pair = AddressPair::<ItemStore>::new();
+        let address = pair.address().clone();
+        let route = JsonRoute::new(Json::<Item>::new("/items").max_size(16), address);
+        let addr = "127.0.0.1:0".parse().unwrap();
+        let request = Request::post("/other").body(Body::empty()).unwrap();
+        assert!(route.try_route(&addr, request).is_err());
+        let err = json_error(&route, "{\"name\":");
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        let err = json_error(&route, "{\"name\":\"too long name\"}");
+        assert_eq!(err.status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn json_response() {
+        let item = Item {
+            name: "meio".into(),
+        };
+        let response = JsonResponse(item).into_response().unwrap();
+        let content_type = response.headers().get(hyper::header::CONTENT_TYPE);
+        assert_eq!(content_type.unwrap(), "application/json");
+    }
+
+    #[test]
+    fn static_route_traversal() {
+        let route = StaticRoute::new("/static/", "/var/www".into());
+        let addr = "127.0.0.1:0".parse().unwrap();
+        let request = Request::get("/staticfile").body(Body::empty()).unwrap();
+        assert!(route.try_route(&addr, request).is_err());
+        let request = Request::post("/static/app.js").body(Body::empty()).unwrap();
+        assert!(route.try_route(&addr, request).is_err());
+        for path in ["/static/../etc/passwd", "/static/%2e%2e/etc/passwd"] {
+            let request = Request::get(path).body(Body::empty()).unwrap();
+            let fut = route.try_route(&addr, request).ok().unwrap();
+            let err = block_on(fut)
+                .err()
+                .unwrap()
+                .downcast::<HttpError>()
+                .unwrap();
+            assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn health_route() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let lame_duck = Arc::new(AtomicBool::new(false));
+        let route = HealthRoute::new("/health".into(), lame_duck.clone());
+        let addr = "127.0.0.1:0".parse().unwrap();
+        let status = |route: &HealthRoute| {
+            let request = Request::get("/health").body(Body::empty()).unwrap();
+            let fut = route.try_route(&addr, request).ok().unwrap();
+            block_on(fut).unwrap().status()
+        };
+        assert_eq!(status(&route), StatusCode::OK);
+        lame_duck.store(true, Ordering::SeqCst);
+        assert_eq!(status(&route), StatusCode::SERVICE_UNAVAILABLE);
+        let request = Request::get("/api").body(Body::empty()).unwrap();
+        assert!(route.try_route(&addr, request).is_err());
+    }
+
+    #[test]
+    fn ws_sender_closed() {
+        let (tx, rx) = futures::channel::mpsc::unbounded::<u8>();
+        let sender = crate::client::WsSender::new("Test", tx);
+        assert!(!sender.is_closed());
+        drop(rx);
+        assert!(sender.is_closed());
+    }
+
+    #[test]
+    fn server_builder() {
+        assert!(HttpServerBuilder::new("localhost").build().is_err());
+        let zero_backlog = HttpServerBuilder::new("127.0.0.1:8080").backlog(0);
+        assert!(zero_backlog.build().is_err());
+        let server = HttpServerBuilder::new("127.0.0.1:8080")
+            .backlog(16)
+            .reuse_addr(false)
+            .keep_alive(Some(Duration::from_secs(30)))
+            .build()
+            .unwrap();
+        assert_eq!(server.socket.addr, "127.0.0.1:8080".parse().unwrap());
+        assert_eq!(server.socket.backlog, 16);
+        assert!(!server.socket.reuse_addr);
+        assert_eq!(server.socket.keep_alive, Some(Duration::from_secs(30)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn static_route_symlinks() {
+        let root = std::env::temp_dir().join(format!("meio-static-{}", std::process::id()));
+        let dir = root.join("www");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("app.js"), "app").unwrap();
+        std::fs::write(root.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(root.join("secret.txt"), dir.join("leak.txt")).unwrap();
+        std::os::unix::fs::symlink(dir.join("app.js"), dir.join("alias.js")).unwrap();
+        let route = StaticRoute::new("/static", dir);
+        let addr = "127.0.0.1:0".parse().unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let status = |path: &str| {
+            let request = Request::get(path).body(Body::empty()).unwrap();
+            let fut = route.try_route(&addr, request).ok().unwrap();
+            match runtime.block_on(fut) {
+                Ok(response) => response.status(),
+                Err(err) => err.downcast::<HttpError>().unwrap().status,
+            }
+        };
+        assert_eq!(status("/static/app.js"), StatusCode::OK);
+        assert_eq!(status("/static/alias.js"), StatusCode::OK);
+        assert_eq!(status("/static/leak.txt"), StatusCode::NOT_FOUND);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn uds_listener() {
+        let path = std::env::temp_dir().join(format!("meio-uds-{}.sock", std::process::id()));
+        let server = super::HttpServer::bind_uds(&path, None);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let listener = server.socket.bind().unwrap();
+            assert!(matches!(listener, super::bind::Listener::Uds(_)));
+            assert!(path.exists());
+            // Another server can't take the socket that is in use.
+            assert!(server.socket.bind().is_err());
+            drop(listener);
+            // The stale socket file doesn't prevent binding.
+            let listener = server.socket.bind().unwrap();
+            assert!(matches!(listener, super::bind::Listener::Uds(_)));
+        });
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn uds_wait_for_address() {
+        let path = std::env::temp_dir().join(format!("meio-uds-wait-{}.sock", std::process::id()));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let address = System::spawn(HttpServer::bind_uds(&path, None));
+            let link = HttpServerLink::from(address.clone());
+            let res = timeout(Duration::from_secs(5), link.wait_for_address().recv()).await;
+            assert!(res.unwrap().is_err());
+            System::interrupt(&address).unwrap();
+            timeout(Duration::from_secs(5), address.join())
+                .await
+                .unwrap();
+        });
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn body_stream() {
+        use futures::StreamExt;
+        use std::io;
+
+        let chunks: Vec<Result<&'static str, io::Error>> = vec![Ok("abc"), Ok("def")];
+        let stream = BodyStream::new(Body::wrap_stream(futures::stream::iter(chunks)));
+        let chunks: Vec<_> = block_on(stream.collect());
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].as_ref().unwrap().as_ref(), b"def");
+
+        let stream = BodyStream::new(Body::from("too long body")).max_size(4);
+        let mut chunks: Vec<_> = block_on(stream.collect());
+        let err = chunks.pop().unwrap().unwrap_err();
+        assert_eq!(
+            err.downcast::<HttpError>().unwrap().status,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert!(chunks.is_empty());
+
+        // The client disconnected in the middle of the body.
+        let chunks: Vec<Result<&'static str, io::Error>> = vec![
+            Ok("abc"),
+            Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset")),
+            Ok("def"),
+        ];
+        let stream = BodyStream::new(Body::wrap_stream(futures::stream::iter(chunks)));
+        let chunks: Vec<_> = block_on(stream.collect());
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[1].is_err());
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("item not found")]
+    struct NotFound;
+
+    #[test]
+    fn map_error() {
+        let handler = MapError::new(|err: &NotFound| {
+            let body = serde_json::json!({ "error": err.to_string() });
+            let mut response = JsonResponse(body).into_response().unwrap();
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            response
+        });
+        let response = handler.on_error(&NotFound.into()).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(handler.on_error(&Error::msg("other")).is_none());
+    }
+
+    #[test]
+    fn stats_snapshot() {
+        let stats = super::stats::StatsCollector::default();
+        stats.connection_opened();
+        stats.connection_opened();
+        stats.connection_closed();
+        stats.request_received();
+        stats.request_received();
+        stats.route_matched(1);
+        stats.route_not_found();
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.active_connections, 1);
+        assert_eq!(snapshot.requests, 2);
+        assert_eq!(snapshot.not_found, 1);
+        assert_eq!(snapshot.route_hits.get(&1), Some(&1));
+    }
+
+    /// Responds after the `delay` and notifies when a request arrived.
+    struct SlowRoute {
+        delay: Duration,
+        arrived: Arc<Notify>,
+    }
+
+    impl Route for SlowRoute {
+        fn try_route(&self, _addr: &SocketAddr, _request: Request<Body>) -> RouteResult {
+            let delay = self.delay;
+            let arrived = self.arrived.clone();
+            let fut = async move {
+                arrived.notify_one();
+                sleep(delay).await;
+                Ok(Response::new(Body::from("done")))
+            };
+            Ok(Box::pin(fut))
+        }
+    }
+
+    /// Sends a request to the server with the `SlowRoute` and interrupts
+    /// the server when the request is in flight.
+    async fn interrupt_in_flight(
+        delay: Duration,
+        drain_timeout: Duration,
+    ) -> Result<Result<Response<Body>, hyper::Error>, Error> {
+        let server =
+            HttpServer::new("127.0.0.1:0".parse()?, None).with_drain_timeout(drain_timeout);
+        let address = System::spawn(server);
+        let mut link = HttpServerLink::from(address.clone());
+        let arrived = Arc::new(Notify::new());
+        link.add_route(SlowRoute {
+            delay,
+            arrived: arrived.clone(),
+        })?;
+        let addr = link.wait_for_address().recv().await?;
+        let uri = format!("http://{}/slow", addr).parse()?;
+        let request = tokio::spawn(hyper::Client::new().get(uri));
+        arrived.notified().await;
+        System::interrupt(&address)?;
+        let response = timeout(Duration::from_secs(5), request).await??;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(response)
+    }
+
+    #[test]
+    fn drain_in_flight() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let delay = Duration::from_millis(100);
+            let response = interrupt_in_flight(delay, Duration::from_secs(5))
+                .await
+                .unwrap();
+            assert_eq!(response.unwrap().status(), StatusCode::OK);
+            // The request is cut off when the drain timeout elapsed.
+            let delay = Duration::from_secs(5);
+            let response = interrupt_in_flight(delay, Duration::from_millis(50))
+                .await
+                .unwrap();
+            assert!(response.is_err());
+        });
+    }
+
+    /// Logs requests and marks responses with its name.
+    /// Replies with `403` if the request has `x-deny` header with the name.
+    struct Tagging {
+        name: &'static str,
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Middleware for Tagging {
+        fn on_request(
+            &self,
+            _addr: &SocketAddr,
+            request: &mut Request<Body>,
+        ) -> Option<Response<Body>> {
+            self.log.lock().unwrap().push(self.name);
+            if request.headers().get("x-deny")? == self.name {
+                let mut response = Response::new(Body::empty());
+                *response.status_mut() = StatusCode::FORBIDDEN;
+                Some(response)
+            } else {
+                None
+            }
+        }
+
+        fn on_response(&self, response: &mut Response<Body>) {
+            let value = hyper::header::HeaderValue::from_static(self.name);
+            response.headers_mut().append("x-tag", value);
+        }
+    }
+
+    /// Counts routed requests.
+    struct CountingRoute {
+        hits: Arc<AtomicUsize>,
+    }
+
+    impl Route for CountingRoute {
+        fn try_route(&self, _addr: &SocketAddr, _request: Request<Body>) -> RouteResult {
+            self.hits.fetch_add(1, Ordering::SeqCst);
+            Ok(Box::pin(async { Ok(Response::new(Body::empty())) }))
+        }
+    }
+
+    #[test]
+    fn middlewares() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let server = HttpServer::new("127.0.0.1:0".parse().unwrap(), None);
+            let address = System::spawn(server);
+            let mut link = HttpServerLink::from(address.clone());
+            let log = Arc::new(Mutex::new(Vec::new()));
+            for name in ["first", "second"] {
+                let log = log.clone();
+                link.add_middleware(Tagging { name, log }).unwrap();
+            }
+            let hits = Arc::new(AtomicUsize::new(0));
+            link.add_route(CountingRoute { hits: hits.clone() })
+                .unwrap();
+            let addr = link.wait_for_address().recv().await.unwrap();
+            let client = hyper::Client::new();
+            let send = |deny: &str| {
+                let request = Request::get(format!("http://{}/", addr))
+                    .header("x-deny", deny)
+                    .body(Body::empty())
+                    .unwrap();
+                client.request(request)
+            };
+            let tags = |response: &Response<Body>| {
+                let values = response.headers().get_all("x-tag").iter();
+                values
+                    .map(|value| value.to_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            };
+            // Middlewares run in the order they were added.
+            let response = send("none").await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(*log.lock().unwrap(), vec!["first", "second"]);
+            assert_eq!(tags(&response), vec!["first", "second"]);
+            assert_eq!(hits.load(Ordering::SeqCst), 1);
+            // The short-circuit skips routes and the following middlewares,
+            // but every middleware can modify the response.
+            log.lock().unwrap().clear();
+            let response = send("first").await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+            assert_eq!(*log.lock().unwrap(), vec!["first"]);
+            assert_eq!(tags(&response), vec!["first", "second"]);
+            assert_eq!(hits.load(Ordering::SeqCst), 1);
+            System::interrupt(&address).unwrap();
+            address.join().await;
+        });
+    }
+
+    #[test]
+    fn health_route_is_prior() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let mut table = super::route::RoutingTable::default();
+            let hits = Arc::new(AtomicUsize::new(0));
+            table
+                .insert_route(Box::new(CountingRoute { hits: hits.clone() }))
+                .await;
+            let lame_duck = Arc::new(std::sync::atomic::AtomicBool::new(true));
+            let health = HealthRoute::new("/health".into(), lame_duck);
+            table.insert_prior_route(Box::new(health)).await;
+            let addr = "127.0.0.1:0".parse().unwrap();
+            let routes = table.routes().await;
+            let mut request = Request::get("/health").body(Body::empty()).unwrap();
+            let mut response = None;
+            for (_, route) in routes.iter() {
+                match route.try_route(&addr, request) {
+                    Ok(fut) => {
+                        response = Some(fut.await.unwrap());
+                        break;
+                    }
+                    Err(req) => request = req,
+                }
+            }
+            // The catch-all route added earlier doesn't shadow the health route.
+            let status = response.unwrap().status();
+            assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(hits.load(Ordering::SeqCst), 0);
+        });
+    }
 }
@@ -0,0 +1,120 @@
+//! Routes with named segments in a path.
+
+use super::route::{FromRequest, RouteError};
+use anyhow::Error;
+use hyper::{Body, Request};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Exact(String),
+    Named(String),
+}
+
+/// The pattern of a path like `/users/:id`.
+///
+/// Segments started with `:` match any non-empty segment
+/// and capture it by the name.
+#[derive(Debug, Clone)]
+pub struct PathPattern {
+    segments: Vec<Segment>,
+}
+
+impl PathPattern {
+    /// Parses a pattern.
+    pub fn new(pattern: &str) -> Self {
+        let segments = pattern
+            .trim_matches('/')
+            .split('/')
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(name) => Segment::Named(name.to_string()),
+                None => Segment::Exact(segment.to_string()),
+            })
+            .collect();
+        Self { segments }
+    }
+
+    /// Matches the `path` and returns captured segments if it matched.
+    pub fn captures<'a>(&'a self, path: &'a str) -> Option<Vec<(&'a str, &'a str)>> {
+        let mut parts = path.trim_matches('/').split('/');
+        let mut captures = Vec::new();
+        for segment in &self.segments {
+            let part = parts.next()?;
+            match segment {
+                Segment::Exact(value) => {
+                    if value != part {
+                        return None;
+                    }
+                }
+                Segment::Named(name) => {
+                    if part.is_empty() {
+                        return None;
+                    }
+                    captures.push((name.as_str(), part));
+                }
+            }
+        }
+        if parts.next().is_some() {
+            None
+        } else {
+            Some(captures)
+        }
+    }
+}
+
+/// Extracts named segments of a path into a value of type `T`.
+///
+/// Use it with `WebRoute` to handle paths like `/users/:id`.
+pub struct PathParams<T> {
+    pattern: PathPattern,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> PathParams<T> {
+    /// Creates an extractor for the `pattern`.
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: PathPattern::new(pattern),
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<T> FromRequest for PathParams<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    type Output = T;
+
+    fn from_request(&self, request: &Request<Body>) -> Option<Result<Self::Output, Error>> {
+        let path = request.uri().path();
+        let captures = self.pattern.captures(path)?;
+        let query = captures
+            .into_iter()
+            .map(|(name, value)| format!("{}={}", name, escape(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let output =
+            serde_qs::from_str(&query).map_err(|err| RouteError::new(path, &query, err).into());
+        Some(output)
+    }
+}
+
+/// Escapes characters that have a special meaning in a query string.
+///
+/// Segments of a path are percent-encoded already.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("%26"),
+            '=' => escaped.push_str("%3D"),
+            '+' => escaped.push_str("%2B"),
+            '[' => escaped.push_str("%5B"),
+            ']' => escaped.push_str("%5D"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,47 @@
+//! Mapping of route errors to responses.
+
+use anyhow::Error;
+use hyper::{Body, Response};
+use std::marker::PhantomData;
+
+/// A boxed error handler alias to hold them in the `RoutingTable`.
+pub type BoxedErrorHandler = Box<dyn ErrorHandler>;
+
+/// Converts errors of routes into responses.
+///
+/// Errors that are not mapped by any handler are reported as
+/// the status of `HttpError` or `500 Internal Server Error`.
+pub trait ErrorHandler: Send + Sync + 'static {
+    /// Returns a response for the error or `None` to pass it to the next handler.
+    fn on_error(&self, error: &Error) -> Option<Response<Body>>;
+}
+
+/// Maps errors of the type `E` to responses with the function.
+pub struct MapError<E, F> {
+    func: F,
+    _error: PhantomData<fn(E)>,
+}
+
+impl<E, F> MapError<E, F>
+where
+    E: std::error::Error + Send + Sync + 'static,
+    F: Fn(&E) -> Response<Body> + Send + Sync + 'static,
+{
+    /// Creates a handler for errors of the type `E`.
+    pub fn new(func: F) -> Self {
+        Self {
+            func,
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<E, F> ErrorHandler for MapError<E, F>
+where
+    E: std::error::Error + Send + Sync + 'static,
+    F: Fn(&E) -> Response<Body> + Send + Sync + 'static,
+{
+    fn on_error(&self, error: &Error) -> Option<Response<Body>> {
+        error.downcast_ref::<E>().map(&self.func)
+    }
+}
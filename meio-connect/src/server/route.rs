@@ -1,9 +1,11 @@
 //! Contains basic routing capabilities.
 
+use super::middleware::{BoxedMiddleware, Middleware};
+use super::recover::{BoxedErrorHandler, ErrorHandler};
 use anyhow::Error;
-use hyper::{Body, Request, Response};
+use hyper::{Body, Request, Response, StatusCode};
 use meio::handlers::Interact;
-use meio::prelude::{ActionHandler, Actor, Address, Interaction};
+use meio::prelude::{ActionHandler, Actor, Address, InteractError, Interaction};
 use serde::{de::DeserializeOwned, Deserialize};
 use slab::Slab;
 use std::future::Future;
@@ -17,18 +19,70 @@ use tokio::sync::RwLock;
 /// A boxed route alias to hold them in the `RoutingTable`.
 pub type BoxedRoute = Box<dyn Route>;
 
+/// Routes in the matching order.
+///
+/// Keys of the slab identify routes in stats.
+#[derive(Default)]
+pub(super) struct Routes {
+    slab: Slab<BoxedRoute>,
+    order: Vec<usize>,
+    /// The number of routes at the head of the `order` that
+    /// are matched before routes added by a user.
+    prior: usize,
+}
+
+impl Routes {
+    /// Iterates over routes and their keys in the matching order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &BoxedRoute)> {
+        self.order.iter().map(move |idx| (*idx, &self.slab[*idx]))
+    }
+}
+
 #[derive(Clone, Default)]
 pub(super) struct RoutingTable {
-    routes: Arc<RwLock<Slab<BoxedRoute>>>,
+    routes: Arc<RwLock<Routes>>,
+    middlewares: Arc<RwLock<Vec<Arc<dyn Middleware>>>>,
+    error_handlers: Arc<RwLock<Vec<Arc<dyn ErrorHandler>>>>,
 }
 
 impl RoutingTable {
     pub async fn insert_route(&mut self, route: BoxedRoute) {
         let mut routes = self.routes.write().await;
-        routes.insert(route);
+        let idx = routes.slab.insert(route);
+        routes.order.push(idx);
+    }
+
+    /// Inserts the route that is matched before all routes added with
+    /// `insert_route`, but after prior routes inserted earlier.
+    pub async fn insert_prior_route(&mut self, route: BoxedRoute) {
+        let mut routes = self.routes.write().await;
+        let idx = routes.slab.insert(route);
+        let pos = routes.prior;
+        routes.order.insert(pos, idx);
+        routes.prior += 1;
     }
 
-    pub async fn routes(&self) -> impl Deref<Target = Slab<BoxedRoute>> + '_ {
+    pub async fn insert_middleware(&mut self, middleware: BoxedMiddleware) {
+        let mut middlewares = self.middlewares.write().await;
+        middlewares.push(Arc::from(middleware));
+    }
+
+    /// Returns middlewares in the registration order.
+    pub async fn middlewares(&self) -> Vec<Arc<dyn Middleware>> {
+        self.middlewares.read().await.clone()
+    }
+
+    pub async fn insert_error_handler(&mut self, handler: BoxedErrorHandler) {
+        let mut error_handlers = self.error_handlers.write().await;
+        error_handlers.push(Arc::from(handler));
+    }
+
+    /// Returns error handlers in the registration order.
+    pub async fn error_handlers(&self) -> Vec<Arc<dyn ErrorHandler>> {
+        self.error_handlers.read().await.clone()
+    }
+
+    pub async fn routes(&self) -> impl Deref<Target = Routes> + '_ {
         self.routes.read().await
     }
 }
@@ -61,6 +115,33 @@ impl RouteError {
     }
 }
 
+/// Error that has to be reported to a client with the specific status code.
+///
+/// Any other error of a route is reported as `500 Internal Server Error`.
+#[derive(Debug, Error)]
+#[error("{status}: {reason}")]
+pub struct HttpError {
+    /// The status of the response.
+    pub status: StatusCode,
+    /// The reason that will be sent to a client.
+    pub reason: String,
+}
+
+impl HttpError {
+    /// Creates a new error with the `status`.
+    pub fn new(status: StatusCode, reason: impl ToString) -> Self {
+        Self {
+            status,
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Creates `400 Bad Request` error.
+    pub fn bad_request(reason: impl ToString) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, reason)
+    }
+}
+
 /// The special handler for plain routes.
 pub trait DirectPath: Sized + Send + Sync + 'static {
     /// Extracted value from the request.
@@ -100,11 +181,22 @@ pub trait FromRequest: Sized + Send + Sync + 'static {
     type Output: Send;
 
     /// Matches and tries to extract data for the corresponding route.
+    #[allow(clippy::wrong_self_convention)]
     fn from_request(&self, request: &Request<Body>) -> Option<Result<Self::Output, Error>>;
 }
 
+/// The type of data extracted from a request by a `Route`.
+pub trait RequestData: Send + Sync + 'static {
+    /// The extracted value.
+    type Output: Send;
+}
+
+impl<T: FromRequest> RequestData for T {
+    type Output = <T as FromRequest>::Output;
+}
+
 /// The request wrapper.
-pub struct Req<T: FromRequest> {
+pub struct Req<T: RequestData> {
     /// Address of a client.
     pub addr: SocketAddr,
     /// Parsed request value.
@@ -113,7 +205,7 @@ pub struct Req<T: FromRequest> {
     pub body: Body,
 }
 
-impl<T: FromRequest> Interaction for Req<T> {
+impl<T: RequestData> Interaction for Req<T> {
     type Output = Response<Body>;
 }
 
@@ -128,6 +220,7 @@ pub trait Route: Send + Sync + 'static {
     /// Tries to process a request and produce a response for it.
     ///
     /// Returns a `Future` tha tproduces a response.
+    #[allow(clippy::result_large_err)]
     fn try_route(&self, addr: &SocketAddr, request: Request<Body>) -> RouteResult;
 }
 
@@ -169,7 +262,8 @@ where
                     data,
                     body: request.into_body(),
                 };
-                let fut = self.address.interact(msg).recv();
+                let task = self.address.interact(msg);
+                let fut = async move { task.recv().await.map_err(InteractError::into_error) };
                 Ok(Box::pin(fut))
             }
             None => Err(request),
@@ -1,7 +1,16 @@
-use super::{bind::AddrReady, route::RoutingTable, HttpServer};
+#[cfg(feature = "tls")]
+use super::tls::{self, rustls::ServerConfig};
+use super::{
+    bind::{AddrReady, Listener, SocketConfig},
+    route::{HttpError, RoutingTable},
+    stats::StatsCollector,
+    HttpServer,
+};
 use anyhow::Error;
 use async_trait::async_trait;
 use futures::future::{self, Either, FutureExt};
+#[cfg(any(feature = "tls", unix))]
+use hyper::server::accept;
 use hyper::server::conn::AddrStream;
 use hyper::service::Service;
 use hyper::{Body, Request, Response, Server, StatusCode};
@@ -14,28 +23,60 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{self, Poll};
 use std::time::{Duration, Instant};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::watch;
 use tokio::time::sleep;
 
 impl HttpServer {
-    pub(super) fn start_http_listener(&mut self, ctx: &mut Context<Self>) {
+    /// Starts serving with the bound `listener` or binds it in the task.
+    pub(super) fn start_http_listener(
+        &mut self,
+        listener: Option<Listener>,
+        ctx: &mut Context<Self>,
+    ) {
         let log_target = Arc::new(format!("{}::HyperRoutine", self.log_target));
         let server_task = HyperRoutine {
             log_target,
             owner: ctx.address().clone(),
-            addr: self.addr,
+            socket: self.socket.clone(),
+            listener,
             routing_table: self.routing_table.clone(),
+            drain_timeout: self.drain_timeout,
+            stats: self.stats.clone(),
+            #[cfg(feature = "tls")]
+            tls: self.tls.clone(),
         };
         ctx.spawn_task(server_task, (), ());
     }
+
+    /// Schedules the next attempt to start the listener.
+    ///
+    /// Returns `false` if retries are not enabled.
+    pub(super) fn schedule_restart(&self, ctx: &mut Context<Self>) -> Result<bool, Error> {
+        if let Some(interval) = self.retry_interval {
+            let when = Instant::now() + Duration::from_secs(interval);
+            log::debug!(target: &self.log_target, "Schedule restarting at {:?}", when);
+            ctx.address().schedule(RestartListener, when)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
 }
 
-const SHUTDOWN_TIMEOUT_SEC: u64 = 5;
+pub(super) const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub(super) struct HyperRoutine {
     log_target: Arc<String>,
     owner: Address<HttpServer>,
-    addr: SocketAddr,
+    socket: SocketConfig,
+    listener: Option<Listener>,
     routing_table: RoutingTable,
+    drain_timeout: Duration,
+    stats: Arc<StatsCollector>,
+    #[cfg(feature = "tls")]
+    tls: Option<Arc<ServerConfig>>,
 }
 
 #[async_trait]
@@ -52,27 +93,139 @@ impl LiteTask for HyperRoutine {
         let make_svc = MakeSvc {
             log_target,
             routing_table,
+            stats: self.stats.clone(),
+        };
+        let listener = match self.listener.take() {
+            Some(listener) => listener,
+            None => self.socket.bind()?,
         };
-        let server = Server::try_bind(&self.addr)?.serve(make_svc);
-        let addr = server.local_addr();
+        let (abort_tx, abort_rx) = watch::channel(false);
+        let exec = DrainExec { abort: abort_rx };
+        let incoming = match listener {
+            Listener::Tcp(incoming) => incoming,
+            #[cfg(unix)]
+            Listener::Uds(listener) => {
+                return self
+                    .serve_uds(listener, make_svc, exec, stop, abort_tx)
+                    .await;
+            }
+        };
+        let addr = incoming.local_addr();
         let ready = AddrReady::from(addr);
         self.owner.act(ready)?;
+        let shutdown = stop.clone().into_future();
+        #[cfg(feature = "tls")]
+        if let Some(config) = self.tls.clone() {
+            let incoming = tls::incoming(incoming, config, self.log_target.clone());
+            let server = Server::builder(accept::from_stream(incoming))
+                .executor(exec)
+                .serve(make_svc)
+                .with_graceful_shutdown(shutdown);
+            return self.drain(server, stop, abort_tx).await;
+        }
+        let server = Server::builder(incoming)
+            .executor(exec)
+            .serve(make_svc)
+            .with_graceful_shutdown(shutdown);
+        self.drain(server, stop, abort_tx).await
+    }
+}
+
+impl HyperRoutine {
+    /// Serves connections of a Unix domain socket and removes
+    /// the socket file when the server is finished.
+    #[cfg(unix)]
+    async fn serve_uds(
+        &self,
+        listener: UnixListener,
+        make_svc: MakeSvc,
+        exec: DrainExec,
+        stop: StopReceiver,
+        abort: watch::Sender<bool>,
+    ) -> Result<(), Error> {
+        let incoming = futures::stream::poll_fn(move |cx| {
+            listener
+                .poll_accept(cx)
+                .map(|res| Some(res.map(|(stream, _)| stream)))
+        });
+        let shutdown = stop.clone().into_future();
+        let server = Server::builder(accept::from_stream(incoming))
+            .executor(exec)
+            .serve(make_svc)
+            .with_graceful_shutdown(shutdown);
+        let res = self.drain(server, stop, abort).await;
+        if let Some(path) = &self.socket.uds {
+            if let Err(err) = std::fs::remove_file(path) {
+                log::warn!(target: &self.log_target, "Can't remove the socket file {}: {}", path.display(), err);
+            }
+        }
+        res
+    }
+
+    /// Stops accepting new connections on the signal and
+    /// waits for in-flight requests no longer than `drain_timeout`.
+    ///
+    /// Connections that were not drained in time are aborted with the `abort` signal.
+    async fn drain<F>(
+        &self,
+        server: F,
+        stop: StopReceiver,
+        abort: watch::Sender<bool>,
+    ) -> Result<(), Error>
+    where
+        F: Future<Output = Result<(), hyper::Error>>,
+    {
+        let drain_timeout = self.drain_timeout;
         let delayed_stop = stop
-            .clone()
             .into_future()
             .map(drop)
-            .then(|_| sleep(Duration::from_secs(SHUTDOWN_TIMEOUT_SEC)));
-        let server_fut = server.with_graceful_shutdown(stop.into_future());
+            .then(move |_| sleep(drain_timeout));
         tokio::pin!(delayed_stop);
-        let res = future::select(delayed_stop, server_fut).await;
+        tokio::pin!(server);
+        let res = future::select(delayed_stop, server).await;
         if let Either::Right((result, _)) = res {
             result.map_err(Error::from)
         } else {
+            log::warn!(target: &self.log_target, "Connections were not drained in time");
+            abort.send(true).ok();
             Ok(())
         }
     }
 }
 
+/// Spawns connections of the server that can be aborted
+/// if they were not drained in time.
+///
+/// Connections are separate tasks, that's why they are not
+/// dropped together with the server.
+#[derive(Clone)]
+struct DrainExec {
+    abort: watch::Receiver<bool>,
+}
+
+impl<F> hyper::rt::Executor<F> for DrainExec
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    fn execute(&self, fut: F) {
+        let aborted = aborted(self.abort.clone());
+        tokio::spawn(async move {
+            tokio::pin!(fut, aborted);
+            future::select(fut, aborted).await;
+        });
+    }
+}
+
+/// Resolves when the abort signal is sent.
+async fn aborted(mut abort: watch::Receiver<bool>) {
+    while !*abort.borrow_and_update() {
+        if abort.changed().await.is_err() {
+            // The server finished without aborting connections.
+            future::pending::<()>().await;
+        }
+    }
+}
+
 #[async_trait]
 impl TaskEliminated<HyperRoutine, ()> for HttpServer {
     async fn handle(
@@ -85,11 +238,7 @@ impl TaskEliminated<HyperRoutine, ()> for HttpServer {
         if !ctx.is_terminating() {
             if let Err(err) = result {
                 log::error!(target: &self.log_target, "Server failed: {}", err);
-                if let Some(interval) = self.retry_interval {
-                    let when = Instant::now() + Duration::from_secs(interval);
-                    log::debug!(target: &self.log_target, "Schedule restarting at {:?}", when);
-                    ctx.address().schedule(RestartListener, when)?;
-                }
+                self.schedule_restart(ctx)?;
             }
         }
         Ok(())
@@ -107,7 +256,7 @@ impl Scheduled<RestartListener> for HttpServer {
         ctx: &mut Context<Self>,
     ) -> Result<(), Error> {
         log::info!(target: &self.log_target, "Attempt to restart the server");
-        self.start_http_listener(ctx);
+        self.start_http_listener(None, ctx);
         Ok(())
     }
 }
@@ -116,6 +265,13 @@ struct Svc {
     log_target: Arc<String>,
     addr: SocketAddr,
     routing_table: RoutingTable,
+    stats: Arc<StatsCollector>,
+}
+
+impl Drop for Svc {
+    fn drop(&mut self) {
+        self.stats.connection_closed();
+    }
 }
 
 pub type SvcFut<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send>>;
@@ -129,63 +285,143 @@ impl Service<Request<Body>> for Svc {
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, req: Request<Body>) -> Self::Future {
-        let uri = req.uri().to_owned();
-        log::trace!(target: &self.log_target, "Incoming request path: {}", uri.path());
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        log::trace!(target: &self.log_target, "Incoming request path: {}", req.uri().path());
+        self.stats.request_received();
         let routing_table = self.routing_table.clone();
+        let stats = self.stats.clone();
         let addr = self.addr;
         let log_target = self.log_target.clone();
         let fut = async move {
-            let mut route = None;
-            {
-                let routes = routing_table.routes().await;
-                let mut opt_req = Some(req);
-                for (_idx, r) in routes.iter() {
-                    if let Some(req) = opt_req.take() {
-                        let res = r.try_route(&addr, req);
-                        match res {
-                            Ok(r) => {
-                                route = Some(r);
-                                break;
-                            }
-                            Err(req) => {
-                                opt_req = Some(req);
-                            }
-                        }
+            let middlewares = routing_table.middlewares().await;
+            let mut short_circuit = None;
+            for middleware in &middlewares {
+                if let Some(response) = middleware.on_request(&addr, &mut req) {
+                    short_circuit = Some(response);
+                    break;
+                }
+            }
+            let mut response = match short_circuit {
+                Some(response) => response,
+                None => dispatch(&routing_table, &stats, &addr, req, &log_target).await,
+            };
+            for middleware in &middlewares {
+                middleware.on_response(&mut response);
+            }
+            Ok(response)
+        };
+        Box::pin(fut)
+    }
+}
+
+/// Finds a route for the request and waits for the response.
+async fn dispatch(
+    routing_table: &RoutingTable,
+    stats: &StatsCollector,
+    addr: &SocketAddr,
+    req: Request<Body>,
+    log_target: &str,
+) -> Response<Body> {
+    let uri = req.uri().to_owned();
+    let mut route = None;
+    {
+        let routes = routing_table.routes().await;
+        let mut opt_req = Some(req);
+        for (idx, r) in routes.iter() {
+            if let Some(req) = opt_req.take() {
+                let res = r.try_route(addr, req);
+                match res {
+                    Ok(r) => {
+                        stats.route_matched(idx);
+                        route = Some(r);
+                        break;
+                    }
+                    Err(req) => {
+                        opt_req = Some(req);
                     }
                 }
             }
-            let mut response;
-            if let Some(route) = route {
-                let resp = route.await;
-                match resp {
-                    Ok(resp) => {
-                        response = resp;
+        }
+    }
+    let mut response;
+    if let Some(route) = route {
+        let resp = route.await;
+        match resp {
+            Ok(resp) => {
+                response = resp;
+            }
+            Err(err) => match map_error(routing_table, err).await {
+                Ok(resp) => {
+                    response = resp;
+                }
+                Err(err) => match err.downcast::<HttpError>() {
+                    Ok(err) => {
+                        log::debug!(target: log_target, "Request {} failed: {}", uri, err);
+                        response = Response::new(err.reason.into());
+                        *response.status_mut() = err.status;
                     }
                     Err(err) => {
-                        log::error!(target: &log_target, "Server error for {}: {}", uri, err);
+                        log::error!(target: log_target, "Server error for {}: {}", uri, err);
                         let reason: Body = err.to_string().into();
                         response = Response::new(reason);
                         *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
                     }
-                }
-            } else {
-                log::warn!(target: &log_target, "No route for {}", uri);
-                response = Response::new(Body::empty());
-                *response.status_mut() = StatusCode::NOT_FOUND;
-            }
-            Ok(response)
-        };
-        Box::pin(fut)
+                },
+            },
+        }
+    } else {
+        stats.route_not_found();
+        log::warn!(target: log_target, "No route for {}", uri);
+        response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NOT_FOUND;
     }
+    response
+}
+
+/// Converts the error into a response with registered handlers
+/// or returns it back if no one handler mapped it.
+async fn map_error(routing_table: &RoutingTable, err: Error) -> Result<Response<Body>, Error> {
+    let handlers = routing_table.error_handlers().await;
+    handlers
+        .iter()
+        .find_map(|handler| handler.on_error(&err))
+        .ok_or(err)
 }
 
 struct MakeSvc {
     log_target: Arc<String>,
     routing_table: RoutingTable,
+    stats: Arc<StatsCollector>,
+}
+
+/// A connection that knows the address of a client.
+trait RemoteAddr {
+    fn remote_addr(&self) -> SocketAddr;
 }
 
-impl<'a> Service<&'a AddrStream> for MakeSvc {
+impl RemoteAddr for AddrStream {
+    fn remote_addr(&self) -> SocketAddr {
+        AddrStream::remote_addr(self)
+    }
+}
+
+/// Peers of a Unix domain socket have no IP address,
+/// that's why routes get the unspecified one.
+#[cfg(unix)]
+impl RemoteAddr for UnixStream {
+    fn remote_addr(&self) -> SocketAddr {
+        SocketAddr::from(([0, 0, 0, 0], 0))
+    }
+}
+
+#[cfg(feature = "tls")]
+impl RemoteAddr for tokio_rustls::server::TlsStream<AddrStream> {
+    fn remote_addr(&self) -> SocketAddr {
+        self.get_ref().0.remote_addr()
+    }
+}
+
+impl<'a, C: RemoteAddr> Service<&'a C> for MakeSvc {
     type Response = Svc;
     type Error = hyper::Error;
     type Future = SvcFut<Self::Response, Self::Error>;
@@ -194,17 +430,19 @@ impl<'a> Service<&'a AddrStream> for MakeSvc {
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, addr_stream: &'a AddrStream) -> Self::Future {
+    fn call(&mut self, addr_stream: &'a C) -> Self::Future {
         let routing_table = self.routing_table.clone();
         let log_target = self.log_target.clone();
         let addr = addr_stream.remote_addr();
-        let fut = async move {
-            Ok(Svc {
-                log_target,
-                addr,
-                routing_table,
-            })
+        let stats = self.stats.clone();
+        stats.connection_opened();
+        let svc = Svc {
+            log_target,
+            addr,
+            routing_table,
+            stats,
         };
+        let fut = async move { Ok(svc) };
         Box::pin(fut)
     }
 }
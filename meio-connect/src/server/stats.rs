@@ -0,0 +1,96 @@
+//! Counters of connections and requests of a server.
+
+use super::{HttpServer, HttpServerLink};
+use anyhow::Error;
+use async_trait::async_trait;
+use meio::prelude::{Context, Interaction, InteractionHandler, InteractionTask};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// The snapshot of server's counters.
+#[derive(Debug, Clone, Default)]
+pub struct HttpStats {
+    /// The number of currently open connections.
+    pub active_connections: usize,
+    /// The number of requests received since the start.
+    pub requests: u64,
+    /// The number of requests that matched no route.
+    pub not_found: u64,
+    /// Hits of routes by the order they were added.
+    pub route_hits: BTreeMap<usize, u64>,
+}
+
+/// Shared counters updated by the routine of a server.
+#[derive(Debug, Default)]
+pub(super) struct StatsCollector {
+    active_connections: AtomicUsize,
+    requests: AtomicU64,
+    not_found: AtomicU64,
+    route_hits: Mutex<BTreeMap<usize, u64>>,
+}
+
+impl StatsCollector {
+    pub fn connection_opened(&self) {
+        self.active_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn connection_closed(&self) {
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn request_received(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn route_matched(&self, idx: usize) {
+        let mut route_hits = self
+            .route_hits
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        *route_hits.entry(idx).or_default() += 1;
+    }
+
+    pub fn route_not_found(&self) {
+        self.not_found.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> HttpStats {
+        let route_hits = self
+            .route_hits
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        HttpStats {
+            active_connections: self.active_connections.load(Ordering::Relaxed),
+            requests: self.requests.load(Ordering::Relaxed),
+            not_found: self.not_found.load(Ordering::Relaxed),
+            route_hits,
+        }
+    }
+}
+
+/// The interaction to get the snapshot of server's counters.
+pub struct GetStats;
+
+impl Interaction for GetStats {
+    type Output = HttpStats;
+}
+
+impl HttpServerLink {
+    /// Requests the snapshot of server's counters.
+    pub fn stats(&self) -> InteractionTask<GetStats> {
+        self.address.interact(GetStats)
+    }
+}
+
+#[async_trait]
+impl InteractionHandler<GetStats> for HttpServer {
+    async fn handle(
+        &mut self,
+        _msg: GetStats,
+        _ctx: &mut Context<Self>,
+    ) -> Result<HttpStats, Error> {
+        Ok(self.stats.snapshot())
+    }
+}
@@ -27,6 +27,7 @@ pub trait WsFromRequest: Sized + Send + Sync + 'static {
     ///
     /// If the request didn't match: returns `None`. If the request matched returns it.
     /// If parsing and or extracting failed `Some(Err(_))` returned.
+    #[allow(clippy::wrong_self_convention)]
     fn from_request(&self, request: &Request<Body>) -> Option<Result<Self::Output, Error>>;
 }
 
@@ -53,6 +54,11 @@ where
 }
 
 /// Incoming WebSocket connection request.
+///
+/// To talk with a client spawn the `WsProcessor` created by the `stream`
+/// as a task and keep the `WsSender` to push messages to the client.
+/// When the client disconnects the sender becomes closed and the actor
+/// receives `TaskEliminated` for the processor with a `TermReason`.
 pub struct WsReq<T: WsFromRequest> {
     /// The request's meta data.
     pub request: T::Output,
@@ -62,6 +68,13 @@ pub struct WsReq<T: WsFromRequest> {
     pub stream: WsHandler<T::Protocol>,
 }
 
+impl<T: WsFromRequest> WsReq<T> {
+    /// Returns a sender for outgoing messages of the connection.
+    pub fn sender(&self) -> WsSender<<T::Protocol as Protocol>::ToClient> {
+        self.stream.sender()
+    }
+}
+
 impl<T: WsFromRequest> Action for WsReq<T> {}
 
 /// The route for a web socket connection.
@@ -22,6 +22,7 @@ pub trait CustomActionHandler<I: CustomAction>: Actor {
 /// It wraps a `CustomAction` and implements a `Handler` to
 /// `take` the value from the inner `Option` and call the
 /// specific handler trait.
+#[allow(dead_code)]
 struct CustomActionHandlerImpl<I> {
     input: Option<I>,
 }
@@ -38,22 +38,53 @@
 //! }
 //! ```
 
-use crate::forwarders::StreamForwarder;
+use crate::compat::watch;
+use crate::dead_letters;
+use crate::dedupe::{DedupeCache, DedupeWindow};
+#[cfg(feature = "durable")]
+use crate::durable::DurableMailbox;
+use crate::forwarders::{ActionForwarder, StreamForwarder};
 use crate::handlers::{
-    Consumer, Eliminated, Envelope, Interaction, InteractionDone, InteractionTask, InterruptedBy,
-    Operation, StartedBy, TaskEliminated,
+    Action, ActionHandler, Consumer, Eliminated, Envelope, Interaction, InteractionDone,
+    InteractionTask, InterruptedBy, Operation, ScheduleHandle, Scheduled, StartedBy, StreamEnded,
+    TaskEliminated,
 };
 use crate::ids::{Id, IdOf};
-use crate::lifecycle::{Awake, Done, LifecycleNotifier, LifetimeTracker};
-use crate::linkage::{Address, AddressJoint, AddressPair};
+use crate::lifecycle::{
+    Awake, ChildInfo, Done, InterruptReason, LifecycleStage, LifetimeTracker, Supervisor,
+    TerminationReason,
+};
+use crate::linkage::{
+    join_all, Address, AddressJoint, AddressPair, Cancelled, MailboxKind, SendError,
+};
 use crate::lite_runtime::{self, LiteTask, Tag, TaskAddress};
+use crate::tasks::detached::Detached;
+use crate::tasks::interval::{IntervalHandle, IntervalTask};
+use crate::tasks::one_shot::OneShotTask;
+use crate::tasks::race::{Race, RaceTag, Racer, TaskCompleted};
+use crate::tasks::retry::Backoff;
+use crate::tasks::supervised::Supervised;
 use anyhow::Error;
 use async_trait::async_trait;
+use futures::channel::oneshot;
+use futures::future::{self, BoxFuture, Either, Fuse};
 use futures::stream::{pending, FusedStream};
-use futures::{select_biased, FutureExt, Stream, StreamExt};
+use futures::{select_biased, Future, FutureExt, Stream, StreamExt};
+use std::collections::VecDeque;
 use std::hash::Hash;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+/// The default number of handled messages after which
+/// the runtime of an `Actor` yields to the scheduler.
+///
+/// A yield after every message makes a busy loop over a queue ~20 times slower,
+/// after every 256 messages it costs ~10% of an empty handler and nothing
+/// noticeable for handlers that do real work.
+pub const DEFAULT_YIELD_INTERVAL: usize = 256;
+
 #[derive(Debug, Error)]
 enum Reason {
     #[error("Actor is terminating...")]
@@ -82,7 +113,148 @@ pub trait Actor: Sized + Send + 'static {
     type GroupBy: TerminationSequence + Clone + Send + Eq + Hash;
 
     /// The log target for the `Actor`.
-    fn log_target(&self) -> &str;
+    ///
+    /// The runtime logs messages about the `Actor` with this target, that's
+    /// why `RUST_LOG` can enable a level for a single type of actors.
+    /// It's the name of the type by default.
+    fn log_target(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// The capacity of the ordinary priority queue (mailbox).
+    ///
+    /// The mailbox is unbounded if `None` returned. The zero capacity
+    /// makes it a rendezvous mailbox (`MailboxKind::Rendezvous`).
+    /// High-priority messages don't depend on this value.
+    fn mailbox_capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// The kind of the ordinary priority queue (mailbox).
+    ///
+    /// By default it's bounded if `mailbox_capacity` is set.
+    /// High-priority messages stay non-blocking for every kind.
+    fn mailbox_kind(&self) -> MailboxKind {
+        self.mailbox_capacity().into()
+    }
+
+    /// The kind of the mailbox of an `Actor` created by a future
+    /// with `Context::spawn_created` or `System::spawn_with`.
+    ///
+    /// The instance doesn't exist when the mailbox is created, that's why
+    /// `mailbox_kind` can't be used. Other settings are applied when the
+    /// `Actor` is created.
+    fn created_mailbox_kind() -> MailboxKind
+    where
+        Self: Sized,
+    {
+        MailboxKind::Unbounded
+    }
+
+    /// The maximal number of queued high-priority messages sent with
+    /// `instant`, `act_hp`, `schedule` and similar methods.
+    ///
+    /// All these methods return `Err(SendError::Full)` if the limit is reached.
+    /// Service messages of the framework (interruptions, notifications about
+    /// finished children) are not limited to never block the shutdown, but
+    /// the capacity should be generous anyway. Unbounded if `None` returned.
+    fn hp_capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// If `true` the `Actor` starts processing messages even if
+    /// its `StartedBy` handler failed.
+    ///
+    /// By default the failed `Actor` is terminated immediately (or restarted
+    /// according to its `RestartPolicy`) and the supervisor is notified with
+    /// the `Eliminated` event.
+    fn ignore_start_errors(&self) -> bool {
+        false
+    }
+
+    /// The maximal number of consecutive high-priority messages handled
+    /// before a waiting ordinary message gets its turn.
+    ///
+    /// By default high-priority messages are always handled first and
+    /// a flood of them can starve ordinary messages.
+    fn hp_burst_limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// The number of consecutive handled messages after which the `Actor`
+    /// yields to let other tasks of the same thread run.
+    ///
+    /// An `Actor` with a mailbox that is never empty doesn't give control
+    /// back to the runtime otherwise. Smaller values make scheduling fairer
+    /// and throughput lower. `None` disables yielding.
+    fn yield_interval(&self) -> Option<usize> {
+        Some(DEFAULT_YIELD_INTERVAL)
+    }
+
+    /// The duration of handling a single message that is considered slow.
+    ///
+    /// Slow handlers block the loop of the `Actor` and are logged as warnings
+    /// with the type of the message. Handlers are not timed if `None` returned.
+    fn slow_handler_threshold(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The duration without any handled messages (ordinary, high-priority
+    /// or scheduled) after which `idle_timed_out` is called.
+    ///
+    /// Disabled if `None` returned.
+    fn idle_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The window of recent actions to skip duplicates by `Action::dedupe_key`.
+    ///
+    /// Deduplication is disabled if `None` returned.
+    fn dedupe_window(&self) -> Option<DedupeWindow> {
+        None
+    }
+
+    /// The write-ahead log for actions sent by `Address::act_durable`.
+    ///
+    /// It's read once when the `Actor` is spawned.
+    #[cfg(feature = "durable")]
+    fn durable_mailbox(&self) -> Option<DurableMailbox> {
+        None
+    }
+
+    /// If `true` a panic in a handler won't unwind the runtime of the `Actor`.
+    ///
+    /// The panic will be logged as an error and the `Actor` will be shut down
+    /// to notify the supervisor with the `Eliminated` event.
+    fn catch_panics(&self) -> bool {
+        false
+    }
+
+    /// If `true` the runtime panics when the `Done` notification can't be
+    /// delivered to the supervisor instead of logging the error.
+    ///
+    /// The delivery fails only if the supervisor has terminated (or was killed)
+    /// before the `Actor`. The panic happens after joiners are released.
+    fn panic_on_lost_done(&self) -> bool {
+        false
+    }
+
+    /// Called when the `Actor` finished processing messages and all its
+    /// children were eliminated, but before the supervisor will be notified.
+    ///
+    /// The last chance to perform asynchronous cleanup.
+    async fn finalize(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when the `Actor` was idle for `idle_timeout`.
+    ///
+    /// Shuts the `Actor` down by default. If the `Actor` keeps running
+    /// it's called again after the next period of inactivity.
+    async fn idle_timed_out(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ctx.shutdown();
+        Ok(())
+    }
 
     #[doc(hidden)] // Not ready yet
     /// Called when `Action` queue drained (no more messages will be sent).
@@ -113,6 +285,98 @@ impl Status {
     }
 }
 
+/// The lifecycle stage of an `Actor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ActorStatus {
+    /// The `Actor` hasn't finished its `StartedBy` handler yet.
+    Awake,
+    /// The `Actor` is processing messages.
+    Running,
+    /// The `Actor` is waiting for termination of its children.
+    Terminating,
+    /// The `Actor` had finished.
+    Done,
+}
+
+impl ActorStatus {
+    /// Is the `Actor` finished yet?
+    pub fn is_done(&self) -> bool {
+        *self == ActorStatus::Done
+    }
+}
+
+/// The strategy of restarting a failed `Actor`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Never restart the `Actor`.
+    #[default]
+    Never,
+    /// Restart the failed `Actor` no more than `max_retries` times.
+    ///
+    /// The `Actor` is failed if:
+    /// - its `StartedBy` handler returned an error;
+    /// - a handler panicked and `Actor::catch_panics` returned `true`
+    ///   (otherwise the panic unwinds the runtime);
+    /// - a handler returned an error when the `Actor` was already finishing
+    ///   (after `ctx.shutdown()` or `ctx.stop()`). Errors of handlers called
+    ///   before that are only logged.
+    ///
+    /// The `Actor` that handled an interruption is never restarted,
+    /// even if it failed or panicked after that.
+    OnError {
+        /// How many times the `Actor` can be restarted.
+        max_retries: usize,
+    },
+}
+
+/// Creates fresh instances of a failed `Actor`.
+struct Restarter<A: Actor> {
+    factory: Box<dyn FnMut() -> A + Send>,
+    awake: fn(Option<Id>) -> Envelope<A>,
+    supervisor: Option<Id>,
+    policy: RestartPolicy,
+    backoff: Backoff,
+    retries: usize,
+}
+
+/// A fresh instance of the `Actor` that has to be started after the `delay`.
+struct Restart<A: Actor> {
+    actor: A,
+    awake: Envelope<A>,
+    delay: Duration,
+}
+
+impl<A: Actor> Restarter<A> {
+    /// Returns a new instance and its awake envelope if the policy allows that.
+    fn restart(&mut self) -> Option<Restart<A>> {
+        match self.policy {
+            RestartPolicy::Never => None,
+            RestartPolicy::OnError { max_retries } => {
+                if self.retries < max_retries {
+                    self.retries += 1;
+                    Some(Restart {
+                        actor: (self.factory)(),
+                        awake: (self.awake)(self.supervisor.clone()),
+                        delay: self.backoff.delay(self.retries),
+                    })
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+// There is `Envelope` here, because it will be processed at start and
+// will never been sent to prevent other messages come before the `Awake`.
+fn awake_envelope<A, S>(supervisor: Option<Id>) -> Envelope<A>
+where
+    A: Actor + StartedBy<S>,
+    S: Actor,
+{
+    Envelope::instant(Awake::<S>::new(supervisor))
+}
+
 /// Spawns `Actor` in `ActorRuntime`.
 // TODO: No `Option`! Use `static Address<System>` instead.
 // It can be possible when `Controller` and `Operator` will be removed.
@@ -121,46 +385,220 @@ where
     A: Actor + StartedBy<S>,
     S: Actor + Eliminated<A>,
 {
-    let AddressPair { joint, address } = address_pair;
-    let id: Id = address.id().into();
-    // There is `Envelope` here, because it will be processed at start and
-    // will never been sent to prevent other messages come before the `Awake`.
-    let awake_envelope = Envelope::instant(Awake::new());
-    let done_notifier = {
-        match supervisor {
-            None => <dyn LifecycleNotifier<_>>::ignore(),
-            Some(super_addr) => {
-                let op = Operation::Done { id };
-                <dyn LifecycleNotifier<_>>::once(super_addr, op)
+    spawn_runtime(actor, supervisor, address_pair, None, None);
+}
+
+/// Spawns `Actor` and returns a receiver of the result of its `StartedBy` handler.
+pub(crate) fn spawn_ready<A, S>(
+    actor: A,
+    supervisor: Option<Address<S>>,
+    address_pair: AddressPair<A>,
+) -> oneshot::Receiver<Result<(), Error>>
+where
+    A: Actor + StartedBy<S>,
+    S: Actor + Eliminated<A>,
+{
+    let (tx, rx) = oneshot::channel();
+    spawn_runtime(actor, supervisor, address_pair, None, Some(tx));
+    rx
+}
+
+/// Spawns `Actor` that will be recreated by the `factory` if it failed.
+pub(crate) fn spawn_with_restart<A, S, F>(
+    actor: A,
+    factory: F,
+    policy: RestartPolicy,
+    backoff: Backoff,
+    supervisor: Option<Address<S>>,
+    address_pair: AddressPair<A>,
+) where
+    A: Actor + StartedBy<S>,
+    S: Actor + Eliminated<A>,
+    F: FnMut() -> A + Send + 'static,
+{
+    let restarter = Restarter {
+        factory: Box::new(factory),
+        awake: awake_envelope::<A, S>,
+        supervisor: supervisor.as_ref().map(|address| address.id().into()),
+        policy,
+        backoff,
+        retries: 0,
+    };
+    spawn_runtime(actor, supervisor, address_pair, Some(restarter), None);
+}
+
+fn spawn_runtime<A, S>(
+    actor: A,
+    supervisor: Option<Address<S>>,
+    address_pair: AddressPair<A>,
+    restarter: Option<Restarter<A>>,
+    ready: Option<oneshot::Sender<Result<(), Error>>>,
+) where
+    A: Actor + StartedBy<S>,
+    S: Actor + Eliminated<A>,
+{
+    // Pairs can be created without the instance, like in `Context::spawn_actor_with_addr`.
+    address_pair.address().configure(&actor);
+    let supervisor_id = assign_supervisor(&address_pair, supervisor);
+    let runtime = ActorRuntime::new::<S>(actor, supervisor_id, address_pair, restarter, ready);
+    crate::compat::spawn_async(runtime.entrypoint());
+}
+
+/// Spawns the task that creates an `Actor` with the `create` future
+/// and runs it in the same task.
+///
+/// If the future failed the supervisor receives `Eliminated` with
+/// the `TerminationReason::Failed` reason like for a failed start.
+pub(crate) fn spawn_created<A, S, Fut>(
+    create: Fut,
+    supervisor: Option<Address<S>>,
+    address_pair: AddressPair<A>,
+) where
+    A: Actor + StartedBy<S>,
+    S: Actor + Eliminated<A>,
+    Fut: Future<Output = Result<A, Error>> + Send + 'static,
+{
+    let supervisor_id = assign_supervisor(&address_pair, supervisor);
+    let fut = async move {
+        match create.await {
+            Ok(actor) => {
+                address_pair.address().configure(&actor);
+                if actor.mailbox_kind() != A::created_mailbox_kind() {
+                    log::warn!(
+                        target: actor.log_target(),
+                        "The mailbox of {} was created with Actor::created_mailbox_kind",
+                        address_pair.address().id()
+                    );
+                }
+                let runtime =
+                    ActorRuntime::new::<S>(actor, supervisor_id, address_pair, None, None);
+                runtime.entrypoint().await;
+            }
+            Err(err) => {
+                let AddressPair { joint, address } = address_pair;
+                let target = std::any::type_name::<A>();
+                log::error!(target: target, "Can't create the actor {}: {}", address.id(), err);
+                let reason = TerminationReason::Failed(err.to_string());
+                let stage = LifecycleStage::Done(reason.clone());
+                let done_event = Done::new(address.id(), reason, Some(err));
+                if let Err(err) = address.supervisor_slot().finish(done_event) {
+                    log::error!(
+                        target: target,
+                        "Can't send done notification from the actor {:?}: {}",
+                        address.id(),
+                        err
+                    );
+                }
+                joint.join_tx.send_replace(ActorStatus::Done);
+                address.lifecycle_event(stage);
             }
         }
     };
-    let context = Context {
-        alive: true,
-        address: address.clone(),
-        lifetime_tracker: LifetimeTracker::new(),
-        //terminator: Terminator::new(id.clone()),
-    };
-    let runtime = ActorRuntime {
-        id: address.id(),
-        actor,
-        context,
-        awake_envelope: Some(awake_envelope),
-        done_notifier,
-        joint,
-    };
-    crate::compat::spawn_async(runtime.entrypoint());
+    crate::compat::spawn_async(fut);
+}
+
+/// Assigns the supervisor to the `Actor` and returns its `Id`.
+fn assign_supervisor<A, S>(
+    address_pair: &AddressPair<A>,
+    supervisor: Option<Address<S>>,
+) -> Option<Id>
+where
+    A: Actor,
+    S: Actor + Eliminated<A>,
+{
+    let address = address_pair.address();
+    let supervisor_id = supervisor.as_ref().map(|address| address.id().into());
+    if let Some(super_addr) = supervisor {
+        address
+            .supervisor_slot()
+            .assign(super_addr, address.id().into());
+    }
+    supervisor_id
+}
+
+impl<A: Actor> ActorRuntime<A> {
+    fn new<S>(
+        actor: A,
+        supervisor_id: Option<Id>,
+        address_pair: AddressPair<A>,
+        restarter: Option<Restarter<A>>,
+        ready: Option<oneshot::Sender<Result<(), Error>>>,
+    ) -> Self
+    where
+        A: StartedBy<S>,
+        S: Actor,
+    {
+        let AddressPair { joint, address } = address_pair;
+        let id: Id = address.id().into();
+        let awake_envelope = awake_envelope::<A, S>(supervisor_id);
+        let context = Context::new(id, address.clone(), actor.log_target());
+        let dedupe = actor.dedupe_window().map(DedupeCache::new);
+        ActorRuntime {
+            id: address.id(),
+            actor,
+            context,
+            awake_envelope: Some(awake_envelope),
+            restarter,
+            ready,
+            last_error: None,
+            dedupe,
+            joint,
+        }
+    }
 }
 
 /// `Context` of a `ActorRuntime` that contains `Address` and `Receiver`.
 pub struct Context<A: Actor> {
+    id: Id,
+    /// The log target of the `Actor` for helpers that have no access to it.
+    log_target: String,
     alive: bool,
+    draining: bool,
+    pub(crate) paused: bool,
+    pub(crate) spawned_by: Option<Id>,
     address: Address<A>,
-    lifetime_tracker: LifetimeTracker<A>,
+    pub(crate) lifetime_tracker: LifetimeTracker<A>,
+    pub(crate) termination_reason: TerminationReason,
+    /// Messages deferred by handlers.
+    stash: VecDeque<Envelope<A>>,
+    /// Deferred messages that have to be handled before the mailbox.
+    pub(crate) unstashed: VecDeque<Envelope<A>>,
     //terminator: Terminator,
 }
 
 impl<A: Actor> Context<A> {
+    pub(crate) fn new(id: Id, address: Address<A>, log_target: &str) -> Self {
+        Self {
+            id,
+            log_target: log_target.to_string(),
+            alive: true,
+            draining: false,
+            paused: false,
+            spawned_by: None,
+            address,
+            lifetime_tracker: LifetimeTracker::new(log_target),
+            termination_reason: TerminationReason::Normal,
+            stash: VecDeque::new(),
+            unstashed: VecDeque::new(),
+            //terminator: Terminator::new(id.clone()),
+        }
+    }
+
+    /// Returns `true` if the `Actor` wasn't stopped.
+    pub(crate) fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    /// Returns `Id` of the `Actor`.
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    /// Returns the log target of the `Actor`.
+    pub(crate) fn log_target(&self) -> &str {
+        &self.log_target
+    }
+
     /// Returns an instance of the `Address`.
     pub fn address(&mut self) -> &mut Address<A> {
         &mut self.address
@@ -183,12 +621,101 @@ impl<A: Actor> Context<A> {
         T: Actor + StartedBy<A> + InterruptedBy<A>,
         A: Eliminated<T>,
     {
-        let pair = AddressPair::new();
+        let pair = AddressPair::for_actor(&actor);
         let address = pair.address().clone();
         self.spawn_actor_with_addr(actor, pair, group);
         address
     }
 
+    /// Starts and binds an `Actor` created by the `create` future.
+    ///
+    /// The future is awaited in the task of the `Actor`, that's why
+    /// resources can be acquired in an `async fn create() -> Result<Self, Error>`
+    /// without `Option` fields. If it failed the `Actor` is eliminated with
+    /// `TerminationReason::Failed`. The mailbox is created with
+    /// `Actor::created_mailbox_kind`, because it's created before the `Actor`.
+    pub fn spawn_created<T, Fut>(&mut self, create: Fut, group: A::GroupBy) -> Address<T>
+    where
+        T: Actor + StartedBy<A> + InterruptedBy<A>,
+        A: Eliminated<T>,
+        Fut: Future<Output = Result<T, Error>> + Send + 'static,
+    {
+        let pair = AddressPair::for_created();
+        let address = pair.address().clone();
+        spawn_created(create, Some(self.address.clone()), pair);
+        self.lifetime_tracker.insert(address.clone(), group);
+        address
+    }
+
+    /// Starts and binds two actors that know addresses of each other.
+    ///
+    /// The `link` function creates both actors with addresses of each other,
+    /// that's why actors keep addresses without `Option` wrappers and no messages
+    /// are handled before that. Mailboxes are unbounded, because they are created
+    /// before the actors and `Actor::mailbox_kind` is ignored. Other settings
+    /// (like `Actor::hp_capacity`) are applied when `link` returned, that's why
+    /// messages sent inside `link` are not limited by them.
+    pub fn spawn_linked<T, U, F>(&mut self, link: F, group: A::GroupBy) -> (Address<T>, Address<U>)
+    where
+        T: Actor + StartedBy<A> + InterruptedBy<A>,
+        U: Actor + StartedBy<A> + InterruptedBy<A>,
+        A: Eliminated<T> + Eliminated<U>,
+        F: FnOnce(Address<T>, Address<U>) -> (T, U),
+    {
+        let first_pair = AddressPair::new();
+        let second_pair = AddressPair::new();
+        let first_address = first_pair.address().clone();
+        let second_address = second_pair.address().clone();
+        let (first, second) = link(first_address.clone(), second_address.clone());
+        first_address.configure(&first);
+        second_address.configure(&second);
+        self.spawn_actor_with_addr(first, first_pair, group.clone());
+        self.spawn_actor_with_addr(second, second_pair, group);
+        (first_address, second_address)
+    }
+
+    /// Starts and binds an `Actor` that will be restarted according to the `policy`.
+    ///
+    /// The `factory` creates a fresh instance of the `Actor` for every attempt.
+    /// The `Address` remains the same between restarts.
+    pub fn spawn_actor_with<T, F>(
+        &mut self,
+        factory: F,
+        policy: RestartPolicy,
+        group: A::GroupBy,
+    ) -> Address<T>
+    where
+        T: Actor + StartedBy<A> + InterruptedBy<A>,
+        A: Eliminated<T>,
+        F: FnMut() -> T + Send + 'static,
+    {
+        let backoff = Backoff::Fixed(Duration::ZERO);
+        self.spawn_actor_with_backoff(factory, policy, backoff, group)
+    }
+
+    /// Starts and binds an `Actor` that will be restarted according to the `policy`
+    /// after a delay of the `backoff`.
+    pub fn spawn_actor_with_backoff<T, F>(
+        &mut self,
+        mut factory: F,
+        policy: RestartPolicy,
+        backoff: Backoff,
+        group: A::GroupBy,
+    ) -> Address<T>
+    where
+        T: Actor + StartedBy<A> + InterruptedBy<A>,
+        A: Eliminated<T>,
+        F: FnMut() -> T + Send + 'static,
+    {
+        let actor = factory();
+        let pair = AddressPair::for_actor(&actor);
+        let address = pair.address().clone();
+        let supervisor = Some(self.address.clone());
+        spawn_with_restart(actor, factory, policy, backoff, supervisor, pair);
+        self.lifetime_tracker.insert(address.clone(), group);
+        address
+    }
+
     /// Starts and binds a `Task`.
     pub fn spawn_task<T, M>(&mut self, task: T, tag: M, group: A::GroupBy) -> TaskAddress<T>
     where
@@ -201,6 +728,72 @@ impl<A: Actor> Context<A> {
         stopper
     }
 
+    /// Starts and binds a task that is created by the `factory` again
+    /// after every failure while the `policy` allows that.
+    ///
+    /// Restarts are delayed with the `backoff`. `TaskEliminated` is called
+    /// only when the task succeeded, was interrupted or failed permanently.
+    pub fn spawn_task_supervised<T, F, M>(
+        &mut self,
+        factory: F,
+        policy: RestartPolicy,
+        backoff: Backoff,
+        tag: M,
+        group: A::GroupBy,
+    ) -> TaskAddress<Supervised<T>>
+    where
+        T: LiteTask,
+        F: FnMut() -> T + Send + 'static,
+        A: TaskEliminated<Supervised<T>, M>,
+        M: Tag,
+    {
+        let task = Supervised::new(factory, policy, backoff);
+        self.spawn_task(task, tag, group)
+    }
+
+    /// Runs the future without blocking the loop of the `Actor` and sends
+    /// its output back as an `Action`.
+    ///
+    /// Useful to await interactions with other actors concurrently. The future
+    /// is a child task and it's dropped if the `Actor` starts termination
+    /// before it completed, the output is not delivered in that case.
+    pub fn spawn_detached<F>(&mut self, fut: F, group: A::GroupBy) -> TaskAddress<Detached>
+    where
+        F: Future + Send + 'static,
+        F::Output: Action,
+        A: ActionHandler<F::Output>,
+    {
+        let task = Detached::new(fut, self.address.clone());
+        self.spawn_task(task, (), group)
+    }
+
+    /// Starts and binds tasks that race with each other.
+    ///
+    /// `TaskCompleted` is sent to the `Actor` when the first task finished.
+    /// The rest tasks are stopped if `cancel_rest` is `true` and their
+    /// results are ignored anyway.
+    pub fn spawn_race<T, I>(
+        &mut self,
+        tasks: I,
+        cancel_rest: bool,
+        group: A::GroupBy,
+    ) -> Vec<TaskAddress<Racer<T>>>
+    where
+        T: LiteTask,
+        I: IntoIterator<Item = T>,
+        A: ActionHandler<TaskCompleted<T>>,
+    {
+        let race = Arc::new(Race::new(cancel_rest));
+        let mut addresses = Vec::new();
+        for (which, task) in tasks.into_iter().enumerate() {
+            let tag = RaceTag::new(which, race.clone());
+            let address = self.spawn_task(Racer::new(task), tag, group.clone());
+            race.add(address.clone());
+            addresses.push(address);
+        }
+        addresses
+    }
+
     /// Spawns interaction task that forwards the result of an interaction.
     pub fn attach<S, M>(&mut self, stream: S, tag: M, group: A::GroupBy)
     where
@@ -213,6 +806,79 @@ impl<A: Actor> Context<A> {
         self.spawn_task(forwarder, tag, group);
     }
 
+    /// Spawns a task that forwards actions from the `stream` to the `Actor`.
+    ///
+    /// The task ends when the stream is over or the `Actor` is terminating.
+    pub fn attach_stream<S>(&mut self, stream: S, group: A::GroupBy)
+    where
+        S: Stream + Unpin + Send + 'static,
+        S::Item: Action,
+        A: ActionHandler<S::Item>,
+    {
+        let forwarder = ActionForwarder::new(stream, self.address.clone(), None, None);
+        self.spawn_task(forwarder, (), group);
+    }
+
+    /// The same as `attach_stream`, but lets at most `prefetch` items
+    /// be sent to the `Actor` and not handled yet.
+    ///
+    /// The stream is not polled while the window is full. That keeps
+    /// the memory bounded even if the mailbox is unbounded.
+    pub fn attach_stream_prefetch<S>(&mut self, stream: S, prefetch: usize, group: A::GroupBy)
+    where
+        S: Stream + Unpin + Send + 'static,
+        S::Item: Action,
+        A: ActionHandler<S::Item>,
+    {
+        let forwarder = ActionForwarder::new(stream, self.address.clone(), None, Some(prefetch));
+        self.spawn_task(forwarder, (), group);
+    }
+
+    /// The same as `attach_stream`, but also sends `StreamEnded` to
+    /// the `Actor` after the last item of the `stream`.
+    pub fn attach_stream_notify<S>(&mut self, stream: S, group: A::GroupBy)
+    where
+        S: Stream + Unpin + Send + 'static,
+        S::Item: Action,
+        A: ActionHandler<S::Item> + ActionHandler<StreamEnded<S::Item>>,
+    {
+        let ended = Box::new(self.address.clone());
+        let forwarder = ActionForwarder::new(stream, self.address.clone(), Some(ended), None);
+        self.spawn_task(forwarder, (), group);
+    }
+
+    /// Sends an action created by `make_msg` to the `Actor` itself every `period`.
+    ///
+    /// The interval stops when the returned handle is canceled or dropped.
+    pub fn start_interval<M, F>(&mut self, period: Duration, make_msg: F) -> IntervalHandle
+    where
+        A: ActionHandler<M>,
+        M: Action,
+        F: Fn() -> M + Send + 'static,
+    {
+        let task = IntervalTask::new(self.address.clone(), period, make_msg);
+        let address = lite_runtime::spawn(task, (), Option::<Address<A>>::None);
+        IntervalHandle::new(address.stop_on_drop())
+    }
+
+    /// Runs the `future` as a `LiteTask` and sends its output
+    /// to the `Actor` itself as an `Action`.
+    ///
+    /// The `future` is dropped if the task is interrupted.
+    pub fn spawn_future<F, T>(
+        &mut self,
+        future: F,
+        group: A::GroupBy,
+    ) -> TaskAddress<OneShotTask<F, T>>
+    where
+        F: Future<Output = Result<T, Error>> + Send + 'static,
+        T: Action,
+        A: ActionHandler<T>,
+    {
+        let task = OneShotTask::new(future, self.address.clone());
+        self.spawn_task(task, (), group)
+    }
+
     /// Spawns `InteractionTask` as a `LiteTask` and await the result as an `Action`
     /// that will call `InteractionDone` handler.
     pub fn track_interaction<I, M>(&mut self, task: InteractionTask<I>, tag: M, group: A::GroupBy)
@@ -224,14 +890,85 @@ impl<A: Actor> Context<A> {
         self.spawn_task(task, tag, group);
     }
 
+    /// Schedules an event to the `Actor` itself that will be handled
+    /// by the `Scheduled` handler when the `deadline` has reached.
+    ///
+    /// The returned `ScheduleHandle` can be used to cancel the event.
+    pub fn schedule<I>(&mut self, input: I, deadline: Instant) -> Result<ScheduleHandle, SendError>
+    where
+        I: Send + 'static,
+        A: Scheduled<I>,
+    {
+        self.address.schedule_cancelable(input, deadline)
+    }
+
     /// Interrupts an `Actor`.
-    pub fn interrupt<T>(&mut self, address: &mut Address<T>) -> Result<(), Error>
+    pub fn interrupt<T>(&mut self, address: &mut Address<T>) -> Result<(), SendError>
     where
         T: Actor + InterruptedBy<A>,
     {
         address.interrupt_by()
     }
 
+    /// Interrupts an `Actor` with the `reason`.
+    pub fn interrupt_with<T>(
+        &mut self,
+        address: &mut Address<T>,
+        reason: InterruptReason,
+    ) -> Result<(), SendError>
+    where
+        T: Actor + InterruptedBy<A>,
+    {
+        address.interrupt_with(reason)
+    }
+
+    /// The reason the `Actor` was interrupted with.
+    ///
+    /// Returns `None` if the `Actor` wasn't interrupted.
+    pub fn interrupt_reason(&self) -> Option<&InterruptReason> {
+        self.lifetime_tracker.interrupt_reason()
+    }
+
+    /// Defers handling of the `Action` until `unstash_all` is called.
+    ///
+    /// Messages that are still deferred when the `Actor` finishes
+    /// are reported as dead letters.
+    pub fn defer<M>(&mut self, msg: M)
+    where
+        M: Action,
+        A: ActionHandler<M>,
+    {
+        self.stash.push_back(Envelope::new(msg));
+    }
+
+    /// Returns all deferred messages back to be handled again before
+    /// the other ordinary messages in the order they were deferred.
+    pub fn unstash_all(&mut self) {
+        self.unstashed.append(&mut self.stash);
+    }
+
+    /// The number of deferred messages.
+    pub fn deferred(&self) -> usize {
+        self.stash.len()
+    }
+
+    /// Reports deferred messages that will never be handled.
+    fn drain_stash(&mut self) {
+        let mut dropped = 0;
+        for envelope in self.unstashed.drain(..).chain(self.stash.drain(..)) {
+            dead_letters::report(&self.id, envelope.message_type());
+            dropped += 1;
+        }
+        if dropped > 0 {
+            log::warn!(
+                target: &self.log_target,
+                "{} deferred messages of {} were not handled",
+                dropped,
+                self.id
+            );
+        }
+    }
+
     /// Returns `Error` if the `Actor` is terminating.
     /// Useful for checking in handlers.
     pub fn not_terminating(&self) -> Result<(), Error> {
@@ -242,19 +979,69 @@ impl<A: Actor> Context<A> {
         }
     }
 
+    /// Returns `true` if the `Actor` received an interruption that will
+    /// be handled after the current handler.
+    ///
+    /// Long running handlers can check it to finish earlier.
+    pub fn is_interrupt_pending(&self) -> bool {
+        self.address.is_interrupt_pending()
+    }
+
+    /// Returns the `Cancelled` error if the `Actor` is terminating or
+    /// an interruption is pending.
+    ///
+    /// Interaction handlers can use it to cancel the interaction and
+    /// to not block the shutdown. The caller gets `InteractError::Cancelled`.
+    pub fn not_interrupted(&self) -> Result<(), Error> {
+        if self.is_terminating() || self.is_interrupt_pending() {
+            Err(Cancelled.into())
+        } else {
+            Ok(())
+        }
+    }
+
     /// Stops the runtime of the `Actor` on one message will be processed after this call.
     ///
     /// It's recommended way to terminate `Actor` is the `shutdown` method.
     ///
     /// > Attention! Termination process will never started here and all spawned actors
-    /// and tasks will be orphaned.
+    /// > and tasks will be orphaned.
     pub fn stop(&mut self) {
         self.alive = false;
     }
 
-    /// Starts graceful termination of the `Actor`.
-    pub fn shutdown(&mut self) {
-        self.lifetime_tracker.start_termination();
+    /// Returns `Id` of the `Actor` that spawned this one.
+    ///
+    /// It's `None` for actors spawned by the `System`.
+    pub fn spawned_by(&self) -> Option<&Id> {
+        self.spawned_by.as_ref()
+    }
+
+    /// Returns the `Supervisor` to inspect and control children of the `Actor`.
+    pub fn supervisor(&mut self) -> Supervisor<'_, A> {
+        Supervisor::new(&mut self.lifetime_tracker)
+    }
+
+    /// Closes the ordinary priority queue for new messages, handles
+    /// all the messages that are already in the queue and starts
+    /// graceful termination of the `Actor` after that.
+    ///
+    /// High-priority messages are still handled while draining.
+    pub fn drain(&mut self) {
+        self.draining = true;
+    }
+
+    /// Starts graceful termination of the `Actor`.
+    ///
+    /// When all the children were eliminated the runtime stops and:
+    ///
+    /// 1. closes both queues, sending to the `Actor` fails after that;
+    /// 2. handles high-priority messages that were queued before the closing,
+    ///    ordinary and scheduled messages are dropped;
+    /// 3. calls `Actor::finalize`;
+    /// 4. notifies the supervisor with the `Done` event.
+    pub fn shutdown(&mut self) {
+        self.lifetime_tracker.start_termination();
         if self.lifetime_tracker.is_finished() {
             self.stop();
         }
@@ -268,17 +1055,52 @@ impl<A: Actor> Context<A> {
         self.lifetime_tracker.terminate_group(group)
     }
 
+    /// Returns live children (actors and tasks) in the order of spawning.
+    ///
+    /// Eliminated children are removed before the `Eliminated` handler is called.
+    pub fn children(&self) -> Vec<ChildInfo<A::GroupBy>> {
+        self.lifetime_tracker.children_info()
+    }
+
     /// Returns true if the shutdown process is in progress.
     pub fn is_terminating(&self) -> bool {
         self.lifetime_tracker.is_terminating()
     }
 
-    /// Increases the priority of the `Actor`'s type.
+    /// Sets the order of groups termination.
     ///
-    /// Used internally before `StartedBy` call.
-    fn termination_sequence(&mut self, sequence: Vec<A::GroupBy>) {
+    /// Every group is interrupted only after all the members of the previous group
+    /// were eliminated. Groups that are not in the `sequence` are interrupted
+    /// together after all the listed groups.
+    ///
+    /// The sequence from `TerminationSequence` of the `GroupBy` type
+    /// is used by default and can be overriden in the `StartedBy` handler.
+    pub fn termination_sequence(&mut self, sequence: Vec<A::GroupBy>) {
         self.lifetime_tracker.termination_sequence(sequence);
     }
+
+    /// Sets the shutdown priority of the child (an actor or a task) within its group.
+    ///
+    /// When the group terminates children with lower priorities are interrupted
+    /// first and the next ones only after all of them were eliminated. The default
+    /// priority is `0`. Fails if there is no child with the `id`.
+    pub fn set_shutdown_priority(&mut self, id: impl Into<Id>, priority: i32) -> Result<(), Error> {
+        let id = id.into();
+        if self.lifetime_tracker.set_priority(&id, priority) {
+            Ok(())
+        } else {
+            Err(Error::msg(format!("no child with the id {}", id)))
+        }
+    }
+
+    /// Moves the child (an actor or a task) to another group.
+    ///
+    /// The termination sequence takes the new group into account.
+    /// Fails if there is no child with the `id` or if the termination
+    /// of the `Actor` has already started.
+    pub fn set_group(&mut self, id: impl Into<Id>, group: A::GroupBy) -> Result<(), Error> {
+        self.lifetime_tracker.set_group(&id.into(), group)
+    }
 }
 
 /// `ActorRuntime` for `Actor`.
@@ -287,11 +1109,25 @@ pub struct ActorRuntime<A: Actor> {
     actor: A,
     context: Context<A>,
     awake_envelope: Option<Envelope<A>>,
-    done_notifier: Box<dyn LifecycleNotifier<Done<A>>>,
+    restarter: Option<Restarter<A>>,
+    /// Receives the result of the start if someone waits for it.
+    ready: Option<oneshot::Sender<Result<(), Error>>>,
+    /// The error of the last handled message if it failed.
+    last_error: Option<Error>,
+    /// Keys of recent messages if the `Actor` skips duplicates.
+    dedupe: Option<DedupeCache>,
     joint: AddressJoint<A>,
 }
 
 impl<A: Actor> ActorRuntime<A> {
+    /// Resolves when the `Actor` has to be dropped immediately.
+    async fn kill_signal(mut kill_rx: watch::Receiver<bool>) {
+        if kill_rx.wait_for(|killed| *killed).await.is_err() {
+            // All addresses are dropped and no one can kill the `Actor`.
+            future::pending::<()>().await;
+        }
+    }
+
     /// The `entrypoint` of the `ActorRuntime` that calls `routine` method.
     async fn entrypoint(mut self) {
         log::info!(target: self.actor.log_target(), "Actor started: {}", self.id);
@@ -301,25 +1137,102 @@ impl<A: Actor> ActorRuntime<A> {
             .expect("awake envelope has to be set in spawn method!");
         let term_seq = A::GroupBy::termination_sequence();
         self.context.termination_sequence(term_seq);
-        let awake_res = awake_envelope
-            .handle(&mut self.actor, &mut self.context)
-            .await;
-        match awake_res {
-            Ok(_) => {
-                self.routine().await;
+        let mut start_error = None;
+        loop {
+            self.context.address.lifecycle_event(LifecycleStage::Awake);
+            let awake_res = awake_envelope
+                .handle(&mut self.actor, &mut self.context)
+                .await;
+            let started = match awake_res {
+                Ok(_) => true,
+                Err(err) => {
+                    log::error!(
+                        target: self.actor.log_target(),
+                        "Can't call awake notification handler of the actor {:?}: {}",
+                        self.id,
+                        err
+                    );
+                    let ignore = self.actor.ignore_start_errors();
+                    if !ignore {
+                        start_error = Some(err);
+                    }
+                    ignore
+                }
+            };
+            if started {
+                start_error = None;
+                self.set_status(ActorStatus::Running);
+                if let Some(ready) = self.ready.take() {
+                    ready.send(Ok(())).ok();
+                }
+                let killed = {
+                    let kill = Self::kill_signal(self.joint.kill_rx.clone());
+                    let routine = self.routine();
+                    futures::pin_mut!(kill, routine);
+                    matches!(future::select(routine, kill).await, Either::Right(_))
+                };
+                if killed {
+                    log::warn!(target: self.actor.log_target(), "Actor killed: {}", self.id);
+                    // The `Actor` is done only when all killed descendants are done.
+                    let children = self.context.lifetime_tracker.kill_all();
+                    join_all(children).await;
+                    self.context.termination_reason = TerminationReason::Interrupted;
+                    break;
+                }
+                // An interrupted `Actor` has to finish, even if it failed.
+                let interrupted = self.context.interrupt_reason().is_some();
+                let restarted = if self.is_failed() && !interrupted {
+                    self.restarter.as_mut().and_then(Restarter::restart)
+                } else {
+                    None
+                };
+                if restarted.is_none() {
+                    self.close_queues().await;
+                }
+                if let Err(err) = self.actor.finalize(&mut self.context).await {
+                    log::error!(target: self.actor.log_target(), "Finalization of {} failed: {}", self.id, err);
+                }
+                match restarted {
+                    Some(restart) => {
+                        awake_envelope = self.restart(restart).await;
+                        continue;
+                    }
+                    None => break,
+                }
             }
-            Err(err) => {
-                log::error!(
-                    target: self.actor.log_target(),
-                    "Can't call awake notification handler of the actor {:?}: {}",
-                    self.id,
-                    err
-                );
+            let restarted = self.restarter.as_mut().and_then(Restarter::restart);
+            if let Some(restart) = restarted {
+                awake_envelope = self.restart(restart).await;
+            } else {
+                break;
+            }
+        }
+        let reason = match &start_error {
+            Some(err) => TerminationReason::Failed(err.to_string()),
+            None => self.context.termination_reason.clone(),
+        };
+        let mut error = self.last_error.take();
+        match (self.ready.take(), start_error) {
+            (Some(ready), Some(err)) => {
+                error = Some(Error::msg(err.to_string()));
+                ready.send(Err(err)).ok();
+            }
+            (Some(ready), None) => {
+                ready
+                    .send(Err(Error::msg("the actor has not started")))
+                    .ok();
             }
+            (None, Some(err)) => {
+                error = Some(err);
+            }
+            (None, None) => {}
         }
+        self.context.drain_stash();
         log::info!(target: self.actor.log_target(), "Actor finished: {}", self.id);
-        let done_event = Done::new(self.id.clone());
-        if let Err(err) = self.done_notifier.notify(done_event) {
+        let stage = LifecycleStage::Done(reason.clone());
+        let done_event = Done::new(self.id.clone(), reason, error);
+        let notified = self.context.address.supervisor_slot().finish(done_event);
+        if let Err(err) = &notified {
             log::error!(
                 target: self.actor.log_target(),
                 "Can't send done notification from the actor {:?}: {}",
@@ -327,17 +1240,50 @@ impl<A: Actor> ActorRuntime<A> {
                 err
             );
         }
-        if !self.joint.join_tx.is_closed() {
-            if let Err(_err) = self.joint.join_tx.send(Status::Stop) {
-                log::error!(target: self.actor.log_target(), "Can't release joiners of {}", self.id);
+        self.set_status(ActorStatus::Done);
+        self.context.address.lifecycle_event(stage);
+        if let Err(err) = notified {
+            if self.actor.panic_on_lost_done() {
+                panic!("The done notification of {:?} was lost: {}", self.id, err);
             }
         }
     }
 
+    /// Updates the status observed by addresses and releases joiners if it's done.
+    fn set_status(&mut self, status: ActorStatus) {
+        let modified = self.joint.join_tx.send_if_modified(|current| {
+            let modified = *current != status;
+            *current = status;
+            modified
+        });
+        if modified {
+            let stage = match status {
+                ActorStatus::Running => LifecycleStage::Running,
+                ActorStatus::Terminating => LifecycleStage::Terminating,
+                ActorStatus::Awake | ActorStatus::Done => return,
+            };
+            self.context.address.lifecycle_event(stage);
+        }
+    }
+
     async fn routine(&mut self) {
         let mut scheduled_queue = crate::compat::DelayQueue::<Envelope<A>>::new().fuse();
         let mut pendel = pending();
+        let mut mailbox_closed = false;
+        let mut mailbox_drained = false;
+        let hp_burst_limit = self.actor.hp_burst_limit();
+        let mut hp_streak = 0;
+        let yield_interval = self.actor.yield_interval().filter(|interval| *interval > 0);
+        let mut handled = 0;
+        let idle_timeout = self.actor.idle_timeout();
+        let mut last_activity = Instant::now();
+        let mut idle_timer = Self::idle_timer(idle_timeout.map(|timeout| last_activity + timeout));
         while self.context.alive {
+            let mut idle = false;
+            if self.context.draining && !mailbox_closed {
+                self.joint.msg_rx.close();
+                mailbox_closed = true;
+            }
             // This is a workaround not to call `DelayQueue` if it has no items,
             // because its resumable, but returns `None` and brokes (closes) `FusedStream`.
             // And after some delay no any future scheduled event occured.
@@ -349,84 +1295,143 @@ impl<A: Actor> ActorRuntime<A> {
                 } else {
                     &mut pendel
                 };
-            select_biased! {
-                hp_envelope = self.joint.hp_msg_rx.recv().fuse() => {
-                    if let Some(hp_env) = hp_envelope {
-                        let envelope = hp_env.envelope;
-                        let process_envelope;
-                        match hp_env.operation {
-                            Operation::Forward => {
-                                process_envelope = Some(envelope);
+            // Draining has to complete even if the `Actor` is paused.
+            let paused = self.context.paused && !self.context.draining;
+            // Gives a turn to a waiting ordinary message if the burst limit reached.
+            let mut starving = None;
+            if hp_burst_limit.is_some_and(|limit| hp_streak >= limit) {
+                hp_streak = 0;
+                if !mailbox_drained && !paused {
+                    starving = self.joint.msg_rx.try_recv();
+                }
+            }
+            if let Some(envelope) = starving {
+                #[cfg(feature = "metrics")]
+                self.joint.metrics.received();
+                self.process(envelope, Queue::Normal).await;
+            } else {
+                select_biased! {
+                    hp_envelope = self.joint.hp_msg_rx.recv().fuse() => {
+                        if let Some(hp_env) = hp_envelope {
+                            if hp_env.interrupt {
+                                self.context.address.interrupt_received();
                             }
-                            Operation::Done { id } => {
-                                self.context.lifetime_tracker.remove(&id);
-                                if self.context.lifetime_tracker.is_finished() {
+                            let envelope = hp_env.envelope;
+                            let process_envelope = match hp_env.operation {
+                                Operation::Forward => {
+                                    Some(envelope)
+                                }
+                                Operation::Done { id } => {
+                                    self.context.lifetime_tracker.remove(&id);
+                                    if self.context.lifetime_tracker.is_finished() {
 
-                                    self.context.stop();
+                                        self.context.stop();
+                                    }
+                                    Some(envelope)
                                 }
-                                process_envelope = Some(envelope);
+                                Operation::Schedule { deadline } => {
+                                    scheduled_queue.get_mut().insert_at(envelope, deadline);
+                                    log::trace!(target: self.actor.log_target(), "Scheduled events: {}", scheduled_queue.get_ref().len());
+                                    None
+                                }
+                            };
+                            if let Some(envelope) = process_envelope {
+                                hp_streak += 1;
+                                self.process(envelope, Queue::HighPriority).await;
                             }
-                            Operation::Schedule { deadline } => {
-                                scheduled_queue.get_mut().insert_at(envelope, deadline);
-                                log::trace!(target: self.actor.log_target(), "Scheduled events: {}", scheduled_queue.get_ref().len());
-                                process_envelope = None;
+                        } else {
+                            // Even if all `Address` dropped `Actor` can do something useful on
+                            // background. Than don't terminate actors without `Addresses`, because
+                            // it still has controllers.
+                            // Background tasks = something spawned that `Actors` waits for finishing.
+                            log::trace!(target: self.actor.log_target(), "Messages stream of {} (high-priority) drained.", self.id);
+                            if let Err(err) = self.actor.instant_queue_drained(&mut self.context).await {
+                                log::error!(target: self.actor.log_target(), "Queue (high-priority) drained handler {} failed: {}", self.id, err);
                             }
                         }
-                        if let Some(mut envelope) = process_envelope {
-                            let handle_res = envelope.handle(&mut self.actor, &mut self.context).await;
-                            if let Err(err) = handle_res {
-                                log::error!(target: self.actor.log_target(), "Handler for {} (high-priority) failed: {}", self.id, err);
+                    }
+                    opt_delayed_envelope = maybe_queue.next() => {
+                        if let Some(delayed_envelope) = opt_delayed_envelope {
+                            match delayed_envelope {
+                                Ok(expired) => {
+                                    log::trace!(target: self.actor.log_target(), "Execute scheduled event. Remained: {}", scheduled_queue.get_ref().len());
+                                    let envelope = expired.into_inner();
+                                    self.process(envelope, Queue::Scheduled).await;
+                                }
+                                Err(err) => {
+                                    log::error!(target: self.actor.log_target(), "Failed scheduled execution for {}: {}", self.id, err);
+                                }
+                            }
+                        } else {
+                            log::error!(target: self.actor.log_target(), "Delay queue of {} closed.", self.id);
+                            if let Err(err) = self.actor.instant_queue_drained(&mut self.context).await {
+                                log::error!(target: self.actor.log_target(), "Queue (high-priority) drained handler {} failed: {}", self.id, err);
                             }
                         }
-                    } else {
-                        // Even if all `Address` dropped `Actor` can do something useful on
-                        // background. Than don't terminate actors without `Addresses`, because
-                        // it still has controllers.
-                        // Background tasks = something spawned that `Actors` waits for finishing.
-                        log::trace!(target: self.actor.log_target(), "Messages stream of {} (high-priority) drained.", self.id);
-                        if let Err(err) = self.actor.instant_queue_drained(&mut self.context).await {
-                            log::error!(target: self.actor.log_target(), "Queue (high-priority) drained handler {} failed: {}", self.id, err);
+                    }
+                    () = if paused || self.context.unstashed.is_empty() { Fuse::terminated() } else { future::ready(()).fuse() } => {
+                        if let Some(envelope) = self.context.unstashed.pop_front() {
+                            hp_streak = 0;
+                            self.process(envelope, Queue::Normal).await;
                         }
                     }
-                }
-                opt_delayed_envelope = maybe_queue.next() => {
-                    if let Some(delayed_envelope) = opt_delayed_envelope {
-                        match delayed_envelope {
-                            Ok(expired) => {
-                                log::trace!(target: self.actor.log_target(), "Execute scheduled event. Remained: {}", scheduled_queue.get_ref().len());
-                                let mut envelope = expired.into_inner();
-                                let handle_res = envelope.handle(&mut self.actor, &mut self.context).await;
-                                if let Err(err) = handle_res {
-                                    log::error!(target: self.actor.log_target(), "Handler for {} (scheduled) failed: {}", self.id, err);
-                                }
+                    lp_envelope = if mailbox_drained || paused { Fuse::terminated() } else { self.joint.msg_rx.recv().fuse() } => {
+                        if let Some(envelope) = lp_envelope {
+                            hp_streak = 0;
+                            #[cfg(feature = "metrics")]
+                            self.joint.metrics.received();
+                            self.process(envelope, Queue::Normal).await;
+                        } else {
+                            // Even if all `Address` dropped `Actor` can do something useful on
+                            // background. Than don't terminate actors without `Addresses`, because
+                            // it still has controllers.
+                            // Background tasks = something spawned that `Actors` waits for finishing.
+                            log::trace!(target: self.actor.log_target(), "Messages stream of {} drained.", self.id);
+                            if let Err(err) = self.actor.queue_drained(&mut self.context).await {
+                                log::error!(target: self.actor.log_target(), "Queue drained handler {} failed: {}", self.id, err);
                             }
-                            Err(err) => {
-                                log::error!(target: self.actor.log_target(), "Failed scheduled execution for {}: {}", self.id, err);
+                            if self.context.draining {
+                                // The queue is closed and will never provide messages again.
+                                mailbox_drained = true;
+                                self.context.shutdown();
                             }
                         }
-                    } else {
-                        log::error!(target: self.actor.log_target(), "Delay queue of {} closed.", self.id);
-                        if let Err(err) = self.actor.instant_queue_drained(&mut self.context).await {
-                            log::error!(target: self.actor.log_target(), "Queue (high-priority) drained handler {} failed: {}", self.id, err);
-                        }
+                    }
+                    () = &mut idle_timer => {
+                        idle = true;
                     }
                 }
-                lp_envelope = self.joint.msg_rx.recv().fuse() => {
-                    if let Some(mut envelope) = lp_envelope {
-                        let handle_res = envelope.handle(&mut self.actor, &mut self.context).await;
-                        if let Err(err) = handle_res {
-                            log::error!(target: self.actor.log_target(), "Handler for {} failed: {}", self.id, err);
-                        }
-                    } else {
-                        // Even if all `Address` dropped `Actor` can do something useful on
-                        // background. Than don't terminate actors without `Addresses`, because
-                        // it still has controllers.
-                        // Background tasks = something spawned that `Actors` waits for finishing.
-                        log::trace!(target: self.actor.log_target(), "Messages stream of {} drained.", self.id);
-                        if let Err(err) = self.actor.queue_drained(&mut self.context).await {
-                            log::error!(target: self.actor.log_target(), "Queue drained handler {} failed: {}", self.id, err);
+            }
+            if let Some(timeout) = idle_timeout {
+                // The timer isn't recreated for every message, but is checked
+                // against the last activity when it fired.
+                let now = Instant::now();
+                if !idle {
+                    last_activity = now;
+                } else {
+                    if now >= last_activity + timeout {
+                        log::debug!(target: self.actor.log_target(), "Actor {} is idle for {:?}", self.id, timeout);
+                        if let Err(err) = self.actor.idle_timed_out(&mut self.context).await {
+                            log::error!(target: self.actor.log_target(), "Idle handler of {} failed: {}", self.id, err);
                         }
+                        last_activity = now;
                     }
+                    let deadline = if self.context.is_terminating() {
+                        None
+                    } else {
+                        Some(last_activity + timeout)
+                    };
+                    idle_timer = Self::idle_timer(deadline);
+                }
+            }
+            if self.context.is_terminating() {
+                self.set_status(ActorStatus::Terminating);
+            }
+            if let Some(interval) = yield_interval {
+                handled += 1;
+                if handled >= interval {
+                    handled = 0;
+                    crate::compat::yield_now().await;
                 }
             }
             /*
@@ -437,4 +1442,2087 @@ impl<A: Actor> ActorRuntime<A> {
             */
         }
     }
+
+    /// Returns `true` if the routine of the `Actor` ended abnormally.
+    fn is_failed(&self) -> bool {
+        matches!(
+            self.context.termination_reason,
+            TerminationReason::Failed(_) | TerminationReason::Panicked(_)
+        )
+    }
+
+    /// Replaces the failed instance and its `Context` with fresh ones
+    /// and returns the awake envelope of the new instance.
+    ///
+    /// Queues stay open and messages sent meanwhile are handled by the new instance.
+    async fn restart(&mut self, restart: Restart<A>) -> Envelope<A> {
+        log::warn!(target: self.actor.log_target(), "Restarting the actor {:?} in {:?}", self.id, restart.delay);
+        // Children of the failed instance can't be adopted by the new one.
+        let children = self.context.lifetime_tracker.kill_all();
+        join_all(children).await;
+        self.context.drain_stash();
+        if !restart.delay.is_zero() {
+            crate::compat::delay(restart.delay).await;
+        }
+        let id: Id = self.id.clone().into();
+        self.context = Context::new(id, self.context.address.clone(), restart.actor.log_target());
+        self.context
+            .termination_sequence(A::GroupBy::termination_sequence());
+        self.actor = restart.actor;
+        self.last_error = None;
+        restart.awake
+    }
+
+    fn idle_timer(deadline: Option<Instant>) -> Fuse<BoxFuture<'static, ()>> {
+        match deadline {
+            Some(deadline) => {
+                let duration = deadline.saturating_duration_since(Instant::now());
+                crate::compat::delay(duration).boxed().fuse()
+            }
+            None => Fuse::terminated(),
+        }
+    }
+
+    /// Closes both queues and handles high-priority messages that are already queued.
+    ///
+    /// Nothing can be sent to the `Actor` after that, that's why the final `Done`
+    /// notification never races with interruptions sent later.
+    async fn close_queues(&mut self) {
+        self.joint.msg_rx.close();
+        self.joint.hp_msg_rx.close();
+        while let Some(parcel) = self.joint.hp_msg_rx.recv().await {
+            if parcel.interrupt {
+                self.context.address.interrupt_received();
+            }
+            match parcel.operation {
+                Operation::Forward => {}
+                Operation::Done { id } => {
+                    self.context.lifetime_tracker.remove(&id);
+                }
+                Operation::Schedule { .. } => {
+                    dead_letters::report(self.context.id(), parcel.envelope.message_type());
+                    continue;
+                }
+            }
+            self.process(parcel.envelope, Queue::HighPriority).await;
+        }
+    }
+
+    /// Calls the handler of the `envelope` and logs its error.
+    async fn process(&mut self, mut envelope: Envelope<A>, queue: Queue) {
+        let catch_panics = self.actor.catch_panics();
+        let message = envelope.message_type();
+        if let (Some(cache), Some(key)) = (self.dedupe.as_mut(), envelope.dedupe_key()) {
+            if !cache.insert(message, key) {
+                log::debug!(target: self.actor.log_target(), "Duplicate {} skipped by {}", message, self.id);
+                return;
+            }
+        }
+        let timing = self
+            .actor
+            .slow_handler_threshold()
+            .map(HandlerTiming::start);
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "handle",
+            actor_id = %self.id,
+            actor = std::any::type_name::<A>(),
+            message,
+            ?queue,
+            error = tracing::field::Empty,
+        );
+        let fut = envelope.handle(&mut self.actor, &mut self.context);
+        #[cfg(feature = "tracing")]
+        let fut = tracing::Instrument::instrument(fut, span.clone());
+        let handle_res = if catch_panics {
+            match AssertUnwindSafe(fut).catch_unwind().await {
+                Ok(res) => res,
+                Err(payload) => {
+                    let reason = payload
+                        .downcast_ref::<&str>()
+                        .map(|reason| reason.to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    #[cfg(feature = "tracing")]
+                    span.record("error", reason.as_str());
+                    log::error!(target: self.actor.log_target(), "Handler for {}{} panicked on {}: {}", self.id, queue.suffix(), message, reason);
+                    self.last_error = Some(Error::msg(format!("the handler panicked: {}", reason)));
+                    self.context.termination_reason = TerminationReason::Panicked(reason);
+                    self.context.shutdown();
+                    return;
+                }
+            }
+        } else {
+            fut.await
+        };
+        if let Some(elapsed) = timing.and_then(|timing| timing.slow()) {
+            log::warn!(target: self.actor.log_target(), "Handler for {}{} was slow on {}: {:?}", self.id, queue.suffix(), message, elapsed);
+        }
+        #[cfg(feature = "metrics")]
+        self.joint.metrics.handled(handle_res.is_ok());
+        if let Err(err) = handle_res {
+            #[cfg(feature = "tracing")]
+            span.record("error", tracing::field::display(&err));
+            log::error!(target: self.actor.log_target(), "Handler for {}{} failed: {}", self.id, queue.suffix(), err);
+            // The failure is remembered if the `Actor` is finishing, since handlers
+            // called during the termination don't change the outcome.
+            let finishing = self.context.is_terminating() || !self.context.alive;
+            if finishing && self.context.termination_reason == TerminationReason::Normal {
+                self.context.termination_reason = TerminationReason::Failed(err.to_string());
+                self.last_error = Some(err);
+            }
+        }
+    }
+}
+
+/// The queue that provided an envelope.
+#[derive(Debug, Clone, Copy)]
+enum Queue {
+    Normal,
+    HighPriority,
+    Scheduled,
+}
+
+impl Queue {
+    /// The suffix for log messages.
+    fn suffix(&self) -> &'static str {
+        match self {
+            Self::Normal => "",
+            Self::HighPriority => " (high-priority)",
+            Self::Scheduled => " (scheduled)",
+        }
+    }
+}
+
+/// Measures a handler against the `Actor::slow_handler_threshold`.
+#[derive(Debug, Clone, Copy)]
+struct HandlerTiming {
+    threshold: Duration,
+    started: Instant,
+}
+
+impl HandlerTiming {
+    fn start(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            started: Instant::now(),
+        }
+    }
+
+    /// Returns the elapsed time if it exceeded the threshold.
+    fn slow(&self) -> Option<Duration> {
+        let elapsed = self.started.elapsed();
+        (elapsed > self.threshold).then_some(elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::channel::oneshot;
+    use futures::{stream, StreamExt};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use tokio::time::{sleep, timeout};
+
+    struct PanickingActor;
+
+    impl Actor for PanickingActor {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "PanickingActor"
+        }
+
+        fn catch_panics(&self) -> bool {
+            true
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for PanickingActor {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Touch> for PanickingActor {
+        async fn handle(&mut self, _: Touch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            panic!("the handler panicked intentionally");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_catch_panics() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(PanickingActor);
+        address.act(Touch)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    struct Computing {
+        done: Option<oneshot::Sender<u8>>,
+    }
+
+    impl Actor for Computing {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Computing"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Computing {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.spawn_future(
+                async {
+                    sleep(Duration::from_millis(10)).await;
+                    Ok(Seq(42))
+                },
+                (),
+            );
+            // Interrupted by the shutdown and never completed.
+            ctx.spawn_future(
+                async {
+                    sleep(Duration::from_secs(60)).await;
+                    Ok(Seq(0))
+                },
+                (),
+            );
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Computing {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Seq> for Computing {
+        async fn handle(&mut self, msg: Seq, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            if let Some(tx) = self.done.take() {
+                tx.send(msg.0).ok();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_future() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let address = System::spawn(Computing { done: Some(tx) });
+        assert_eq!(rx.await?, 42);
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hp_burst_limit() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let address = System::spawn(FairActor {
+            handled: Vec::new(),
+            done: Some(tx),
+        });
+        let handled = rx.await?;
+        assert_eq!(handled, vec!["urgent", "urgent", "regular"]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    struct WhoAmI;
+
+    impl Interaction for WhoAmI {
+        type Output = Id;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<WhoAmI> for OrderedActor {
+        async fn handle(&mut self, _msg: WhoAmI, ctx: &mut Context<Self>) -> Result<Id, Error> {
+            Ok(ctx.id().clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_context_id() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let id = address.interact(WhoAmI).recv().await?;
+        assert_eq!(id, Id::from(address.id()));
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    struct StreamingActor {
+        seen: Vec<u8>,
+        ended: Option<oneshot::Sender<Vec<u8>>>,
+    }
+
+    impl Actor for StreamingActor {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "StreamingActor"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for StreamingActor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let items = stream::iter(vec![Seq(1), Seq(2), Seq(3)]);
+            ctx.attach_stream_notify(items, ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for StreamingActor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Seq> for StreamingActor {
+        async fn handle(&mut self, msg: Seq, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.seen.push(msg.0);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<StreamEnded<Seq>> for StreamingActor {
+        async fn handle(
+            &mut self,
+            _msg: StreamEnded<Seq>,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            if let Some(tx) = self.ended.take() {
+                tx.send(self.seen.clone()).ok();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_attach_stream() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let actor = StreamingActor {
+            seen: Vec::new(),
+            ended: Some(tx),
+        };
+        let address = System::spawn(actor);
+        let seen = rx.await?;
+        assert_eq!(seen, vec![1, 2, 3]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    struct PrefetchActor {
+        pulled: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        handled: usize,
+        max_in_flight: usize,
+        done: Option<oneshot::Sender<usize>>,
+    }
+
+    impl Actor for PrefetchActor {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "PrefetchActor"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for PrefetchActor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let pulled = self.pulled.clone();
+            let items = stream::iter(1..=10).map(move |n| {
+                pulled.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Seq(n)
+            });
+            ctx.attach_stream_prefetch(items, 2, ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for PrefetchActor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Seq> for PrefetchActor {
+        async fn handle(&mut self, msg: Seq, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            sleep(Duration::from_millis(5)).await;
+            let pulled = self.pulled.load(std::sync::atomic::Ordering::SeqCst);
+            self.max_in_flight = self.max_in_flight.max(pulled - self.handled);
+            self.handled += 1;
+            if msg.0 == 10 {
+                if let Some(tx) = self.done.take() {
+                    tx.send(self.max_in_flight).ok();
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_attach_stream_prefetch() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let actor = PrefetchActor {
+            pulled: Default::default(),
+            handled: 0,
+            max_in_flight: 0,
+            done: Some(tx),
+        };
+        let address = System::spawn(actor);
+        let max_in_flight = rx.await?;
+        assert!(max_in_flight <= 2, "in flight: {}", max_in_flight);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    struct FlakyChild {
+        attempts: Arc<AtomicUsize>,
+    }
+
+    impl Actor for FlakyChild {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "FlakyChild"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<RestartingParent> for FlakyChild {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempt < 3 {
+                Err(Error::msg("not ready yet"))
+            } else {
+                ctx.shutdown();
+                Ok(())
+            }
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<RestartingParent> for FlakyChild {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RestartingParent {
+        attempts: Arc<AtomicUsize>,
+    }
+
+    impl Actor for RestartingParent {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "RestartingParent"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for RestartingParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let attempts = self.attempts.clone();
+            let factory = move || FlakyChild {
+                attempts: attempts.clone(),
+            };
+            let policy = RestartPolicy::OnError { max_retries: 2 };
+            ctx.spawn_actor_with(factory, policy, ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<FlakyChild> for RestartingParent {
+        async fn handle(
+            &mut self,
+            _id: IdOf<FlakyChild>,
+            _reason: TerminationReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restart_policy() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let parent = RestartingParent::default();
+        let attempts = parent.attempts.clone();
+        let address = System::spawn(parent);
+        timeout(Duration::from_secs(5), address.join()).await?;
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+
+    struct WatchingParent {
+        eliminated: Option<oneshot::Sender<Option<String>>>,
+    }
+
+    impl Actor for WatchingParent {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "WatchingParent"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for WatchingParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.spawn_actor(ExplodingChild, ()).act(Explode)?;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<ExplodingChild> for WatchingParent {
+        async fn handle(
+            &mut self,
+            _id: IdOf<ExplodingChild>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            unreachable!("handle_with_error is overridden")
+        }
+
+        async fn handle_with_error(
+            &mut self,
+            _id: IdOf<ExplodingChild>,
+            _reason: TerminationReason,
+            error: Option<Error>,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            if let Some(tx) = self.eliminated.take() {
+                tx.send(error.map(|err| err.to_string())).ok();
+            }
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct ExplodingChild;
+
+    impl Actor for ExplodingChild {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "ExplodingChild"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<WatchingParent> for ExplodingChild {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<WatchingParent> for ExplodingChild {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct Explode;
+
+    impl Action for Explode {}
+
+    #[async_trait]
+    impl ActionHandler<Explode> for ExplodingChild {
+        async fn handle(&mut self, _msg: Explode, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Err(Error::msg("boom"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_eliminated_with_error() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let address = System::spawn(WatchingParent {
+            eliminated: Some(tx),
+        });
+        let error = rx.await?;
+        assert_eq!(error.as_deref(), Some("boom"));
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    #[derive(Default)]
+    struct SchedulingActor {
+        fired: Vec<&'static str>,
+    }
+
+    impl Actor for SchedulingActor {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "SchedulingActor"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for SchedulingActor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let now = Instant::now();
+            let handle = ctx.schedule("canceled", now + Duration::from_millis(10))?;
+            ctx.schedule("fired", now + Duration::from_millis(50))?;
+            handle.cancel();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Scheduled<&'static str> for SchedulingActor {
+        async fn handle(
+            &mut self,
+            _timestamp: Instant,
+            item: &'static str,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.fired.push(item);
+            assert_eq!(self.fired, vec!["fired"]);
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schedule_cancel() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(SchedulingActor::default());
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    #[derive(Default)]
+    struct DrainingActor {
+        handled: Arc<AtomicUsize>,
+        finalized: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl Actor for DrainingActor {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "DrainingActor"
+        }
+
+        async fn finalize(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.finalized.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for DrainingActor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            for _ in 0..3 {
+                ctx.address().act(Touch)?;
+            }
+            ctx.drain();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Touch> for DrainingActor {
+        async fn handle(&mut self, _: Touch, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.handled.fetch_add(1, Ordering::SeqCst);
+            assert!(ctx.address().act(Touch).is_err());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let actor = DrainingActor::default();
+        let handled = actor.handled.clone();
+        let finalized = actor.finalized.clone();
+        let address = System::spawn(actor);
+        timeout(Duration::from_secs(5), address.join()).await?;
+        assert_eq!(handled.load(Ordering::SeqCst), 3);
+        assert!(finalized.load(Ordering::SeqCst));
+        Ok(())
+    }
+
+    #[derive(Default)]
+    struct Gate {
+        open: bool,
+        passed: Vec<u8>,
+    }
+
+    impl Actor for Gate {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Gate"
+        }
+    }
+
+    struct Open;
+
+    impl Action for Open {}
+
+    #[async_trait]
+    impl ActionHandler<Open> for Gate {
+        async fn handle(&mut self, _: Open, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.open = true;
+            ctx.unstash_all();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Seq> for Gate {
+        async fn handle(&mut self, msg: Seq, ctx: &mut Context<Self>) -> Result<(), Error> {
+            if self.open {
+                self.passed.push(msg.0);
+            } else {
+                ctx.defer(msg);
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<GetSeen> for Gate {
+        async fn handle(&mut self, msg: GetSeen, ctx: &mut Context<Self>) -> Result<(), Error> {
+            assert_eq!(ctx.deferred(), 0);
+            msg.0.send(self.passed.clone()).ok();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Gate {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Gate {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_defer_and_unstash() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(Gate::default());
+        address.act(Seq(1))?;
+        address.act(Seq(2))?;
+        address.act(Open)?;
+        address.act(Seq(3))?;
+        // Deferred messages are handled before the newer ones.
+        assert_eq!(address.ask(GetSeen).await?, vec![1, 2, 3]);
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    struct Ping;
+
+    impl InstantAction for Ping {}
+
+    #[async_trait]
+    impl InstantActionHandler<Ping> for Stopper {
+        async fn handle(&mut self, _: Ping, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.pinged.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hp_queue_closed_on_stop() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let pinged = Arc::new(AtomicBool::new(false));
+        let address = System::spawn(Stopper {
+            pinged: pinged.clone(),
+        });
+        address.act(StopLater)?;
+        sleep(Duration::from_millis(10)).await;
+        // Queued before the stop and handled before the actor finishes.
+        address.instant(Ping)?;
+        timeout(Duration::from_secs(5), address.clone().join()).await?;
+        assert!(pinged.load(Ordering::SeqCst));
+        assert!(address.instant(Ping).is_err());
+        Ok(())
+    }
+
+    struct Flooded {
+        handled: usize,
+        progress: Arc<AtomicUsize>,
+        observed: Option<oneshot::Sender<usize>>,
+    }
+
+    impl Actor for Flooded {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Flooded"
+        }
+
+        fn yield_interval(&self) -> Option<usize> {
+            Some(10)
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Flooded {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Flooded {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Bump> for Flooded {
+        async fn handle(&mut self, _msg: Bump, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.handled += 1;
+            if self.handled == 100 {
+                if let Some(observed) = self.observed.take() {
+                    observed.send(self.progress.load(Ordering::SeqCst)).ok();
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_yield_interval() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let progress = Arc::new(AtomicUsize::new(0));
+        let address = System::spawn(Flooded {
+            handled: 0,
+            progress: progress.clone(),
+            observed: Some(tx),
+        });
+        for _ in 0..100 {
+            address.act(Bump)?;
+        }
+        // The neighbour shares the single thread of the test runtime.
+        let neighbour = tokio::spawn(async move {
+            loop {
+                progress.fetch_add(1, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+            }
+        });
+        let progress = rx.await?;
+        assert!(progress > 0);
+        neighbour.abort();
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancel_interaction() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let job = tokio::spawn(address.interact(LongJob).recv());
+        sleep(Duration::from_millis(50)).await;
+        System::interrupt(&address)?;
+        let err = timeout(Duration::from_secs(1), job).await??.unwrap_err();
+        assert!(matches!(err, InteractError::Cancelled));
+        timeout(Duration::from_secs(1), address.join()).await?;
+        Ok(())
+    }
+
+    struct Handshake(u32);
+
+    impl Action for Handshake {}
+
+    struct PairOwner {
+        done: Option<oneshot::Sender<u32>>,
+    }
+
+    impl Actor for PairOwner {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "PairOwner"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for PairOwner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let done = self.done.take();
+            ctx.spawn_linked(
+                |producer, responder| (Producer { responder, done }, Responder { producer }),
+                (),
+            );
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for PairOwner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Producer> for PairOwner {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Producer>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Responder> for PairOwner {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Responder>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    struct Producer {
+        responder: Address<Responder>,
+        done: Option<oneshot::Sender<u32>>,
+    }
+
+    impl Actor for Producer {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Producer"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<PairOwner> for Producer {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.responder.act(Handshake(1))?;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<PairOwner> for Producer {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Handshake> for Producer {
+        async fn handle(&mut self, msg: Handshake, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            if let Some(done) = self.done.take() {
+                done.send(msg.0).ok();
+            }
+            Ok(())
+        }
+    }
+
+    struct Responder {
+        producer: Address<Producer>,
+    }
+
+    impl Actor for Responder {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Responder"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<PairOwner> for Responder {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<PairOwner> for Responder {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Handshake> for Responder {
+        async fn handle(&mut self, msg: Handshake, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.producer.act(Handshake(msg.0 + 1))?;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_linked() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let address = System::spawn(PairOwner { done: Some(tx) });
+        let reply = timeout(Duration::from_secs(5), rx).await??;
+        assert_eq!(reply, 2);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    struct Acquirer {
+        connections: u8,
+    }
+
+    impl Acquirer {
+        async fn create(connections: u8) -> Result<Self, Error> {
+            if connections == 0 {
+                Err(Error::msg("no connections available"))
+            } else {
+                Ok(Self { connections })
+            }
+        }
+    }
+
+    impl Actor for Acquirer {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Acquirer"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<AcquiringOwner> for Acquirer {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            assert_eq!(self.connections, 2);
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Acquirer {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<AcquiringOwner> for Acquirer {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct AcquiringOwner {
+        reasons: Vec<TerminationReason>,
+        done: Option<oneshot::Sender<Vec<TerminationReason>>>,
+    }
+
+    impl Actor for AcquiringOwner {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "AcquiringOwner"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for AcquiringOwner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.spawn_created(Acquirer::create(0), ());
+            ctx.spawn_created(Acquirer::create(2), ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for AcquiringOwner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Acquirer> for AcquiringOwner {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Acquirer>,
+            reason: TerminationReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.reasons.push(reason);
+            if self.reasons.len() == 2 {
+                if let Some(done) = self.done.take() {
+                    done.send(std::mem::take(&mut self.reasons)).ok();
+                }
+                ctx.shutdown();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_created() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let address = System::spawn(AcquiringOwner {
+            reasons: Vec::new(),
+            done: Some(tx),
+        });
+        let mut reasons = timeout(Duration::from_secs(5), rx).await??;
+        reasons.sort_by_key(|reason| matches!(reason, TerminationReason::Normal));
+        assert!(
+            matches!(&reasons[0], TerminationReason::Failed(msg) if msg.contains("no connections"))
+        );
+        assert!(matches!(reasons[1], TerminationReason::Normal));
+        address.join().await;
+
+        let failed = System::spawn_with(Acquirer::create(0));
+        timeout(Duration::from_secs(5), failed.join()).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(Session);
+        let started = Instant::now();
+        for i in 0..6 {
+            sleep(Duration::from_millis(40)).await;
+            if i % 2 == 0 {
+                address.act(Touch)?;
+            } else {
+                address.instant(UrgentTouch)?;
+            }
+        }
+        assert_eq!(address.status(), ActorStatus::Running);
+        timeout(Duration::from_secs(5), address.join()).await?;
+        assert!(started.elapsed() >= Duration::from_millis(340));
+        Ok(())
+    }
+
+    struct DefaultTarget;
+
+    impl Actor for DefaultTarget {
+        type GroupBy = ();
+    }
+
+    #[test]
+    fn test_default_log_target() {
+        assert_eq!(
+            DefaultTarget.log_target(),
+            "meio::actor_runtime::tests::DefaultTarget"
+        );
+    }
+
+    struct BusySupervisor;
+
+    impl Actor for BusySupervisor {
+        type GroupBy = ();
+
+        fn hp_capacity(&self) -> Option<usize> {
+            Some(1)
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for BusySupervisor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.spawn_actor(ShortLived, ());
+            // The child finishes while the high-priority slot is taken.
+            sleep(Duration::from_millis(50)).await;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for BusySupervisor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InstantActionHandler<UrgentTouch> for BusySupervisor {
+        async fn handle(&mut self, _: UrgentTouch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<ShortLived> for BusySupervisor {
+        async fn handle(
+            &mut self,
+            _id: IdOf<ShortLived>,
+            _reason: TerminationReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct ShortLived;
+
+    impl Actor for ShortLived {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<BusySupervisor> for ShortLived {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<BusySupervisor> for ShortLived {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_done_bypasses_hp_capacity() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(BusySupervisor);
+        address.instant(UrgentTouch)?;
+        assert!(address.instant(UrgentTouch).is_err());
+        // The supervisor terminates only if it received `Done` of the child.
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    #[derive(Default)]
+    struct JobParent {
+        child: Option<Address<JobChild>>,
+    }
+
+    impl Actor for JobParent {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for JobParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.child = Some(ctx.spawn_actor(JobChild, ()));
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for JobParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<JobChild> for JobParent {
+        async fn handle(
+            &mut self,
+            _id: IdOf<JobChild>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    struct GetJobChild;
+
+    impl Interaction for GetJobChild {
+        type Output = Address<JobChild>;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<GetJobChild> for JobParent {
+        async fn handle(
+            &mut self,
+            _: GetJobChild,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Address<JobChild>, Error> {
+            self.child.clone().ok_or_else(|| Error::msg("no child"))
+        }
+    }
+
+    struct JobChild;
+
+    impl Actor for JobChild {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<JobParent> for JobChild {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<JobParent> for JobChild {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InteractionHandler<LongJob> for JobChild {
+        async fn handle(&mut self, _: LongJob, ctx: &mut Context<Self>) -> Result<(), Error> {
+            for _ in 0..500 {
+                ctx.not_interrupted()?;
+                sleep(Duration::from_millis(10)).await;
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_interaction_by_parent() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let parent = System::spawn(JobParent::default());
+        let child = parent.interact(GetJobChild).recv().await?;
+        let job = tokio::spawn(child.interact(LongJob).recv());
+        sleep(Duration::from_millis(50)).await;
+        System::interrupt(&parent)?;
+        let err = timeout(Duration::from_secs(1), job).await??.unwrap_err();
+        assert!(matches!(err, InteractError::Cancelled));
+        timeout(Duration::from_secs(1), parent.join()).await?;
+        assert!(!child.is_connected());
+        Ok(())
+    }
+
+    struct Crashing {
+        starts: Arc<AtomicUsize>,
+        panic_on_interrupt: bool,
+    }
+
+    impl Actor for Crashing {
+        type GroupBy = ();
+
+        fn catch_panics(&self) -> bool {
+            true
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<CrashingParent> for Crashing {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<CrashingParent> for Crashing {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            if self.panic_on_interrupt {
+                panic!("crashed on interrupt");
+            }
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct Crash;
+
+    impl Action for Crash {}
+
+    #[async_trait]
+    impl ActionHandler<Crash> for Crashing {
+        async fn handle(&mut self, _: Crash, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Err(Error::msg("crashed"))
+        }
+    }
+
+    struct IsTerminating;
+
+    impl Interaction for IsTerminating {
+        type Output = bool;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<IsTerminating> for Crashing {
+        async fn handle(
+            &mut self,
+            _: IsTerminating,
+            ctx: &mut Context<Self>,
+        ) -> Result<bool, Error> {
+            Ok(ctx.is_terminating())
+        }
+    }
+
+    #[derive(Default)]
+    struct CrashingParent {
+        starts: Arc<AtomicUsize>,
+        child: Option<Address<Crashing>>,
+        reasons: Arc<Mutex<Vec<TerminationReason>>>,
+        panic_on_interrupt: bool,
+    }
+
+    impl Actor for CrashingParent {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for CrashingParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let starts = self.starts.clone();
+            let panic_on_interrupt = self.panic_on_interrupt;
+            let factory = move || Crashing {
+                starts: starts.clone(),
+                panic_on_interrupt,
+            };
+            let policy = RestartPolicy::OnError { max_retries: 1 };
+            let backoff = Backoff::Fixed(Duration::from_millis(10));
+            let child = ctx.spawn_actor_with_backoff(factory, policy, backoff, ());
+            self.child = Some(child);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for CrashingParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Crashing> for CrashingParent {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Crashing>,
+            reason: TerminationReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.reasons.lock().unwrap().push(reason);
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct GetCrashing;
+
+    impl Interaction for GetCrashing {
+        type Output = Address<Crashing>;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<GetCrashing> for CrashingParent {
+        async fn handle(
+            &mut self,
+            _: GetCrashing,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Address<Crashing>, Error> {
+            self.child.clone().ok_or_else(|| Error::msg("no child"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restart_after_handler_failure() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let parent = CrashingParent::default();
+        let starts = parent.starts.clone();
+        let reasons = parent.reasons.clone();
+        let address = System::spawn(parent);
+        let child = address.interact(GetCrashing).recv().await?;
+        child.act(Crash)?;
+        // The new instance has a fresh `Context` that is not terminating.
+        let terminating =
+            timeout(Duration::from_secs(1), child.interact(IsTerminating).recv()).await??;
+        assert!(!terminating);
+        assert_eq!(starts.load(Ordering::SeqCst), 2);
+        assert!(reasons.lock().unwrap().is_empty());
+        // No retries left.
+        child.act(Crash)?;
+        timeout(Duration::from_secs(1), address.join()).await?;
+        assert_eq!(starts.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            *reasons.lock().unwrap(),
+            vec![TerminationReason::Failed("crashed".into())]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_no_restart_after_interruption() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let parent = CrashingParent {
+            panic_on_interrupt: true,
+            ..Default::default()
+        };
+        let starts = parent.starts.clone();
+        let reasons = parent.reasons.clone();
+        let address = System::spawn(parent);
+        address.interact(GetCrashing).recv().await?;
+        // The child panics in the interruption handler, but isn't restarted.
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(1), address.join()).await?;
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            *reasons.lock().unwrap(),
+            vec![TerminationReason::Panicked("crashed on interrupt".into())]
+        );
+        Ok(())
+    }
+
+    struct Nesting {
+        starts: Arc<AtomicUsize>,
+    }
+
+    impl Actor for Nesting {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<NestingParent> for Nesting {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            ctx.spawn_actor(Leaf, ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<NestingParent> for Nesting {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Leaf> for Nesting {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Leaf>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Crash> for Nesting {
+        async fn handle(&mut self, _: Crash, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Err(Error::msg("crashed"))
+        }
+    }
+
+    struct Leaf;
+
+    impl Actor for Leaf {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<Nesting> for Leaf {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<Nesting> for Leaf {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct NestingParent {
+        starts: Arc<AtomicUsize>,
+    }
+
+    impl Actor for NestingParent {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for NestingParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let starts = self.starts.clone();
+            let factory = move || Nesting {
+                starts: starts.clone(),
+            };
+            let policy = RestartPolicy::OnError { max_retries: 1 };
+            let child = ctx.spawn_actor_with(factory, policy, ());
+            child.act(Crash)?;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for NestingParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Nesting> for NestingParent {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Nesting>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restart_with_children() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let parent = NestingParent::default();
+        let starts = parent.starts.clone();
+        let address = System::spawn(parent);
+        // Successful handlers of children notifications don't hide the failure.
+        let restarted = async {
+            while starts.load(Ordering::SeqCst) < 2 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        };
+        timeout(Duration::from_secs(1), restarted).await?;
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    struct HpParent {
+        child: Arc<Mutex<Option<Address<HpChild>>>>,
+    }
+
+    impl Actor for HpParent {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for HpParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let pair = AddressPair::new();
+            *self.child.lock().unwrap() = Some(pair.address().clone());
+            ctx.spawn_actor_with_addr(HpChild, pair, ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for HpParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<HpChild> for HpParent {
+        async fn handle(
+            &mut self,
+            _id: IdOf<HpChild>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    struct HpChild;
+
+    impl Actor for HpChild {
+        type GroupBy = ();
+
+        fn hp_capacity(&self) -> Option<usize> {
+            Some(1)
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<HpParent> for HpChild {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<HpParent> for HpChild {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InstantActionHandler<UrgentTouch> for HpChild {
+        async fn handle(&mut self, _: UrgentTouch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_with_addr_configured() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let child = Arc::new(Mutex::new(None));
+        let address = System::spawn(HpParent {
+            child: child.clone(),
+        });
+        address.flush().await?;
+        let child = child.lock().unwrap().take().unwrap();
+        // The child doesn't run until the test yields.
+        child.instant(UrgentTouch)?;
+        let err = child.instant(UrgentTouch).unwrap_err();
+        assert!(matches!(err, SendError::Full));
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    struct Unready {
+        ignore: bool,
+    }
+
+    impl Actor for Unready {
+        type GroupBy = ();
+
+        fn ignore_start_errors(&self) -> bool {
+            self.ignore
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<UnreadyParent> for Unready {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Err(Error::msg("not ready"))
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<UnreadyParent> for Unready {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct UnreadyParent {
+        eliminated: Option<oneshot::Sender<(TerminationReason, usize)>>,
+    }
+
+    impl Actor for UnreadyParent {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for UnreadyParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.spawn_actor(Unready { ignore: false }, ());
+            ctx.spawn_actor(Unready { ignore: true }, ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for UnreadyParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Unready> for UnreadyParent {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Unready>,
+            reason: TerminationReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            if let Some(eliminated) = self.eliminated.take() {
+                eliminated.send((reason, ctx.supervisor().len())).ok();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_start_eliminated() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let address = System::spawn(UnreadyParent {
+            eliminated: Some(tx),
+        });
+        let (reason, alive) = timeout(Duration::from_secs(5), rx).await??;
+        assert_eq!(reason, TerminationReason::Failed("not ready".into()));
+        // The child that ignores start errors keeps working.
+        assert_eq!(alive, 1);
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    struct Finalizing {
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl Actor for Finalizing {
+        type GroupBy = ();
+
+        async fn finalize(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            sleep(Duration::from_millis(20)).await;
+            self.log.lock().unwrap().push("finalized");
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<FinalizingParent> for Finalizing {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<FinalizingParent> for Finalizing {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct FinalizingParent {
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Actor for FinalizingParent {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for FinalizingParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let child = Finalizing {
+                log: self.log.clone(),
+            };
+            ctx.spawn_actor(child, ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for FinalizingParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Finalizing> for FinalizingParent {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Finalizing>,
+            _reason: TerminationReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.log.lock().unwrap().push("eliminated");
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_finalize_before_done() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let address = System::spawn(FinalizingParent { log: log.clone() });
+        timeout(Duration::from_secs(5), address.join()).await?;
+        // The supervisor is notified only when the asynchronous cleanup completed.
+        assert_eq!(*log.lock().unwrap(), vec!["finalized", "eliminated"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_slow_handler_threshold() -> Result<(), Error> {
+        use super::HandlerTiming;
+        let threshold = Duration::from_millis(20);
+        let timing = HandlerTiming::start(threshold);
+        assert_eq!(timing.slow(), None);
+        sleep(Duration::from_millis(30)).await;
+        let elapsed = timing.slow().expect("the handler is slow");
+        assert!(elapsed > threshold);
+        // Handlers are not timed without the threshold.
+        assert_eq!(OrderedActor::default().slow_handler_threshold(), None);
+        Ok(())
+    }
+
+    #[cfg(feature = "tracing")]
+    mod spans {
+        use crate::fixtures::*;
+        use crate::prelude::*;
+        use anyhow::Error;
+        use async_trait::async_trait;
+        use std::fmt;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// Keeps the fields of every created span.
+        #[derive(Default, Clone)]
+        struct SpanRecorder {
+            spans: Arc<Mutex<Vec<Vec<String>>>>,
+        }
+
+        struct Fields<'a>(&'a mut Vec<String>);
+
+        impl Visit for Fields<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                self.0.push(format!("{}={:?}", field.name(), value));
+            }
+        }
+
+        impl Subscriber for SpanRecorder {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut fields = vec![format!("name={}", span.metadata().name())];
+                span.record(&mut Fields(&mut fields));
+                let mut spans = self.spans.lock().unwrap();
+                spans.push(fields);
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, span: &Id, values: &Record<'_>) {
+                let mut spans = self.spans.lock().unwrap();
+                let fields = &mut spans[span.into_u64() as usize - 1];
+                values.record(&mut Fields(fields));
+            }
+
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+            fn event(&self, _event: &Event<'_>) {}
+
+            fn enter(&self, _span: &Id) {}
+
+            fn exit(&self, _span: &Id) {}
+        }
+
+        struct Fail;
+
+        impl Action for Fail {}
+
+        #[async_trait]
+        impl ActionHandler<Fail> for OrderedActor {
+            async fn handle(&mut self, _: Fail, _ctx: &mut Context<Self>) -> Result<(), Error> {
+                Err(Error::msg("rejected"))
+            }
+        }
+
+        #[tokio::test]
+        async fn test_handler_spans() -> Result<(), Error> {
+            env_logger::try_init().ok();
+            let recorder = SpanRecorder::default();
+            // The current-thread runtime runs the actor with this subscriber.
+            let _guard = tracing::subscriber::set_default(recorder.clone());
+            let address = System::spawn(OrderedActor::default());
+            address.act(Seq(1))?;
+            address.act(Fail)?;
+            address.ask(GetSeen).await?;
+            System::interrupt(&address)?;
+            address.join().await;
+            let spans = recorder.spans.lock().unwrap();
+            let find = |message: &str| {
+                spans
+                    .iter()
+                    .find(|fields| fields.iter().any(|field| field.ends_with(message)))
+                    .cloned()
+                    .unwrap_or_default()
+            };
+            let seq = find("fixtures::Seq\"");
+            assert!(seq.contains(&"name=handle".to_string()));
+            assert!(seq.contains(&"queue=Normal".to_string()));
+            assert!(!seq.iter().any(|field| field.starts_with("error=")));
+            let fail = find("spans::Fail\"");
+            assert!(fail.contains(&"error=rejected".to_string()));
+            let interrupt = find("Interrupt<meio::system::System>\"");
+            assert!(interrupt.contains(&"queue=HighPriority".to_string()));
+            Ok(())
+        }
+    }
 }
@@ -38,6 +38,29 @@ pub async fn delay(duration: std::time::Duration) {
     }
 }
 
+/// Gives other tasks a turn to run on the same thread.
+pub async fn yield_now() {
+    #[cfg(not(feature = "wasm"))]
+    {
+        tokio::task::yield_now().await;
+    }
+    #[cfg(feature = "wasm")]
+    {
+        use futures::task::Poll;
+        let mut yielded = false;
+        futures::future::poll_fn(|cx| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+}
+
 pub use delay_queue::DelayQueue;
 
 #[cfg(not(feature = "wasm"))]
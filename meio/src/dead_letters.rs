@@ -0,0 +1,168 @@
+//! Contains the hook for messages that can't be delivered to actors.
+
+use crate::handlers::Action;
+use crate::ids::Id;
+use crate::linkage::ActionRecipient;
+use std::sync::Mutex;
+
+/// The notification about a message that wasn't delivered,
+/// because the `Actor` doesn't receive messages anymore.
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    /// `Id` of the `Actor` that had to receive the message.
+    pub target: Id,
+    /// The type name of the message.
+    pub message: &'static str,
+}
+
+impl Action for DeadLetter {}
+
+static RECIPIENT: Mutex<Option<Box<dyn ActionRecipient<DeadLetter>>>> = Mutex::new(None);
+
+pub(crate) fn set_recipient(recipient: Option<Box<dyn ActionRecipient<DeadLetter>>>) {
+    match RECIPIENT.lock() {
+        Ok(mut guard) => {
+            *guard = recipient;
+        }
+        Err(err) => {
+            log::error!("Can't set the recipient of dead letters: {}", err);
+        }
+    }
+}
+
+/// Reports about the message that can't be delivered.
+pub(crate) fn report(target: &Id, message: &'static str) {
+    log::trace!("Dead letter {} for {}", message, target);
+    // The recipient is cloned to not hold the lock during sending,
+    // because sending can report a dead letter too.
+    let recipient = RECIPIENT
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|recipient| recipient.dyn_clone()));
+    if let Some(mut recipient) = recipient {
+        // Don't report about dead letters for the dead letters recipient itself.
+        if recipient.id_ref() != target {
+            let letter = DeadLetter {
+                target: target.clone(),
+                message,
+            };
+            recipient.act(letter).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    struct DeadLettersCollector {
+        expected: Id,
+    }
+
+    impl Actor for DeadLettersCollector {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "DeadLettersCollector"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for DeadLettersCollector {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<DeadLetter> for DeadLettersCollector {
+        async fn handle(
+            &mut self,
+            letter: DeadLetter,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            if letter.target == self.expected {
+                assert!(letter.message.ends_with("Seq"));
+                ctx.shutdown();
+            }
+            Ok(())
+        }
+    }
+
+    /// The recipient of dead letters is global.
+    static DEAD_LETTERS_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    #[tokio::test]
+    async fn test_dead_letters() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let _lock = DEAD_LETTERS_LOCK.lock().await;
+        let AddressPair { joint, address } = AddressPair::<OrderedActor>::new();
+        let collector = DeadLettersCollector {
+            expected: address.id().into(),
+        };
+        let collector = System::spawn(collector);
+        System::set_dead_letters(collector.clone());
+        drop(joint);
+        assert!(address.act(Seq(1)).is_err());
+        timeout(Duration::from_secs(5), collector.join()).await?;
+        System::reset_dead_letters();
+        Ok(())
+    }
+
+    struct UndeliveredCollector {
+        expected: Id,
+    }
+
+    impl Actor for UndeliveredCollector {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for UndeliveredCollector {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<DeadLetter> for UndeliveredCollector {
+        async fn handle(
+            &mut self,
+            letter: DeadLetter,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            if letter.target == self.expected {
+                assert!(letter.message.contains("Interact<"));
+                ctx.shutdown();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_undelivered_interaction_dead_letter() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let _lock = DEAD_LETTERS_LOCK.lock().await;
+        let AddressPair { joint, address } = AddressPair::<OrderedActor>::new();
+        let collector = UndeliveredCollector {
+            expected: address.id().into(),
+        };
+        let collector = System::spawn(collector);
+        System::set_dead_letters(collector.clone());
+        drop(joint);
+        let err = address
+            .interact(LongJob)
+            .recv_or_return()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, InteractFailure::Undelivered { .. }));
+        timeout(Duration::from_secs(5), collector.join()).await?;
+        System::reset_dead_letters();
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+//! Contains the window of recent keys to drop duplicated messages.
+
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// The window of recently handled messages used by the `Actor`
+/// to skip duplicates. Only actions with `Action::dedupe_key` are tracked.
+///
+/// A key is remembered until `max_messages` newer keys were seen or
+/// it's older than `max_age`. Every remembered key costs about 64 bytes,
+/// that's why the cache of an `Actor` never takes more than
+/// `max_messages * 64` bytes.
+#[derive(Debug, Clone)]
+pub struct DedupeWindow {
+    /// How many recent keys are remembered.
+    pub max_messages: usize,
+    /// How long a key is remembered.
+    pub max_age: Duration,
+}
+
+type Key = (&'static str, u64);
+
+/// Keys of recently handled messages.
+pub(crate) struct DedupeCache {
+    window: DedupeWindow,
+    order: VecDeque<(Instant, Key)>,
+    keys: HashSet<Key>,
+}
+
+impl DedupeCache {
+    pub fn new(window: DedupeWindow) -> Self {
+        Self {
+            window,
+            order: VecDeque::new(),
+            keys: HashSet::new(),
+        }
+    }
+
+    /// Remembers the key and returns `false` if it was seen in the window.
+    pub fn insert(&mut self, message: &'static str, key: u64) -> bool {
+        let now = Instant::now();
+        while let Some((seen, old)) = self.order.front() {
+            if now.duration_since(*seen) > self.window.max_age {
+                self.keys.remove(old);
+                self.order.pop_front();
+            } else {
+                break;
+            }
+        }
+        if self.window.max_messages == 0 {
+            return true;
+        }
+        let key = (message, key);
+        if !self.keys.insert(key) {
+            return false;
+        }
+        self.order.push_back((now, key));
+        // The oldest key is evicted only when a newer one takes its place.
+        while self.order.len() > self.window.max_messages {
+            if let Some((_, old)) = self.order.pop_front() {
+                self.keys.remove(&old);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    struct Command(u64);
+
+    impl Action for Command {
+        fn dedupe_key(&self) -> Option<u64> {
+            Some(self.0)
+        }
+    }
+
+    struct Deduper {
+        seen: Arc<Mutex<Vec<u64>>>,
+    }
+
+    impl Actor for Deduper {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Deduper"
+        }
+
+        fn dedupe_window(&self) -> Option<DedupeWindow> {
+            Some(DedupeWindow {
+                max_messages: 2,
+                max_age: Duration::from_secs(3600),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Deduper {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Deduper {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Command> for Deduper {
+        async fn handle(&mut self, msg: Command, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.seen.lock().unwrap().push(msg.0);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Published> for Deduper {
+        async fn handle(&mut self, msg: Published, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.seen.lock().unwrap().push(msg.0 as u64 * 100);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_window() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let address = System::spawn(Deduper { seen: seen.clone() });
+        for key in [1, 1, 2, 3, 1] {
+            address.act(Command(key))?;
+        }
+        // Actions without a key are never skipped.
+        address.act(Published(1))?;
+        address.act(Published(1))?;
+        address.flush().await?;
+        // The first key left the window after two newer keys.
+        assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3, 1, 100, 100]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    #[test]
+    fn test_dedupe_window_boundary() {
+        let window = DedupeWindow {
+            max_messages: 3,
+            max_age: Duration::from_secs(60),
+        };
+        let mut cache = crate::dedupe::DedupeCache::new(window);
+        for key in 1..=3 {
+            assert!(cache.insert("Command", key));
+        }
+        // The window is full, but the oldest key is still remembered.
+        assert!(!cache.insert("Command", 1));
+        assert!(cache.insert("Command", 4));
+        assert!(cache.insert("Command", 1));
+        assert!(!cache.insert("Command", 4));
+    }
+}
@@ -0,0 +1,555 @@
+//! Contains the durable mailbox that keeps actions in a write-ahead log.
+//!
+//! Actions sent by `Address::act_durable` are appended to the log before
+//! they are sent to the `Actor` and removed from it when the handler
+//! completed successfully. The `Actor` calls `Context::replay_durable`
+//! (usually in the `StartedBy` handler) to receive actions that were not
+//! handled before a crash or a restart of the process.
+//!
+//! The delivery is **at-least-once**: an action is replayed if the process
+//! stopped after the handler completed, but before the completion was
+//! written, and also if the handler failed. Handlers have to be idempotent
+//! or use `Action::dedupe_key` to skip duplicates.
+//!
+//! Every durable action costs two writes with `fsync`: the record with the
+//! JSON of the action (plus ~60 bytes of the envelope) before sending and
+//! a ~20 bytes completion record after handling. Throughput is limited by
+//! the latency of `fsync` of the disk. Writes are performed on the blocking
+//! thread pool to not stall the workers of the runtime. The log is truncated
+//! when all the actions were handled and compacted when opened.
+
+use crate::actor_runtime::{Actor, Context};
+use crate::handlers::{Action, ActionHandler};
+use crate::linkage::Address;
+use anyhow::Error;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// An `Action` that can be kept in the `DurableMailbox`.
+pub trait DurableAction: Action + Serialize + DeserializeOwned {
+    /// The name of the action in the log.
+    ///
+    /// It has to be unique among durable actions of an `Actor` and
+    /// must not be changed to replay records written by older versions.
+    const KIND: &'static str;
+}
+
+#[derive(Serialize, Deserialize)]
+enum Record {
+    Append {
+        seq: u64,
+        kind: String,
+        payload: Value,
+    },
+    Done {
+        seq: u64,
+    },
+}
+
+struct Journal {
+    file: File,
+    next_seq: u64,
+    /// Sequence numbers of actions that weren't handled yet.
+    pending: HashSet<u64>,
+    /// Actions loaded from the log that weren't replayed yet.
+    recovered: BTreeMap<u64, (String, Value)>,
+}
+
+/// The write-ahead log of durable actions of an `Actor`.
+///
+/// The `Actor` opts in by returning it from `Actor::durable_mailbox`.
+#[derive(Clone)]
+pub struct DurableMailbox {
+    path: Arc<PathBuf>,
+    journal: Arc<Mutex<Journal>>,
+}
+
+impl DurableMailbox {
+    /// Opens the log at the `path` or creates a new one.
+    ///
+    /// Unhandled actions of the log are kept for `Context::replay_durable`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut recovered = BTreeMap::new();
+        let mut next_seq = 0;
+        if path.exists() {
+            let reader = BufReader::new(File::open(&path)?);
+            for line in reader.lines() {
+                let line = line?;
+                // The last line can be broken if the process crashed while writing.
+                match serde_json::from_str(&line) {
+                    Ok(Record::Append { seq, kind, payload }) => {
+                        next_seq = next_seq.max(seq + 1);
+                        recovered.insert(seq, (kind, payload));
+                    }
+                    Ok(Record::Done { seq }) => {
+                        recovered.remove(&seq);
+                    }
+                    Err(err) => {
+                        log::warn!(
+                            "Skip a broken record of the log {}: {}",
+                            path.display(),
+                            err
+                        );
+                    }
+                }
+            }
+        }
+        Self::compact(&path, &recovered)?;
+        let file = OpenOptions::new().append(true).create(true).open(&path)?;
+        let journal = Journal {
+            file,
+            next_seq,
+            pending: recovered.keys().cloned().collect(),
+            recovered,
+        };
+        Ok(Self {
+            path: Arc::new(path),
+            journal: Arc::new(Mutex::new(journal)),
+        })
+    }
+
+    /// Rewrites the log with unhandled actions only.
+    fn compact(path: &Path, recovered: &BTreeMap<u64, (String, Value)>) -> Result<(), Error> {
+        let tmp_path = path.with_extension("compact");
+        let mut tmp = File::create(&tmp_path)?;
+        for (seq, (kind, payload)) in recovered {
+            let record = Record::Append {
+                seq: *seq,
+                kind: kind.clone(),
+                payload: payload.clone(),
+            };
+            serde_json::to_writer(&mut tmp, &record)?;
+            tmp.write_all(b"\n")?;
+        }
+        tmp.sync_data()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// The path of the log.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The number of actions that weren't handled yet.
+    pub fn pending(&self) -> usize {
+        self.journal().pending.len()
+    }
+
+    fn journal(&self) -> MutexGuard<'_, Journal> {
+        lock(&self.journal)
+    }
+
+    /// Runs the blocking I/O with the journal on the blocking thread pool.
+    async fn blocking<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Journal) -> Result<T, Error> + Send + 'static,
+        T: Send + 'static,
+    {
+        let journal = self.journal.clone();
+        tokio::task::spawn_blocking(move || f(&mut lock(&journal))).await?
+    }
+
+    async fn append<I: DurableAction>(&self, msg: &I) -> Result<u64, Error> {
+        let payload = serde_json::to_value(msg)?;
+        let kind = I::KIND.to_string();
+        self.blocking(move |journal| {
+            let seq = journal.next_seq;
+            journal.write(&Record::Append { seq, kind, payload })?;
+            journal.next_seq += 1;
+            journal.pending.insert(seq);
+            Ok(seq)
+        })
+        .await
+    }
+
+    async fn complete(&self, seq: u64) -> Result<(), Error> {
+        self.blocking(move |journal| {
+            if journal.pending.remove(&seq) {
+                if journal.pending.is_empty() {
+                    journal.file.set_len(0)?;
+                    journal.file.sync_data()?;
+                } else {
+                    journal.write(&Record::Done { seq })?;
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    fn take_recovered<I: DurableAction>(&self) -> Vec<(u64, Value)> {
+        let kind = I::KIND;
+        let mut journal = self.journal();
+        let seqs: Vec<u64> = journal
+            .recovered
+            .iter()
+            .filter(|(_, (record_kind, _))| record_kind == kind)
+            .map(|(seq, _)| *seq)
+            .collect();
+        seqs.into_iter()
+            .filter_map(|seq| {
+                journal
+                    .recovered
+                    .remove(&seq)
+                    .map(|(_, payload)| (seq, payload))
+            })
+            .collect()
+    }
+
+    /// Returns actions that weren't replayed to recovered ones.
+    fn restore<I: DurableAction>(&self, records: impl IntoIterator<Item = (u64, Value)>) {
+        let kind = I::KIND;
+        let mut journal = self.journal();
+        for (seq, payload) in records {
+            journal.recovered.insert(seq, (kind.to_string(), payload));
+        }
+    }
+}
+
+fn lock(journal: &Mutex<Journal>) -> MutexGuard<'_, Journal> {
+    journal.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+impl Journal {
+    fn write(&mut self, record: &Record) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+}
+
+/// The durable action with its position in the log.
+pub struct Durable<I> {
+    seq: u64,
+    msg: I,
+}
+
+impl<I: Action> Action for Durable<I> {
+    fn dedupe_key(&self) -> Option<u64> {
+        self.msg.dedupe_key()
+    }
+}
+
+#[async_trait]
+impl<A, I> ActionHandler<Durable<I>> for A
+where
+    A: ActionHandler<I>,
+    I: Action,
+{
+    async fn handle(&mut self, durable: Durable<I>, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ActionHandler::<I>::handle(self, durable.msg, ctx).await?;
+        if let Some(mailbox) = ctx.address().durable_mailbox().cloned() {
+            mailbox.complete(durable.seq).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<A: Actor> Address<A> {
+    /// Appends the action to the `DurableMailbox` of the `Actor` and sends it.
+    ///
+    /// Fails if the `Actor` has no durable mailbox. If the action can't be
+    /// sent, its record is completed to not replay it after a restart.
+    pub async fn act_durable<I>(&self, msg: I) -> Result<(), Error>
+    where
+        I: DurableAction,
+        A: ActionHandler<I>,
+    {
+        let mailbox = self
+            .durable_mailbox()
+            .ok_or_else(|| Error::msg("the actor has no durable mailbox"))?;
+        let seq = mailbox.append(&msg).await?;
+        if let Err(err) = self.act::<Durable<I>>(Durable { seq, msg }) {
+            mailbox.complete(seq).await?;
+            return Err(err.into());
+        }
+        Ok(())
+    }
+}
+
+impl<A: Actor> Context<A> {
+    /// Sends to the `Actor` unhandled actions of the type `I` loaded
+    /// from the `DurableMailbox` and returns how many of them were sent.
+    ///
+    /// Actions are replayed once after the log is opened. If the mailbox
+    /// of the `Actor` is bounded and gets full, the rest of actions is kept
+    /// and sent by the next call (for example, after handling some of them).
+    pub fn replay_durable<I>(&mut self) -> Result<usize, Error>
+    where
+        I: DurableAction,
+        A: ActionHandler<I>,
+    {
+        let address = self.address().clone();
+        let mailbox = match address.durable_mailbox() {
+            Some(mailbox) => mailbox,
+            None => return Ok(0),
+        };
+        let mut recovered = mailbox.take_recovered::<I>().into_iter();
+        let mut replayed = 0;
+        while let Some((seq, payload)) = recovered.next() {
+            let msg = match I::deserialize(&payload) {
+                Ok(msg) => msg,
+                Err(err) => {
+                    let target = std::any::type_name::<A>();
+                    log::error!(target: target, "Can't restore a durable action #{}: {}", seq, err);
+                    // The broken record is dropped to not replay it forever.
+                    let mailbox = mailbox.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = mailbox.complete(seq).await {
+                            log::error!(target: target, "Can't complete a durable action #{}: {}", seq, err);
+                        }
+                    });
+                    continue;
+                }
+            };
+            if let Err(err) = address.try_act::<Durable<I>>(Durable { seq, msg }) {
+                mailbox.restore::<I>(std::iter::once((seq, payload)).chain(recovered));
+                if err.is_full() {
+                    break;
+                }
+                return Err(err.reason().into());
+            }
+            replayed += 1;
+        }
+        Ok(replayed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct DurableJob(u32);
+
+    impl Action for DurableJob {}
+
+    impl DurableAction for DurableJob {
+        const KIND: &'static str = "DurableJob";
+    }
+
+    struct JobQueue {
+        mailbox: DurableMailbox,
+        capacity: Option<usize>,
+        failing: Option<u32>,
+        handled: Vec<u32>,
+    }
+
+    impl Actor for JobQueue {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "JobQueue"
+        }
+
+        fn mailbox_kind(&self) -> MailboxKind {
+            match self.capacity {
+                Some(capacity) => MailboxKind::Bounded(capacity),
+                None => MailboxKind::Unbounded,
+            }
+        }
+
+        fn durable_mailbox(&self) -> Option<DurableMailbox> {
+            Some(self.mailbox.clone())
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for JobQueue {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.replay_durable::<DurableJob>()?;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for JobQueue {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<DurableJob> for JobQueue {
+        async fn handle(&mut self, job: DurableJob, ctx: &mut Context<Self>) -> Result<(), Error> {
+            if self.failing == Some(job.0) {
+                return Err(Error::msg("the job failed"));
+            }
+            self.handled.push(job.0);
+            // Actions that didn't fit the bounded mailbox.
+            ctx.replay_durable::<DurableJob>()?;
+            Ok(())
+        }
+    }
+
+    struct GetHandled;
+
+    impl Interaction for GetHandled {
+        type Output = Vec<u32>;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<GetHandled> for JobQueue {
+        async fn handle(
+            &mut self,
+            _: GetHandled,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Vec<u32>, Error> {
+            Ok(self.handled.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_durable_mailbox() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let path = std::env::temp_dir().join(format!("meio-durable-{}.log", Id::unique()));
+        let mailbox = DurableMailbox::open(&path)?;
+        let address = System::spawn(JobQueue {
+            mailbox: mailbox.clone(),
+            capacity: None,
+            failing: Some(2),
+            handled: Vec::new(),
+        });
+        address.act_durable(DurableJob(1)).await?;
+        address.act_durable(DurableJob(2)).await?;
+        let handled = address.interact(GetHandled).recv().await?;
+        assert_eq!(handled, vec![1]);
+        assert_eq!(mailbox.pending(), 1);
+        System::interrupt(&address)?;
+        address.join().await;
+        drop(mailbox);
+
+        // The failed job is replayed after the restart.
+        let mailbox = DurableMailbox::open(&path)?;
+        assert_eq!(mailbox.pending(), 1);
+        let address = System::spawn_ready(JobQueue {
+            mailbox: mailbox.clone(),
+            capacity: None,
+            failing: None,
+            handled: Vec::new(),
+        })
+        .await?;
+        let handled = address.interact(GetHandled).recv().await?;
+        assert_eq!(handled, vec![2]);
+        assert_eq!(mailbox.pending(), 0);
+        System::interrupt(&address)?;
+        address.join().await;
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_durable_rollback() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let path = std::env::temp_dir().join(format!("meio-durable-{}.log", Id::unique()));
+        let mailbox = DurableMailbox::open(&path)?;
+        let address = System::spawn(JobQueue {
+            mailbox: mailbox.clone(),
+            capacity: None,
+            failing: None,
+            handled: Vec::new(),
+        });
+        System::interrupt(&address)?;
+        address.clone().join().await;
+        assert!(address.act_durable(DurableJob(1)).await.is_err());
+        // The record of the undelivered action is not replayed.
+        assert_eq!(mailbox.pending(), 0);
+        drop(mailbox);
+        assert_eq!(DurableMailbox::open(&path)?.pending(), 0);
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    /// The action of an older version that was written with the same kind.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct LegacyJob(String);
+
+    impl Action for LegacyJob {}
+
+    impl DurableAction for LegacyJob {
+        const KIND: &'static str = "DurableJob";
+    }
+
+    #[tokio::test]
+    async fn test_durable_broken_record() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let path = std::env::temp_dir().join(format!("meio-durable-{}.log", Id::unique()));
+        let mailbox = DurableMailbox::open(&path)?;
+        mailbox.append(&LegacyJob("legacy".into())).await?;
+        mailbox.append(&DurableJob(1)).await?;
+        drop(mailbox);
+
+        let mailbox = DurableMailbox::open(&path)?;
+        let address = System::spawn_ready(JobQueue {
+            mailbox: mailbox.clone(),
+            capacity: None,
+            failing: None,
+            handled: Vec::new(),
+        })
+        .await?;
+        // The record that can't be restored is completed too.
+        let replayed = async {
+            while mailbox.pending() > 0 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        };
+        timeout(Duration::from_secs(5), replayed).await?;
+        let handled = address.interact(GetHandled).recv().await?;
+        assert_eq!(handled, vec![1]);
+        System::interrupt(&address)?;
+        address.join().await;
+        drop(mailbox);
+        assert_eq!(DurableMailbox::open(&path)?.pending(), 0);
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_durable_replay_bounded() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let path = std::env::temp_dir().join(format!("meio-durable-{}.log", Id::unique()));
+        let mailbox = DurableMailbox::open(&path)?;
+        for job in 1..=3 {
+            mailbox.append(&DurableJob(job)).await?;
+        }
+        drop(mailbox);
+
+        let mailbox = DurableMailbox::open(&path)?;
+        let address = System::spawn_ready(JobQueue {
+            mailbox: mailbox.clone(),
+            capacity: Some(1),
+            failing: None,
+            handled: Vec::new(),
+        })
+        .await?;
+        // Only one action fits the mailbox, the rest is replayed by handlers.
+        let replayed = async {
+            while mailbox.pending() > 0 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        };
+        timeout(Duration::from_secs(5), replayed).await?;
+        let handled = address.interact(GetHandled).recv().await?;
+        assert_eq!(handled, vec![1, 2, 3]);
+        System::interrupt(&address)?;
+        address.join().await;
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+//! Actors and messages shared by tests of different modules.
+
+use crate::prelude::*;
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+#[derive(Default)]
+pub(crate) struct OrderedActor {
+    pub(crate) seen: Vec<u8>,
+    pub(crate) rendezvous: bool,
+}
+
+impl Actor for OrderedActor {
+    type GroupBy = ();
+
+    fn log_target(&self) -> &str {
+        "OrderedActor"
+    }
+
+    fn mailbox_kind(&self) -> MailboxKind {
+        if self.rendezvous {
+            MailboxKind::Rendezvous
+        } else {
+            MailboxKind::Unbounded
+        }
+    }
+}
+
+#[async_trait]
+impl StartedBy<System> for OrderedActor {
+    async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl InterruptedBy<System> for OrderedActor {
+    async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ctx.shutdown();
+        Ok(())
+    }
+}
+
+pub(crate) struct Seq(pub(crate) u8);
+
+impl Action for Seq {}
+
+#[async_trait]
+impl ActionHandler<Seq> for OrderedActor {
+    async fn handle(&mut self, msg: Seq, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        self.seen.push(msg.0);
+        Ok(())
+    }
+}
+
+pub(crate) struct GetSeen(pub(crate) oneshot::Sender<Vec<u8>>);
+
+impl Action for GetSeen {}
+
+#[async_trait]
+impl ActionHandler<GetSeen> for OrderedActor {
+    async fn handle(&mut self, msg: GetSeen, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        msg.0.send(self.seen.clone()).ok();
+        Ok(())
+    }
+}
+
+pub(crate) struct SlowQuery(pub(crate) Duration);
+
+impl Interaction for SlowQuery {
+    type Output = u8;
+}
+
+#[async_trait]
+impl InteractionHandler<SlowQuery> for OrderedActor {
+    async fn handle(&mut self, msg: SlowQuery, _ctx: &mut Context<Self>) -> Result<u8, Error> {
+        sleep(msg.0).await;
+        Ok(7)
+    }
+}
+
+pub(crate) struct Urgent;
+
+impl InstantAction for Urgent {}
+
+pub(crate) struct Regular;
+
+impl Action for Regular {}
+
+pub(crate) struct FairActor {
+    pub(crate) handled: Vec<&'static str>,
+    pub(crate) done: Option<oneshot::Sender<Vec<&'static str>>>,
+}
+
+impl Actor for FairActor {
+    type GroupBy = ();
+
+    fn log_target(&self) -> &str {
+        "FairActor"
+    }
+
+    fn hp_burst_limit(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
+#[async_trait]
+impl StartedBy<System> for FairActor {
+    async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ctx.address().act(Regular)?;
+        for _ in 0..10 {
+            ctx.address().instant(Urgent)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl InterruptedBy<System> for FairActor {
+    async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ctx.shutdown();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl InstantActionHandler<Urgent> for FairActor {
+    async fn handle(&mut self, _msg: Urgent, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        self.handled.push("urgent");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ActionHandler<Regular> for FairActor {
+    async fn handle(&mut self, _msg: Regular, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        self.handled.push("regular");
+        if let Some(tx) = self.done.take() {
+            tx.send(self.handled.clone()).ok();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Published(pub(crate) u8);
+
+impl Action for Published {}
+
+#[async_trait]
+impl ActionHandler<Published> for OrderedActor {
+    async fn handle(&mut self, msg: Published, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        self.seen.push(msg.0);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Stopper {
+    pub(crate) pinged: Arc<AtomicBool>,
+}
+
+impl Actor for Stopper {
+    type GroupBy = ();
+
+    fn log_target(&self) -> &str {
+        "Stopper"
+    }
+}
+
+#[async_trait]
+impl StartedBy<System> for Stopper {
+    async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl InterruptedBy<System> for Stopper {
+    async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ctx.shutdown();
+        Ok(())
+    }
+}
+
+pub(crate) struct StopLater;
+
+impl Action for StopLater {}
+
+#[async_trait]
+impl ActionHandler<StopLater> for Stopper {
+    async fn handle(&mut self, _: StopLater, ctx: &mut Context<Self>) -> Result<(), Error> {
+        sleep(Duration::from_millis(50)).await;
+        ctx.stop();
+        Ok(())
+    }
+}
+
+pub(crate) struct Bump;
+
+impl Action for Bump {}
+
+#[derive(Clone)]
+pub(crate) struct Item(pub(crate) u32);
+
+impl Action for Item {}
+
+pub(crate) struct LongJob;
+
+impl Interaction for LongJob {
+    type Output = ();
+}
+
+#[async_trait]
+impl InteractionHandler<LongJob> for OrderedActor {
+    async fn handle(&mut self, _: LongJob, ctx: &mut Context<Self>) -> Result<(), Error> {
+        for _ in 0..500 {
+            ctx.not_interrupted()?;
+            sleep(Duration::from_millis(10)).await;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Session;
+
+impl Actor for Session {
+    type GroupBy = ();
+
+    fn log_target(&self) -> &str {
+        "Session"
+    }
+
+    fn idle_timeout(&self) -> Option<Duration> {
+        Some(Duration::from_millis(100))
+    }
+}
+
+#[async_trait]
+impl StartedBy<System> for Session {
+    async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl InterruptedBy<System> for Session {
+    async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ctx.shutdown();
+        Ok(())
+    }
+}
+
+pub(crate) struct Touch;
+
+impl Action for Touch {}
+
+#[async_trait]
+impl ActionHandler<Touch> for Session {
+    async fn handle(&mut self, _: Touch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+pub(crate) struct UrgentTouch;
+
+impl InstantAction for UrgentTouch {}
+
+#[async_trait]
+impl InstantActionHandler<UrgentTouch> for Session {
+    async fn handle(&mut self, _: UrgentTouch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        Ok(())
+    }
+}
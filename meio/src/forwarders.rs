@@ -1,10 +1,15 @@
 use crate::actor_runtime::Context;
-use crate::handlers::{Consumer, InstantAction, InstantActionHandler, StreamAcceptor, StreamItem};
+use crate::handlers::{
+    Action, Consumer, InstantAction, InstantActionHandler, Prefetched, StreamAcceptor, StreamEnded,
+    StreamItem,
+};
 use crate::linkage::ActionRecipient;
 use crate::lite_runtime::{LiteTask, Tag};
 use anyhow::Error;
 use async_trait::async_trait;
 use futures::{Stream, StreamExt};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 /// This worker receives items from a stream and send them as actions
 /// into an `Actor`.
@@ -42,10 +47,75 @@ where
     async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
         while let Some(item) = self.stream.next().await {
             let action = StreamItem::Item(item);
-            self.recipient.act(action)?;
+            self.recipient.act_wait(action).await?;
         }
         let action = StreamItem::Done;
-        self.recipient.act(action)?;
+        self.recipient.act_wait(action).await?;
+        Ok(())
+    }
+}
+
+/// This worker receives actions from a stream and sends them into an `Actor`.
+///
+/// The stream is polled only when the mailbox has free capacity and,
+/// if the prefetch is set, when less than `prefetch` items are not handled yet.
+/// Optionally notifies the `Actor` with `StreamEnded` when the stream is over.
+pub(crate) struct ActionForwarder<S: Stream> {
+    stream: S,
+    recipient: Box<dyn ActionRecipient<Prefetched<S::Item>>>,
+    ended: Option<Box<dyn ActionRecipient<StreamEnded<S::Item>>>>,
+    prefetch: Option<Arc<Semaphore>>,
+}
+
+impl<S> ActionForwarder<S>
+where
+    S: Stream,
+    S::Item: Action,
+{
+    pub fn new(
+        stream: S,
+        recipient: impl ActionRecipient<Prefetched<S::Item>>,
+        ended: Option<Box<dyn ActionRecipient<StreamEnded<S::Item>>>>,
+        prefetch: Option<usize>,
+    ) -> Self {
+        Self {
+            stream,
+            recipient: Box::new(recipient),
+            ended,
+            prefetch: prefetch.map(|n| Arc::new(Semaphore::new(n.max(1)))),
+        }
+    }
+}
+
+#[async_trait]
+impl<S> LiteTask for ActionForwarder<S>
+where
+    S: Stream + Unpin + Send + 'static,
+    S::Item: Action,
+{
+    type Output = ();
+
+    fn log_target(&self) -> &str {
+        "ActionForwarder"
+    }
+
+    async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+        loop {
+            let permit = match &self.prefetch {
+                Some(semaphore) => Some(semaphore.clone().acquire_owned().await?),
+                None => None,
+            };
+            match self.stream.next().await {
+                Some(item) => {
+                    let action = Prefetched { item, permit };
+                    self.recipient.act_wait(action).await?;
+                }
+                None => break,
+            }
+        }
+        if let Some(mut ended) = self.ended {
+            ended.act_wait(StreamEnded::new()).await?;
+        }
         Ok(())
     }
 }
@@ -5,19 +5,24 @@
 //! incoming message.
 
 use crate::actor_runtime::{Actor, Context};
-use crate::forwarders::StreamForwarder;
+use crate::forwarders::{ActionForwarder, StreamForwarder};
 use crate::ids::{Id, IdOf};
-use crate::lifecycle;
-use crate::linkage::{ActionRecipient, Address};
+use crate::lifecycle::{self, InterruptReason, LifecycleStage, TerminationReason};
+use crate::linkage::{Address, HpPermit, InteractError, InteractFailure, TrySendError};
 use crate::lite_runtime::{LiteTask, Tag, TaskError};
 use anyhow::Error;
 use async_trait::async_trait;
 use futures::channel::oneshot;
-use futures::Stream;
-use std::convert::identity;
+use futures::future::{self, Either};
+use futures::{FutureExt, Stream, StreamExt};
 use std::fmt;
 use std::marker::PhantomData;
-use std::time::Instant;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{self, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, OwnedSemaphorePermit};
 
 /// `Parcel` packs any message for an `Actor`
 /// for further processing that can be done later.
@@ -26,6 +31,10 @@ use std::time::Instant;
 pub struct Parcel<A: Actor> {
     pub(crate) operation: Operation,
     pub(crate) envelope: Envelope<A>,
+    /// The slot of the bounded high-priority queue.
+    pub(crate) permit: Option<HpPermit>,
+    /// Carries an `Interrupt` that is counted as pending until it's received.
+    pub(crate) interrupt: bool,
 }
 
 impl<A: Actor> Parcel<A> {
@@ -47,6 +56,20 @@ impl<A: Actor> Parcel<A> {
         Self {
             operation,
             envelope: Envelope::instant(input),
+            permit: None,
+            interrupt: false,
+        }
+    }
+
+    /// Wraps the `Interrupt` to let the `Actor` know it's pending.
+    pub(crate) fn interrupt<S>(input: lifecycle::Interrupt<S>) -> Self
+    where
+        A: InstantActionHandler<lifecycle::Interrupt<S>>,
+        S: Actor,
+    {
+        Self {
+            interrupt: true,
+            ..Self::new(Operation::Forward, input)
         }
     }
 
@@ -54,6 +77,8 @@ impl<A: Actor> Parcel<A> {
         Self {
             operation: Operation::Forward,
             envelope,
+            permit: None,
+            interrupt: false,
         }
     }
 }
@@ -74,7 +99,17 @@ impl<A: Actor> Envelope<A> {
         actor: &mut A,
         ctx: &mut Context<A>,
     ) -> Result<(), Error> {
-        self.handler.handle(actor, ctx).await
+        Handler::handle(self.handler.as_mut(), actor, ctx).await
+    }
+
+    /// Returns the name of the message type inside.
+    pub(crate) fn message_type(&self) -> &'static str {
+        self.handler.message_type()
+    }
+
+    /// Returns the key of the message to skip duplicates.
+    pub(crate) fn dedupe_key(&self) -> Option<u64> {
+        self.handler.dedupe_key()
     }
 
     pub(crate) fn from_handler(handler: impl Handler<A>) -> Self {
@@ -83,6 +118,18 @@ impl<A: Actor> Envelope<A> {
         }
     }
 
+    /// Creates an `Envelope` that handles all the `Action`s in order.
+    pub(crate) fn batch<I>(inputs: Vec<I>) -> Self
+    where
+        A: ActionHandler<I>,
+        I: Action,
+    {
+        let handler = BatchHandlerImpl { inputs };
+        Self {
+            handler: Box::new(handler),
+        }
+    }
+
     // TODO: Is it posiible to use `handle` method directly and drop this one?
     /// Creates an `Envelope` for `Action`.
     pub(crate) fn new<I>(input: I) -> Self
@@ -130,20 +177,15 @@ pub(crate) enum Operation {
 }
 
 /// The priority of the sendig event.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum Priority {
     /// Normal priority queue
+    #[default]
     Normal,
     /// High priority queue
     Instant,
 }
 
-impl Default for Priority {
-    fn default() -> Self {
-        Self::Normal
-    }
-}
-
 /// Internal `Handler` type that used by `Actor`'s routine to execute
 /// `ActionHandler` or `InteractionHandler`.
 ///
@@ -155,6 +197,16 @@ pub trait Handler<A: Actor>: Send + 'static {
         Priority::Normal
     }
 
+    /// Returns the name of the message type that the handler processes.
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Returns the key of the message to skip duplicates.
+    fn dedupe_key(&self) -> Option<u64> {
+        None
+    }
+
     /// Main method that expects a mutable reference to `Actor` that
     /// will be used by implementations to handle messages.
     async fn handle(&mut self, actor: &mut A, _ctx: &mut Context<A>) -> Result<(), Error>;
@@ -162,7 +214,15 @@ pub trait Handler<A: Actor>: Send + 'static {
 
 /// `Action` type can be sent to an `Actor` that implements
 /// `ActionHandler` for that message type.
-pub trait Action: Send + 'static {}
+pub trait Action: Send + 'static {
+    /// The key that identifies the action for the `DedupeWindow` of an `Actor`.
+    ///
+    /// An `Actor` with the window skips actions of the same type with a key
+    /// that was seen recently. Actions without a key are never skipped.
+    fn dedupe_key(&self) -> Option<u64> {
+        None
+    }
+}
 
 /// Type of `Handler` to process incoming messages in one-shot style.
 #[async_trait]
@@ -185,12 +245,46 @@ where
         Priority::Normal
     }
 
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<I>()
+    }
+
+    fn dedupe_key(&self) -> Option<u64> {
+        self.input.as_ref().and_then(Action::dedupe_key)
+    }
+
     async fn handle(&mut self, actor: &mut A, ctx: &mut Context<A>) -> Result<(), Error> {
         let input = self.input.take().expect("action handler called twice");
         actor.handle(input, ctx).await
     }
 }
 
+struct BatchHandlerImpl<I> {
+    inputs: Vec<I>,
+}
+
+#[async_trait]
+impl<A, I> Handler<A> for BatchHandlerImpl<I>
+where
+    A: ActionHandler<I>,
+    I: Action,
+{
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<Vec<I>>()
+    }
+
+    async fn handle(&mut self, actor: &mut A, ctx: &mut Context<A>) -> Result<(), Error> {
+        let mut result = Ok(());
+        for input in self.inputs.drain(..) {
+            let res = actor.handle(input, ctx).await;
+            if result.is_ok() {
+                result = res;
+            }
+        }
+        result
+    }
+}
+
 /// The high-priority action.
 pub trait InstantAction: Send + 'static {}
 
@@ -215,6 +309,10 @@ where
         Priority::Instant
     }
 
+    fn message_type(&self) -> &'static str {
+        std::any::type_name::<I>()
+    }
+
     async fn handle(&mut self, actor: &mut A, ctx: &mut Context<A>) -> Result<(), Error> {
         let input = self
             .input
@@ -252,9 +350,11 @@ where
         // TODO: How to improve that???
         match send_res {
             Ok(()) => Ok(()),
-            Err(Ok(_)) => Err(Error::msg(
-                "Can't send the successful result of interaction",
-            )),
+            Err(Ok(_)) => {
+                // The caller stopped waiting (timed out), that's not a failure of the handler.
+                log::debug!(target: self.log_target(), "The result of interaction was discarded");
+                Ok(())
+            }
             Err(Err(err)) => Err(err),
         }
     }
@@ -283,20 +383,23 @@ impl<T: Interaction> Action for Interact<T> {}
 /// be blocked by `Address::interact` method call. To avoid this issue you have:
 ///
 /// 1. Use `ActionHandler` with `Interact` wrapper as a message to control manually
-/// when a response will be send to avoid blocking of an `Actor` that performs long running
-/// interaction.
+///    when a response will be send to avoid blocking of an `Actor` that performs long running
+///    interaction.
 ///
 /// 2. Use `interaction` method and send a response from a `LiteTask` to an `InteractionResponse`
-/// handler of a caller.
+///    handler of a caller.
 ///
 pub trait Interaction: Send + 'static {
     /// The result of the `Interaction` that will be returned by `InteractionHandler`.
     type Output: Send + 'static;
 }
 
+/// Delivers an interaction request or returns it back.
+type Deliver<I> = Box<dyn FnOnce(Interact<I>) -> Result<(), TrySendError<Interact<I>>> + Send>;
+
 /// Interaction task that can be awaited or aattached to a `Context`.
 pub struct InteractionTask<I: Interaction> {
-    recipient: Box<dyn ActionRecipient<Interact<I>>>,
+    deliver: Deliver<I>,
     request: I,
 }
 
@@ -305,22 +408,59 @@ impl<I: Interaction> InteractionTask<I> {
     where
         T: ActionHandler<Interact<I>>,
     {
+        let address = address.clone();
         Self {
-            recipient: Box::new(address.clone()),
+            deliver: Box::new(move |input| address.try_act(input)),
             request,
         }
     }
 
-    // TODO: impl `Future` instead of this
-    /// Receive a value
-    pub async fn recv(mut self) -> Result<I::Output, Error> {
+    /// Sends the request and returns the receiver of the response.
+    fn send(self) -> Result<oneshot::Receiver<Result<I::Output, Error>>, InteractFailure<I>> {
         let (responder, rx) = oneshot::channel();
         let input = Interact {
             request: self.request,
             responder,
         };
-        self.recipient.act(input)?;
-        rx.await.map_err(Error::from).and_then(identity)
+        (self.deliver)(input).map_err(|err| {
+            let reason = err.reason();
+            InteractFailure::Undelivered {
+                request: err.into_inner().request,
+                reason,
+            }
+        })?;
+        Ok(rx)
+    }
+
+    /// Receive a value, but returns the request back if it wasn't delivered.
+    ///
+    /// It lets the caller retry the same request with another `Actor`.
+    pub async fn recv_or_return(self) -> Result<I::Output, InteractFailure<I>> {
+        let rx = self.send()?;
+        match rx.await {
+            Ok(response) => response.map_err(|err| InteractError::from_handler(err).into()),
+            Err(_) => Err(InteractError::NoReply.into()),
+        }
+    }
+
+    // TODO: impl `Future` instead of this
+    /// Receive a value
+    pub async fn recv(self) -> Result<I::Output, InteractError> {
+        self.recv_or_return().await.map_err(InteractError::from)
+    }
+
+    /// Receive a value, but waits for it no longer than the `timeout`.
+    ///
+    /// Fails with `InteractError::Timeout` if the response wasn't received
+    /// in time. The late response is discarded.
+    pub async fn recv_timeout(self, timeout: Duration) -> Result<I::Output, InteractError> {
+        let rx = self.send().map_err(InteractError::from)?;
+        let delay = crate::compat::delay(timeout).boxed();
+        match future::select(rx, delay).await {
+            Either::Left((Ok(response), _)) => response.map_err(InteractError::from_handler),
+            Either::Left((Err(_), _)) => Err(InteractError::NoReply),
+            Either::Right(((), _)) => Err(InteractError::Timeout),
+        }
     }
 }
 
@@ -336,7 +476,7 @@ where
     }
 
     async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
-        self.recv().await
+        self.recv().await.map_err(Error::from)
     }
 }
 
@@ -355,9 +495,10 @@ where
 {
     async fn handle(
         &mut self,
-        _input: lifecycle::Awake<S>,
+        input: lifecycle::Awake<S>,
         ctx: &mut Context<Self>,
     ) -> Result<(), Error> {
+        ctx.spawned_by = input.supervisor;
         StartedBy::handle(self, ctx).await
     }
 }
@@ -370,6 +511,18 @@ pub trait InterruptedBy<A: Actor>: Actor {
     /// In many cases you should prefer to call `ctx.shutdown()` here.
     async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error>;
     // IMPORTANT! It has to be explicit! Don't add automatic implementation with shuttdown call.
+
+    /// Called with the `reason` of the interruption.
+    ///
+    /// Calls `handle` by default. The `reason` is passed to the children
+    /// when the `Actor` shuts down and is available with `ctx.interrupt_reason()`.
+    async fn handle_with_reason(
+        &mut self,
+        _reason: InterruptReason,
+        ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        InterruptedBy::handle(self, ctx).await
+    }
 }
 
 #[async_trait]
@@ -380,10 +533,16 @@ where
 {
     async fn handle(
         &mut self,
-        _input: lifecycle::Interrupt<S>,
+        input: lifecycle::Interrupt<S>,
         ctx: &mut Context<Self>,
     ) -> Result<(), Error> {
-        InterruptedBy::handle(self, ctx).await
+        ctx.address().lifecycle_event(LifecycleStage::Interrupted);
+        if ctx.termination_reason == TerminationReason::Normal {
+            ctx.termination_reason = TerminationReason::Interrupted;
+        }
+        ctx.lifetime_tracker
+            .set_interrupt_reason(input.reason.clone());
+        InterruptedBy::handle_with_reason(self, input.reason, ctx).await
     }
 }
 
@@ -428,7 +587,30 @@ where
 #[async_trait]
 pub trait Eliminated<A: Actor>: Actor {
     /// Called when the `Actor` finished.
-    async fn handle(&mut self, id: IdOf<A>, ctx: &mut Context<Self>) -> Result<(), Error>;
+    ///
+    /// The `reason` tells why the `Actor` has finished and helps
+    /// to decide whether to restart it or to escalate the failure.
+    async fn handle(
+        &mut self,
+        id: IdOf<A>,
+        reason: TerminationReason,
+        ctx: &mut Context<Self>,
+    ) -> Result<(), Error>;
+
+    /// Called when the `Actor` finished with the `error` that caused
+    /// its termination: the failed start, the panic or the error of
+    /// the last handled message. It's `None` if the `Actor` completed normally.
+    ///
+    /// Calls `handle` by default.
+    async fn handle_with_error(
+        &mut self,
+        id: IdOf<A>,
+        reason: TerminationReason,
+        _error: Option<Error>,
+        ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        Eliminated::handle(self, id, reason, ctx).await
+    }
 }
 
 #[async_trait]
@@ -442,7 +624,9 @@ where
         done: lifecycle::Done<C>,
         ctx: &mut Context<Self>,
     ) -> Result<(), Error> {
-        Eliminated::handle(self, done.id, ctx).await
+        let stage = LifecycleStage::ChildEliminated(done.id.clone().into());
+        ctx.address().lifecycle_event(stage);
+        Eliminated::handle_with_error(self, done.id, done.reason, done.error, ctx).await
     }
 }
 
@@ -471,10 +655,136 @@ where
         done: lifecycle::TaskDone<C, M>,
         ctx: &mut Context<Self>,
     ) -> Result<(), Error> {
+        let stage = LifecycleStage::ChildEliminated(done.id.clone().into());
+        ctx.address().lifecycle_event(stage);
         TaskEliminated::handle(self, done.id, done.tag, done.result, ctx).await
     }
 }
 
+/// The request to an `Actor` that responds with a stream of items.
+pub trait StreamInteraction: Send + 'static {
+    /// The item of the response stream.
+    type Item: Send + 'static;
+
+    /// How many items can be buffered before the producer waits for the caller.
+    fn capacity() -> usize {
+        16
+    }
+}
+
+/// The producing side of a response stream.
+///
+/// The stream of the caller ends when all clones of the responder are dropped.
+/// The `Actor` can keep it to push items later, then it's dropped with the `Actor`
+/// and the stream ends when the `Actor` terminated. A responder passed to a task
+/// is not tied to the `Actor`: the stream ends only when the task drops it.
+pub struct StreamResponder<T> {
+    tx: mpsc::Sender<T>,
+}
+
+impl<T> Clone for StreamResponder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+impl<T: Send + 'static> StreamResponder<T> {
+    /// Sends the item waiting for the free capacity, that's why a slow
+    /// caller slows down the producer. Fails if the caller dropped the stream.
+    pub async fn send(&self, item: T) -> Result<(), Error> {
+        self.tx
+            .send(item)
+            .await
+            .map_err(|_| Error::msg("the response stream was dropped"))
+    }
+
+    /// Sends the item if the buffer has free capacity.
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        self.tx.try_send(item).map_err(|err| match err {
+            mpsc::error::TrySendError::Full(item) => TrySendError::Full(item),
+            mpsc::error::TrySendError::Closed(item) => TrySendError::Closed(item),
+        })
+    }
+
+    /// Sends all items of the `stream`.
+    pub async fn forward<S>(&self, stream: S) -> Result<(), Error>
+    where
+        S: Stream<Item = T> + Send,
+    {
+        futures::pin_mut!(stream);
+        while let Some(item) = stream.next().await {
+            self.send(item).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the caller dropped the stream.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+/// The stream of items received from `Address::interact_stream`.
+pub struct ResponseStream<T> {
+    rx: mpsc::Receiver<T>,
+}
+
+impl<T> Stream for ResponseStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<T>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Creates a connected pair of a responder and a stream.
+pub(crate) fn response_stream<T>(capacity: usize) -> (StreamResponder<T>, ResponseStream<T>) {
+    let (tx, rx) = mpsc::channel(capacity.max(1));
+    (StreamResponder { tx }, ResponseStream { rx })
+}
+
+/// The wrapper for a stream request that keeps the responder.
+pub struct InteractStream<T: StreamInteraction> {
+    /// The request.
+    pub request: T,
+    /// The responder to push items to the caller.
+    pub responder: StreamResponder<T::Item>,
+}
+
+impl<T: StreamInteraction> Action for InteractStream<T> {}
+
+/// The handler of `StreamInteraction` requests.
+#[async_trait]
+pub trait StreamInteractionHandler<I: StreamInteraction>: Actor {
+    /// Handles the request and pushes items to the `responder`.
+    ///
+    /// Awaiting `StreamResponder::send` here blocks the `Actor` until the caller
+    /// takes items. Keep the responder or pass it to a task to produce items later.
+    async fn handle(
+        &mut self,
+        input: I,
+        responder: StreamResponder<I::Item>,
+        ctx: &mut Context<Self>,
+    ) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl<T, I> ActionHandler<InteractStream<I>> for T
+where
+    T: StreamInteractionHandler<I>,
+    I: StreamInteraction,
+{
+    async fn handle(
+        &mut self,
+        input: InteractStream<I>,
+        ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        StreamInteractionHandler::handle(self, input.request, input.responder, ctx).await
+    }
+}
+
 /// Independent interaction results listener. It necessary to avoid blocking.
 #[async_trait]
 pub trait InteractionDone<I: Interaction, M: Tag>: Actor {
@@ -494,7 +804,7 @@ pub trait InteractionDone<I: Interaction, M: Tag>: Actor {
         _ctx: &mut Context<Self>,
     ) -> Result<(), Error> {
         if let Some(err) = err.into_other() {
-            log::error!("Interaction failed: {}", err);
+            log::error!(target: self.log_target(), "Interaction failed: {}", err);
         }
         Ok(())
     }
@@ -528,6 +838,28 @@ pub(crate) enum StreamItem<T> {
 
 impl<T: Send + 'static> Action for StreamItem<T> {}
 
+/// The item of a stream that holds a place in the prefetch window
+/// until it's handled by an `Actor`.
+pub(crate) struct Prefetched<T> {
+    pub item: T,
+    pub permit: Option<OwnedSemaphorePermit>,
+}
+
+impl<T: Action> Action for Prefetched<T> {}
+
+#[async_trait]
+impl<T, I> ActionHandler<Prefetched<I>> for T
+where
+    T: ActionHandler<I>,
+    I: Action,
+{
+    async fn handle(&mut self, msg: Prefetched<I>, ctx: &mut Context<Self>) -> Result<(), Error> {
+        let res = ActionHandler::handle(self, msg.item, ctx).await;
+        drop(msg.permit);
+        res
+    }
+}
+
 /// Represents a capability to receive message from a `Stream`.
 #[async_trait]
 pub trait Consumer<T: 'static>: Actor {
@@ -541,14 +873,14 @@ pub trait Consumer<T: 'static>: Actor {
     ///
     /// Called after the last item in the stream only.
     async fn finished(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
-        log::info!("Stream finished");
+        log::info!(target: self.log_target(), "Stream finished");
         Ok(())
     }
 
     /// The stream was failed.
     async fn task_failed(&mut self, err: TaskError, _ctx: &mut Context<Self>) -> Result<(), Error> {
         if let Some(err) = err.into_other() {
-            log::error!("Consumer task failed: {}", err);
+            log::error!(target: self.log_target(), "Consumer task failed: {}", err);
         }
         Ok(())
     }
@@ -558,7 +890,7 @@ pub trait Consumer<T: 'static>: Actor {
     /// You should prefer to use `finished` instead of this, because it's service
     /// event with the high-priority and it can overtake ordinary stream items.
     async fn task_finished(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
-        log::info!("Stream task finished");
+        log::info!(target: self.log_target(), "Stream task finished");
         Ok(())
     }
 }
@@ -600,6 +932,51 @@ where
     }
 }
 
+/// The notification that a stream attached by `Context::attach_stream_notify`
+/// has ended.
+///
+/// It's sent after the last item of the stream with the ordinary priority.
+pub struct StreamEnded<T> {
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> StreamEnded<T> {
+    pub(crate) fn new() -> Self {
+        Self { _item: PhantomData }
+    }
+}
+
+impl<T> fmt::Debug for StreamEnded<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamEnded").finish()
+    }
+}
+
+impl<T: 'static> Action for StreamEnded<T> {}
+
+#[async_trait]
+impl<T, S> TaskEliminated<ActionForwarder<S>, ()> for T
+where
+    T: Actor,
+    S: Stream + Unpin + Send + 'static,
+    S::Item: Action,
+{
+    async fn handle(
+        &mut self,
+        _id: IdOf<ActionForwarder<S>>,
+        _tag: (),
+        result: Result<(), TaskError>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        if let Err(err) = result {
+            if let Some(err) = err.into_other() {
+                log::warn!(target: self.log_target(), "Attached stream failed: {}", err);
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Controls where stream can be accepted to an `Actor` using `Address`.
 pub trait StreamAcceptor<T>: Actor {
     /// The termination group used by `Address::attach` method.
@@ -642,6 +1019,25 @@ where
 pub(crate) struct ScheduledItem<T> {
     pub timestamp: Instant,
     pub item: T,
+    pub handle: Option<ScheduleHandle>,
+}
+
+/// The handle to cancel a scheduled event.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleHandle {
+    canceled: Arc<AtomicBool>,
+}
+
+impl ScheduleHandle {
+    /// Cancels the scheduled event if it hasn't happened yet.
+    pub fn cancel(&self) {
+        self.canceled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the event was canceled.
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::Relaxed)
+    }
 }
 
 /// Priority never taken into account for `Scheduled` message,
@@ -672,6 +1068,110 @@ where
         msg: ScheduledItem<I>,
         ctx: &mut Context<Self>,
     ) -> Result<(), Error> {
+        if msg
+            .handle
+            .map(|handle| handle.is_canceled())
+            .unwrap_or(false)
+        {
+            return Ok(());
+        }
         Scheduled::handle(self, msg.timestamp, msg.item, ctx).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    struct Subscribe;
+
+    impl StreamInteraction for Subscribe {
+        type Item = u32;
+
+        fn capacity() -> usize {
+            1
+        }
+    }
+
+    struct Broadcast(u32);
+
+    impl Action for Broadcast {}
+
+    #[derive(Default)]
+    struct Broadcaster {
+        subscribers: Vec<StreamResponder<u32>>,
+    }
+
+    impl Actor for Broadcaster {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Broadcaster {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Broadcaster {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StreamInteractionHandler<Subscribe> for Broadcaster {
+        async fn handle(
+            &mut self,
+            _input: Subscribe,
+            responder: StreamResponder<u32>,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            responder.send(0).await?;
+            self.subscribers.push(responder);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Broadcast> for Broadcaster {
+        async fn handle(
+            &mut self,
+            input: Broadcast,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.subscribers.retain(|responder| !responder.is_closed());
+            for responder in &self.subscribers {
+                if let Err(crate::linkage::TrySendError::Full(_)) = responder.try_send(input.0) {
+                    log::warn!("Subscriber is lagging");
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interact_stream() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(Broadcaster::default());
+        let mut stream = address.interact_stream(Subscribe)?;
+        assert_eq!(stream.next().await, Some(0));
+        address.act(Broadcast(1))?;
+        assert_eq!(stream.next().await, Some(1));
+        // The capacity is 1, the second item is dropped by `try_send`
+        address.act(Broadcast(2))?;
+        address.act(Broadcast(3))?;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        System::interrupt(&address)?;
+        address.join().await;
+        let rest: Vec<_> = stream.collect().await;
+        assert_eq!(rest, vec![2]);
+        Ok(())
+    }
+}
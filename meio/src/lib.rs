@@ -12,21 +12,37 @@
 
 mod actor_runtime;
 mod compat;
+mod dead_letters;
+mod dedupe;
+#[cfg(feature = "durable")]
+pub mod durable;
+#[cfg(test)]
+mod fixtures;
 mod forwarders;
 pub mod handlers;
 pub mod ids;
 mod lifecycle;
 pub mod linkage;
 mod lite_runtime;
+#[cfg(feature = "metrics")]
+mod metrics;
+pub mod pubsub;
+pub mod registry;
+#[cfg(feature = "remote")]
+pub mod remote;
 #[cfg(not(feature = "wasm"))]
 pub mod signal;
+pub mod state_machine;
 pub mod system;
 pub mod tasks;
+pub mod testing;
 #[cfg(not(feature = "wasm"))]
 pub mod thread;
 
 pub mod prelude;
 
+pub use linkage::join_all;
+
 // %%%%%%%%%%%%%%%%%%%%%% TESTS %%%%%%%%%%%%%%%%%%%%%
 
 #[cfg(test)]
@@ -95,7 +111,8 @@ mod tests {
 
         impl MyLink {
             pub fn send_signal(&mut self) -> Result<(), Error> {
-                self.address.act(LinkSignal)
+                self.address.act(LinkSignal)?;
+                Ok(())
             }
         }
 
@@ -104,7 +121,7 @@ mod tests {
         /// 1. You can have different links/views to the `Actor`.
         ///
         /// 2. And if `DerefMut` implemented you can use the `Link`
-        /// as an ordinary `Address` instance.
+        ///    as an ordinary `Address` instance.
         ///
         #[derive(Debug, From, Deref, DerefMut, Into)]
         pub struct MyAlternativeLink {
@@ -211,11 +228,11 @@ mod tests {
     #[tokio::test]
     async fn start_and_terminate() -> Result<(), Error> {
         env_logger::try_init().ok();
-        let mut address = System::spawn(MyActor);
+        let address = System::spawn(MyActor);
         address.act(MsgOne)?;
         let res = address.interact(MsgTwo).recv().await?;
         assert_eq!(res, 1);
-        System::interrupt(&mut address)?;
+        System::interrupt(&address)?;
         address.join().await;
         Ok(())
     }
@@ -223,7 +240,7 @@ mod tests {
     #[tokio::test]
     async fn test_recipient() -> Result<(), Error> {
         env_logger::try_init().ok();
-        let mut address = System::spawn(MyActor);
+        let address = System::spawn(MyActor);
         let action_recipient = address.action_recipient();
         action_recipient.clone().act(MsgOne)?;
         let interaction_recipient = address.interaction_recipient();
@@ -233,7 +250,7 @@ mod tests {
             .recv()
             .await?;
         assert_eq!(res, 1);
-        System::interrupt(&mut address)?;
+        System::interrupt(&address)?;
         address.join().await;
         Ok(())
     }
@@ -241,10 +258,10 @@ mod tests {
     #[tokio::test]
     async fn test_custom_interaction() -> Result<(), Error> {
         env_logger::try_init().ok();
-        let mut address = System::spawn(MyActor);
+        let address = System::spawn(MyActor);
         let res = address.clone().interact(MsgThree).recv().await?;
         assert_eq!(res, 123);
-        System::interrupt(&mut address)?;
+        System::interrupt(&address)?;
         address.join().await;
         Ok(())
     }
@@ -257,7 +274,7 @@ mod tests {
         address.attach(stream, ())?;
         // If you acivate this line the test will wait for the `Ctrl+C` signal.
         //address.attach(signal::CtrlC::stream()).await?;
-        //System::interrupt(&mut address)?;
+        //System::interrupt(&address)?;
         address.join().await;
         Ok(())
     }
@@ -268,8 +285,8 @@ mod tests {
         let address = System::spawn(MyActor);
         let mut link: link::MyLink = address.link();
         link.send_signal()?;
-        let mut alternative_link: link::MyAlternativeLink = address.link();
-        System::interrupt(&mut alternative_link)?;
+        let alternative_link: link::MyAlternativeLink = address.link();
+        System::interrupt(&alternative_link)?;
         address.join().await;
         Ok(())
     }
@@ -293,7 +310,7 @@ mod tests {
         Ok(())
     }
 
-    struct ActorSingle(usize);
+    struct ActorSingle(#[allow(dead_code)] usize);
 
     impl Actor for ActorSingle {
         type GroupBy = ();
@@ -342,6 +359,7 @@ mod tests {
         async fn handle(
             &mut self,
             id: IdOf<ActorSingle>,
+            _reason: TerminationReason,
             ctx: &mut Context<Self>,
         ) -> Result<(), Error> {
             self.actors.remove(&id);
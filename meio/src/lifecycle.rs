@@ -1,11 +1,15 @@
 //! Contains message of the `Actor`'s lifecycle.
 
-use crate::actor_runtime::Actor;
-use crate::handlers::{InstantAction, InstantActionHandler, Operation, Parcel};
+use crate::actor_runtime::{Actor, Context};
+use crate::handlers::{
+    Action, ActionHandler, InstantAction, InstantActionHandler, Operation, Parcel,
+};
 use crate::ids::{Id, IdOf};
-use crate::linkage::Address;
+use crate::linkage::{Address, Joinable};
 use crate::lite_runtime::{LiteTask, Tag, TaskAddress, TaskError};
 use anyhow::Error;
+use async_trait::async_trait;
+use futures::channel::oneshot;
 use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 
@@ -21,20 +25,61 @@ impl Stage {
     }
 }
 
+/// The kind of a child of an `Actor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChildKind {
+    /// The child is an `Actor`.
+    Actor,
+    /// The child is a `LiteTask`.
+    Task,
+}
+
+/// Describes a live child of an `Actor`.
+#[derive(Debug, Clone)]
+pub struct ChildInfo<G> {
+    /// `Id` of the child.
+    pub id: Id,
+    /// The type name of the child.
+    pub type_name: &'static str,
+    /// Is it an `Actor` or a `LiteTask`.
+    pub kind: ChildKind,
+    /// The group the child belongs to.
+    pub group: G,
+    /// The shutdown priority inside the group.
+    pub priority: i32,
+}
+
 struct Record<A: Actor> {
+    /// The order of spawning.
+    seq: u64,
+    type_name: &'static str,
+    kind: ChildKind,
     group: A::GroupBy,
+    /// Members of a group with lower priorities are interrupted first.
+    priority: i32,
+    /// Was interrupted by the termination of its stage.
+    interrupted: bool,
     notifier: Box<dyn LifecycleNotifier<Interrupt<A>>>,
+    /// Drops the child immediately.
+    killer: Box<dyn Fn() + Send>,
+    /// Waits for the termination of a child `Actor`.
+    joinable: Option<Joinable>,
 }
 
 impl<A: Actor> Record<A> {
-    fn interrupt(&mut self) -> Result<(), Error> {
-        self.notifier.notify(Interrupt::new())
+    fn interrupt(&mut self, reason: &InterruptReason) -> Result<(), Error> {
+        self.notifier.notify(Interrupt::new(reason.clone()))
     }
 }
 
 // TODO: Rename to Terminator again
 pub(crate) struct LifetimeTracker<A: Actor> {
+    /// The log target of the `Actor` that owns children.
+    log_target: String,
     terminating: bool,
+    next_seq: u64,
+    /// The reason the `Actor` was interrupted with (passed to children).
+    reason: Option<InterruptReason>,
     prioritized: Vec<A::GroupBy>,
     stages: HashMap<A::GroupBy, Stage>,
     records: HashMap<Id, Record<A>>,
@@ -43,9 +88,12 @@ pub(crate) struct LifetimeTracker<A: Actor> {
 // TODO: Change T to A
 impl<A: Actor> LifetimeTracker<A> {
     // TODO: Make the constructor private
-    pub fn new() -> Self {
+    pub fn new(log_target: &str) -> Self {
         Self {
+            log_target: log_target.to_string(),
             terminating: false,
+            next_seq: 0,
+            reason: None,
             // TODO: with_capacity 0 ?
             prioritized: Vec::new(),
             stages: HashMap::new(),
@@ -57,25 +105,55 @@ impl<A: Actor> LifetimeTracker<A> {
         self.terminating
     }
 
+    pub fn interrupt_reason(&self) -> Option<&InterruptReason> {
+        self.reason.as_ref()
+    }
+
+    /// Remembers the first reason of the interruption.
+    pub fn set_interrupt_reason(&mut self, reason: InterruptReason) {
+        self.reason.get_or_insert(reason);
+    }
+
+    fn children_reason(&self) -> InterruptReason {
+        self.reason.clone().unwrap_or_default()
+    }
+
     // TODO: Rename to `insert_actor`
     pub fn insert<T>(&mut self, address: Address<T>, group: A::GroupBy)
     where
         T: Actor + InstantActionHandler<Interrupt<A>>,
     {
+        let seq = self.next_seq();
         let stage = self.stages.entry(group.clone()).or_default();
         let id: Id = address.id().into();
         stage.ids.insert(id.clone());
         // TODO: Use the same `stopper` like `LiteTasks` does. The problem it's not cloneable.
         // TODO: Use `schedule` queue with oneshot to avoid blocking of queue drain handlers
-        let notifier = <dyn LifecycleNotifier<_>>::once(address, Operation::Forward);
-        let mut record = Record { group, notifier };
+        let killer = {
+            let address = address.clone();
+            Box::new(move || address.kill())
+        };
+        let joinable = Some(Joinable::from(address.clone()));
+        let notifier = <dyn LifecycleNotifier<_>>::interrupt(address);
+        let mut record = Record {
+            seq,
+            type_name: std::any::type_name::<T>(),
+            kind: ChildKind::Actor,
+            group,
+            priority: 0,
+            interrupted: stage.terminating,
+            notifier,
+            killer,
+            joinable,
+        };
         if stage.terminating {
             log::warn!(
+                target: &self.log_target,
                 "Actor added into the terminating state (interrupt it immediately): {}",
                 id
             );
-            if let Err(err) = record.interrupt() {
-                log::error!("Can't interrupt actor {:?} immediately: {}", id, err);
+            if let Err(err) = record.interrupt(&self.children_reason()) {
+                log::error!(target: &self.log_target, "Can't interrupt actor {:?} immediately: {}", id, err);
             }
         }
         self.records.insert(id, record);
@@ -85,25 +163,143 @@ impl<A: Actor> LifetimeTracker<A> {
     where
         T: LiteTask,
     {
+        let seq = self.next_seq();
         let stage = self.stages.entry(group.clone()).or_default();
         let id: Id = stopper.id().into();
         stage.ids.insert(id.clone());
+        let killer = {
+            let stopper = stopper.clone();
+            Box::new(move || {
+                stopper.stop().ok();
+            })
+        };
         let notifier = <dyn LifecycleNotifier<_>>::stop(stopper);
-        let mut record = Record { group, notifier };
+        let mut record = Record {
+            seq,
+            type_name: std::any::type_name::<T>(),
+            kind: ChildKind::Task,
+            group,
+            priority: 0,
+            interrupted: stage.terminating,
+            notifier,
+            killer,
+            joinable: None,
+        };
         if stage.terminating {
             log::warn!(
+                target: &self.log_target,
                 "Task added into the terminating state (interrupt it immediately): {}",
                 id
             );
             // But this event will never received, because LiteTasks can't do that.
             // Instead it will set stop signal to watcher.
-            if let Err(err) = record.interrupt() {
-                log::error!("Can't interrupt task {:?} immediately: {}", id, err);
+            if let Err(err) = record.interrupt(&self.children_reason()) {
+                log::error!(target: &self.log_target, "Can't interrupt task {:?} immediately: {}", id, err);
             }
         }
         self.records.insert(id, record);
     }
 
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        seq
+    }
+
+    /// Returns ids of alive children in the spawning order.
+    pub fn children(&self) -> Vec<Id> {
+        let mut children: Vec<_> = self.records.iter().collect();
+        children.sort_by_key(|(_, record)| record.seq);
+        children.into_iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Sets the priority of the child within its group.
+    ///
+    /// Returns `false` if there is no child with the `id`.
+    pub fn set_priority(&mut self, id: &Id, priority: i32) -> bool {
+        if let Some(record) = self.records.get_mut(id) {
+            record.priority = priority;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves the child to the `group`.
+    ///
+    /// Fails if there is no child with the `id` or the termination has started,
+    /// because a stage that is being terminated must not gain or lose members.
+    pub fn set_group(&mut self, id: &Id, group: A::GroupBy) -> Result<(), Error> {
+        if self.terminating {
+            return Err(Error::msg(format!(
+                "can't change the group of {} during the termination",
+                id
+            )));
+        }
+        let record = self
+            .records
+            .get_mut(id)
+            .ok_or_else(|| Error::msg(format!("no child with the id {}", id)))?;
+        if let Some(stage) = self.stages.get_mut(&record.group) {
+            stage.ids.remove(id);
+        }
+        self.stages
+            .entry(group.clone())
+            .or_default()
+            .ids
+            .insert(id.clone());
+        record.group = group;
+        Ok(())
+    }
+
+    pub fn contains(&self, id: &Id) -> bool {
+        self.records.contains_key(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Interrupts all children in the reverse spawning order.
+    pub fn interrupt_all(&mut self) {
+        let reason = self.children_reason();
+        for id in self.children().into_iter().rev() {
+            if let Some(record) = self.records.get_mut(&id) {
+                record.interrupted = true;
+                if let Err(err) = record.interrupt(&reason) {
+                    log::error!(target: &self.log_target, "Can't send interruption signal to {:?}: {}", id, err);
+                }
+            }
+        }
+    }
+
+    /// Kills child actors and stops tasks immediately.
+    /// Kills all children and returns handles to wait for child actors.
+    pub fn kill_all(&mut self) -> Vec<Joinable> {
+        let mut joinables = Vec::new();
+        for record in self.records.values() {
+            (record.killer)();
+            joinables.extend(record.joinable.clone());
+        }
+        joinables
+    }
+
+    /// Returns descriptions of alive children in the spawning order.
+    pub fn children_info(&self) -> Vec<ChildInfo<A::GroupBy>> {
+        let mut records: Vec<_> = self.records.iter().collect();
+        records.sort_by_key(|(_, record)| record.seq);
+        records
+            .into_iter()
+            .map(|(id, record)| ChildInfo {
+                id: id.clone(),
+                type_name: record.type_name,
+                kind: record.kind,
+                group: record.group.clone(),
+                priority: record.priority,
+            })
+            .collect()
+    }
+
     pub fn remove(&mut self, id: &Id) {
         if let Some(record) = self.records.remove(id) {
             if let Some(stage) = self.stages.get_mut(&record.group) {
@@ -124,22 +320,16 @@ impl<A: Actor> LifetimeTracker<A> {
         self.stages.values().all(Stage::is_finished)
     }
 
-    fn stage_sequence(&self) -> Vec<A::GroupBy> {
-        let stages_to_term: HashSet<_> = self.stages.keys().cloned().collect();
-        let prior_set: HashSet<_> = self.prioritized.iter().cloned().collect();
-        let remained = stages_to_term.difference(&prior_set).cloned();
-        let mut sequence = self.prioritized.clone();
-        sequence.extend(remained);
-        sequence
-    }
-
     pub fn terminate_group(&mut self, group: A::GroupBy) {
+        let reason = self.children_reason();
         if let Some(stage) = self.stages.get(&group) {
             for id in stage.ids.iter() {
                 if let Some(record) = self.records.get_mut(id) {
-                    if let Err(err) = record.interrupt() {
+                    record.interrupted = true;
+                    if let Err(err) = record.interrupt(&reason) {
                         // TODO: Add `Group` name to logs?
                         log::error!(
+                            target: &self.log_target,
                             "Can't send interruption signal to {:?} for a group termination: {}",
                             id,
                             err,
@@ -152,26 +342,55 @@ impl<A: Actor> LifetimeTracker<A> {
 
     fn try_terminate_next(&mut self) {
         self.terminating = true;
-        for stage_name in self.stage_sequence() {
-            if let Some(stage) = self.stages.get_mut(&stage_name) {
-                if !stage.terminating {
-                    stage.terminating = true;
-                    for id in stage.ids.iter() {
-                        if let Some(record) = self.records.get_mut(id) {
-                            if let Err(err) = record.interrupt() {
-                                log::error!(
-                                    "Can't notify the supervisor about actor with {:?} termination: {}",
-                                    id,
-                                    err
-                                );
-                            }
+        for stage_name in self.prioritized.clone() {
+            if !self.terminate_stage(&stage_name) {
+                return;
+            }
+        }
+        // Groups that are not in the sequence are terminated together.
+        let remained: Vec<_> = self
+            .stages
+            .keys()
+            .filter(|group| !self.prioritized.contains(group))
+            .cloned()
+            .collect();
+        for stage_name in remained {
+            self.terminate_stage(&stage_name);
+        }
+    }
+
+    /// Interrupts alive members of the stage with the lowest priority
+    /// if they weren't interrupted yet.
+    ///
+    /// Returns `true` if the stage is finished.
+    fn terminate_stage(&mut self, stage_name: &A::GroupBy) -> bool {
+        let reason = self.children_reason();
+        if let Some(stage) = self.stages.get_mut(stage_name) {
+            stage.terminating = true;
+            let level = stage
+                .ids
+                .iter()
+                .filter_map(|id| self.records.get(id))
+                .map(|record| record.priority)
+                .min();
+            for id in stage.ids.iter() {
+                if let Some(record) = self.records.get_mut(id) {
+                    if Some(record.priority) == level && !record.interrupted {
+                        record.interrupted = true;
+                        if let Err(err) = record.interrupt(&reason) {
+                            log::error!(
+                                target: &self.log_target,
+                                "Can't notify the supervisor about actor with {:?} termination: {}",
+                                id,
+                                err
+                            );
                         }
                     }
                 }
-                if !stage.is_finished() {
-                    break;
-                }
             }
+            stage.is_finished()
+        } else {
+            true
         }
     }
 
@@ -207,8 +426,9 @@ impl<P> dyn LifecycleNotifier<P> {
     {
         let notifier = move |msg| {
             // TODO: Take the priority into account (don't put all in hp)
+            // Bypasses `hp_capacity`, lifecycle events can't be lost on a busy supervisor.
             let parcel = Parcel::new(operation.clone(), msg);
-            address.unpack_parcel(parcel)
+            address.unpack_parcel(parcel).map_err(Error::from)
         };
         Box::new(notifier)
     }
@@ -219,15 +439,132 @@ impl<P> dyn LifecycleNotifier<P> {
     }
 }
 
+impl<S: Actor> dyn LifecycleNotifier<Interrupt<S>> {
+    /// Sends the `Interrupt` that is visible as pending to the receiver.
+    pub fn interrupt<A>(address: Address<A>) -> Box<Self>
+    where
+        A: Actor + InstantActionHandler<Interrupt<S>>,
+    {
+        let notifier = move |msg| {
+            address
+                .unpack_parcel(Parcel::interrupt(msg))
+                .map_err(Error::from)
+        };
+        Box::new(notifier)
+    }
+}
+
+/// Keeps the notifiers of the current supervisor of an `Actor`.
+///
+/// It's shared by all addresses of the `Actor` to let them
+/// replace the supervisor while the `Actor` is alive.
+pub(crate) struct SupervisorSlot<A: Actor> {
+    finished: bool,
+    done_notifier: Box<dyn LifecycleNotifier<Done<A>>>,
+    release_notifier: Box<dyn LifecycleNotifier<()>>,
+}
+
+impl<A: Actor> SupervisorSlot<A> {
+    pub fn new() -> Self {
+        Self {
+            finished: false,
+            done_notifier: <dyn LifecycleNotifier<_>>::ignore(),
+            release_notifier: <dyn LifecycleNotifier<_>>::ignore(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Sets the supervisor that will receive the `Done` event.
+    pub fn assign<S>(&mut self, supervisor: Address<S>, id: Id)
+    where
+        S: Actor + InstantActionHandler<Done<A>> + InstantActionHandler<Release>,
+    {
+        let op = Operation::Done { id: id.clone() };
+        self.done_notifier = <dyn LifecycleNotifier<_>>::once(supervisor.clone(), op);
+        self.release_notifier = Box::new(move |_| {
+            supervisor
+                .service(Release::new(id.clone()))
+                .map_err(Error::from)
+        });
+    }
+
+    /// Asks the current supervisor to stop tracking the `Actor`.
+    pub fn release(&mut self) -> Result<(), Error> {
+        let res = self.release_notifier.notify(());
+        self.done_notifier = <dyn LifecycleNotifier<_>>::ignore();
+        self.release_notifier = <dyn LifecycleNotifier<_>>::ignore();
+        res
+    }
+
+    /// Notifies the current supervisor and drops its address.
+    ///
+    /// The `Done` event is a service message that doesn't take a slot
+    /// of `Actor::hp_capacity` of the supervisor. It fails only if the
+    /// supervisor has already terminated (or was killed) and doesn't
+    /// track the `Actor` anymore, since the supervisor closes its queues
+    /// after all children are done.
+    pub fn finish(&mut self, done: Done<A>) -> Result<(), Error> {
+        self.finished = true;
+        let res = self.done_notifier.notify(done);
+        self.done_notifier = <dyn LifecycleNotifier<_>>::ignore();
+        self.release_notifier = <dyn LifecycleNotifier<_>>::ignore();
+        res
+    }
+}
+
+/// The view to children (actors and tasks) spawned by an `Actor`.
+pub struct Supervisor<'a, A: Actor> {
+    tracker: &'a mut LifetimeTracker<A>,
+}
+
+impl<'a, A: Actor> Supervisor<'a, A> {
+    pub(crate) fn new(tracker: &'a mut LifetimeTracker<A>) -> Self {
+        Self { tracker }
+    }
+
+    /// Returns ids of alive children in the spawning order.
+    pub fn children(&self) -> Vec<Id> {
+        self.tracker.children()
+    }
+
+    /// Returns `true` if the child with the `id` is still alive.
+    pub fn contains(&self, id: &Id) -> bool {
+        self.tracker.contains(id)
+    }
+
+    /// The number of alive children.
+    pub fn len(&self) -> usize {
+        self.tracker.len()
+    }
+
+    /// Returns `true` if there are no alive children.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Interrupts all children in the reverse spawning order.
+    ///
+    /// It doesn't start termination of the `Actor` itself.
+    pub fn shutdown_all(&mut self) {
+        self.tracker.interrupt_all();
+    }
+}
+
 /// This message sent by a `Supervisor` to a spawned child actor.
 #[derive(Debug)]
 pub(crate) struct Awake<T: Actor> {
+    /// `Id` of the `Actor` that spawned the child.
+    pub supervisor: Option<Id>,
     _origin: PhantomData<T>,
 }
 
 impl<T: Actor> Awake<T> {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(supervisor: Option<Id>) -> Self {
         Self {
+            supervisor,
             _origin: PhantomData,
         }
     }
@@ -236,15 +573,29 @@ impl<T: Actor> Awake<T> {
 // High priority to indicate it will be called first.
 impl<T: Actor> InstantAction for Awake<T> {}
 
+/// Why an `Actor` is asked to interrupt its activity.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum InterruptReason {
+    /// The interruption was requested by a supervisor or a user.
+    #[default]
+    Requested,
+    /// The `Actor` will be spawned again with a new configuration.
+    Reload,
+    /// A dependency of the `Actor` has failed and there is no sense to continue.
+    Fatal(String),
+}
+
 /// The event to ask an `Actor` to interrupt its activity.
 #[derive(Debug)]
 pub(crate) struct Interrupt<T: Actor> {
+    pub reason: InterruptReason,
     _origin: PhantomData<T>,
 }
 
 impl<T: Actor> Interrupt<T> {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(reason: InterruptReason) -> Self {
         Self {
+            reason,
             _origin: PhantomData,
         }
     }
@@ -255,15 +606,61 @@ impl<T: Actor> Interrupt<T> {
 // as possible even in case when all queues are full.
 impl<T: Actor> InstantAction for Interrupt<T> {}
 
+/// How many lifecycle events a subscriber can lag behind.
+pub const LIFECYCLE_EVENTS_CAPACITY: usize = 32;
+
+/// The lifecycle transition of an `Actor` from `Address::lifecycle_events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecycleEvent {
+    /// The `Id` of the `Actor`.
+    pub id: Id,
+    /// The stage the `Actor` entered.
+    pub stage: LifecycleStage,
+}
+
+/// The stage of the lifecycle of an `Actor`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LifecycleStage {
+    /// The `StartedBy` handler is called (again if the `Actor` restarted).
+    Awake,
+    /// The `Actor` started and entered the routine that handles messages.
+    Running,
+    /// The `Actor` received the interruption.
+    Interrupted,
+    /// The `Actor` is waiting for termination of its children.
+    Terminating,
+    /// A child actor or task with the `Id` finished.
+    ChildEliminated(Id),
+    /// The `Actor` finished with the reason.
+    Done(TerminationReason),
+}
+
+/// The reason why an `Actor` has finished.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum TerminationReason {
+    /// The `Actor` has completed its activity by itself.
+    #[default]
+    Normal,
+    /// The `Actor` was interrupted by its supervisor or the `System`.
+    Interrupted,
+    /// The `Actor` has failed to start or a failed handler has finished it.
+    Failed(String),
+    /// A handler of the `Actor` panicked.
+    Panicked(String),
+}
+
 /// Notifies when `Actor`'s activity is completed.
 #[derive(Debug)]
 pub(crate) struct Done<T: Actor> {
     pub id: IdOf<T>,
+    pub reason: TerminationReason,
+    /// The error that caused the abnormal termination.
+    pub error: Option<Error>,
 }
 
 impl<T: Actor> Done<T> {
-    pub(crate) fn new(id: IdOf<T>) -> Self {
-        Self { id }
+    pub(crate) fn new(id: IdOf<T>, reason: TerminationReason, error: Option<Error>) -> Self {
+        Self { id, reason, error }
     }
 }
 
@@ -272,6 +669,101 @@ impl<T: Actor> Done<T> {
 // that have to notify the actor. It can be high-priority only.
 impl<T: Actor> InstantAction for Done<T> {}
 
+/// Asks a supervisor to stop tracking a reparented child.
+#[derive(Debug)]
+pub(crate) struct Release {
+    id: Id,
+}
+
+impl Release {
+    pub(crate) fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+impl InstantAction for Release {}
+
+#[async_trait]
+impl<T: Actor> InstantActionHandler<Release> for T {
+    async fn handle(&mut self, input: Release, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ctx.lifetime_tracker.remove(&input.id);
+        if ctx.lifetime_tracker.is_finished() {
+            ctx.stop();
+        }
+        Ok(())
+    }
+}
+
+/// Stops or restarts handling of ordinary priority messages.
+#[derive(Debug)]
+pub(crate) struct SetPaused {
+    paused: bool,
+}
+
+impl SetPaused {
+    pub(crate) fn new(paused: bool) -> Self {
+        Self { paused }
+    }
+}
+
+impl InstantAction for SetPaused {}
+
+#[async_trait]
+impl<T: Actor> InstantActionHandler<SetPaused> for T {
+    async fn handle(&mut self, input: SetPaused, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ctx.paused = input.paused;
+        Ok(())
+    }
+}
+
+/// The sentinel of the ordinary queue that notifies when it's reached.
+#[derive(Debug)]
+pub(crate) struct Flush {
+    tx: oneshot::Sender<()>,
+}
+
+impl Flush {
+    pub(crate) fn new(tx: oneshot::Sender<()>) -> Self {
+        Self { tx }
+    }
+}
+
+impl Action for Flush {}
+
+#[async_trait]
+impl<T: Actor> ActionHandler<Flush> for T {
+    async fn handle(&mut self, input: Flush, _ctx: &mut Context<Self>) -> Result<(), Error> {
+        input.tx.send(()).ok();
+        Ok(())
+    }
+}
+
+/// Asks a new supervisor to track a reparented child.
+pub(crate) struct Adopt<T: Actor, S: Actor> {
+    address: Address<T>,
+    group: S::GroupBy,
+}
+
+impl<T: Actor, S: Actor> Adopt<T, S> {
+    pub(crate) fn new(address: Address<T>, group: S::GroupBy) -> Self {
+        Self { address, group }
+    }
+}
+
+impl<T: Actor, S: Actor> InstantAction for Adopt<T, S> {}
+
+#[async_trait]
+impl<T, S> InstantActionHandler<Adopt<T, S>> for S
+where
+    T: Actor + InstantActionHandler<Interrupt<S>>,
+    S: Actor,
+{
+    async fn handle(&mut self, input: Adopt<T, S>, ctx: &mut Context<Self>) -> Result<(), Error> {
+        ctx.lifetime_tracker.insert(input.address, input.group);
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct TaskDone<T: LiteTask, M> {
     pub id: IdOf<T>,
@@ -288,3 +780,795 @@ impl<T: LiteTask, M> TaskDone<T, M> {
 // It's high priority, because it's impossible to use a channel with limited
 // size for this type of messages.
 impl<T: LiteTask, M: Tag> InstantAction for TaskDone<T, M> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::channel::oneshot;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    struct SupervisedChild;
+
+    impl Actor for SupervisedChild {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "SupervisedChild"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<SupervisingParent> for SupervisedChild {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            assert!(ctx.spawned_by().is_some());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<SupervisingParent> for SupervisedChild {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct SupervisingParent;
+
+    impl Actor for SupervisingParent {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "SupervisingParent"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for SupervisingParent {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            assert!(ctx.spawned_by().is_none());
+            let first: Id = ctx.spawn_actor(SupervisedChild, ()).id().into();
+            let second: Id = ctx.spawn_actor(SupervisedChild, ()).id().into();
+            let mut supervisor = ctx.supervisor();
+            assert_eq!(supervisor.children(), vec![first, second]);
+            supervisor.shutdown_all();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<SupervisedChild> for SupervisingParent {
+        async fn handle(
+            &mut self,
+            id: IdOf<SupervisedChild>,
+            _reason: TerminationReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            let supervisor = ctx.supervisor();
+            assert!(!supervisor.contains(&id.into()));
+            if supervisor.is_empty() {
+                ctx.shutdown();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_supervisor() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(SupervisingParent);
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    struct Dispatcher {
+        courier: Option<oneshot::Sender<InterruptReason>>,
+    }
+
+    impl Actor for Dispatcher {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Dispatcher"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Dispatcher {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let courier = Courier {
+                reason: self.courier.take(),
+            };
+            ctx.spawn_actor(courier, ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Dispatcher {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            assert!(ctx.interrupt_reason().is_some());
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Courier> for Dispatcher {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Courier>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    struct Courier {
+        reason: Option<oneshot::Sender<InterruptReason>>,
+    }
+
+    impl Actor for Courier {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Courier"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<Dispatcher> for Courier {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<Dispatcher> for Courier {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+
+        async fn handle_with_reason(
+            &mut self,
+            reason: InterruptReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            if let Some(tx) = self.reason.take() {
+                tx.send(reason).ok();
+            }
+            InterruptedBy::handle(self, ctx).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interrupt_with_reason() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let address = System::spawn(Dispatcher { courier: Some(tx) });
+        let fatal = InterruptReason::Fatal("the storage is gone".into());
+        System::interrupt_with(&address, fatal.clone())?;
+        // The reason is passed down to the children.
+        assert_eq!(timeout(Duration::from_secs(5), rx).await??, fatal);
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    struct IoGroup {
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Actor for IoGroup {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "IoGroup"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for IoGroup {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            for (name, priority) in [("reader", 1), ("writer", 0)] {
+                let member = IoMember {
+                    name,
+                    log: self.log.clone(),
+                };
+                let address = ctx.spawn_actor(member, ());
+                ctx.set_shutdown_priority(address.id(), priority)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for IoGroup {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<IoMember> for IoGroup {
+        async fn handle(
+            &mut self,
+            _id: IdOf<IoMember>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    struct IoMember {
+        name: &'static str,
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Actor for IoMember {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "IoMember"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<IoGroup> for IoMember {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<IoGroup> for IoMember {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.log.lock().unwrap().push(self.name);
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_priority() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let address = System::spawn(IoGroup { log: log.clone() });
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        // The writer is interrupted first and the reader after it finished.
+        assert_eq!(*log.lock().unwrap(), vec!["writer", "reader"]);
+        Ok(())
+    }
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    enum ConnStage {
+        Handshaking,
+        Active,
+    }
+
+    impl TerminationSequence for ConnStage {
+        fn termination_sequence() -> Vec<Self> {
+            vec![Self::Handshaking, Self::Active]
+        }
+    }
+
+    struct Migrator {
+        log: Arc<Mutex<Vec<&'static str>>>,
+        children: Vec<Id>,
+        rejected: Arc<AtomicBool>,
+    }
+
+    impl Actor for Migrator {
+        type GroupBy = ConnStage;
+
+        fn log_target(&self) -> &str {
+            "Migrator"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Migrator {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            for (name, group) in [
+                ("first", ConnStage::Handshaking),
+                ("second", ConnStage::Active),
+            ] {
+                let member = IoMember {
+                    name,
+                    log: self.log.clone(),
+                };
+                let address = ctx.spawn_actor(member, group);
+                self.children.push(address.id().into());
+            }
+            ctx.set_group(self.children[0].clone(), ConnStage::Active)?;
+            ctx.set_group(self.children[1].clone(), ConnStage::Handshaking)?;
+            assert!(ctx.set_group(Id::unique(), ConnStage::Active).is_err());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Migrator {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            let res = ctx.set_group(self.children[0].clone(), ConnStage::Handshaking);
+            self.rejected.store(res.is_err(), Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<IoMember> for Migrator {
+        async fn handle(
+            &mut self,
+            _id: IdOf<IoMember>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<Migrator> for IoMember {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<Migrator> for IoMember {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.log.lock().unwrap().push(self.name);
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_group() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let rejected = Arc::new(AtomicBool::new(false));
+        let address = System::spawn(Migrator {
+            log: log.clone(),
+            children: Vec::new(),
+            rejected: rejected.clone(),
+        });
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        // Groups were swapped and the termination follows the new ones.
+        assert_eq!(*log.lock().unwrap(), vec!["second", "first"]);
+        assert!(rejected.load(Ordering::SeqCst));
+        Ok(())
+    }
+
+    struct Inspector {
+        bridge_tx: Option<tokio::sync::mpsc::Sender<Item>>,
+    }
+
+    impl Actor for Inspector {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Inspector"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Inspector {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.spawn_actor(InspectedChild, ());
+            let (tx, rx) = tokio::sync::mpsc::channel(1);
+            self.bridge_tx = Some(tx);
+            let bridge = ChannelBridge::from_mpsc(rx, ctx.address().clone());
+            ctx.spawn_task(bridge, (), ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Inspector {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Item> for Inspector {
+        async fn handle(&mut self, _item: Item, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    struct CloseBridge;
+
+    impl Action for CloseBridge {}
+
+    #[async_trait]
+    impl ActionHandler<CloseBridge> for Inspector {
+        async fn handle(&mut self, _: CloseBridge, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.bridge_tx.take();
+            Ok(())
+        }
+    }
+
+    struct GetChildren;
+
+    impl Interaction for GetChildren {
+        type Output = Vec<ChildInfo<()>>;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<GetChildren> for Inspector {
+        async fn handle(
+            &mut self,
+            _: GetChildren,
+            ctx: &mut Context<Self>,
+        ) -> Result<Vec<ChildInfo<()>>, Error> {
+            Ok(ctx.children())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<InspectedChild> for Inspector {
+        async fn handle(
+            &mut self,
+            _id: IdOf<InspectedChild>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    struct InspectedChild;
+
+    impl Actor for InspectedChild {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "InspectedChild"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<Inspector> for InspectedChild {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<Inspector> for InspectedChild {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_children() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(Inspector { bridge_tx: None });
+        let children = address.interact(GetChildren).recv().await?;
+        let kinds: Vec<_> = children.iter().map(|child| child.kind).collect();
+        assert_eq!(kinds, vec![ChildKind::Actor, ChildKind::Task]);
+        assert!(children[0].type_name.ends_with("InspectedChild"));
+        assert!(children[1].type_name.contains("ChannelBridge"));
+        address.act(CloseBridge)?;
+        let remained = timeout(Duration::from_secs(5), async {
+            loop {
+                let children = address.interact(GetChildren).recv().await?;
+                if children.len() == 1 {
+                    break Ok::<_, Error>(children);
+                }
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await??;
+        assert_eq!(remained[0].kind, ChildKind::Actor);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    struct Lingering {
+        interruptions: Arc<AtomicUsize>,
+    }
+
+    impl Actor for Lingering {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<GroupOwner> for Lingering {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<GroupOwner> for Lingering {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.interruptions.fetch_add(1, Ordering::SeqCst);
+            sleep(Duration::from_millis(50)).await;
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct GroupOwner {
+        interruptions: Arc<AtomicUsize>,
+    }
+
+    impl Actor for GroupOwner {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for GroupOwner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let child = Lingering {
+                interruptions: self.interruptions.clone(),
+            };
+            ctx.spawn_actor(child, ());
+            ctx.terminate_group(());
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Lingering> for GroupOwner {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Lingering>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_terminate_group_once() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let interruptions = Arc::new(AtomicUsize::new(0));
+        let address = System::spawn(GroupOwner {
+            interruptions: interruptions.clone(),
+        });
+        timeout(Duration::from_secs(5), address.join()).await?;
+        // The shutdown doesn't interrupt the group again.
+        assert_eq!(interruptions.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    enum Tier {
+        Front,
+        Middle,
+        Back,
+    }
+
+    impl TerminationSequence for Tier {
+        fn termination_sequence() -> Vec<Self> {
+            vec![Self::Front, Self::Middle, Self::Back]
+        }
+    }
+
+    struct Tiered {
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Actor for Tiered {
+        type GroupBy = Tier;
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Tiered {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            for (name, group) in [
+                ("front", Tier::Front),
+                ("middle", Tier::Middle),
+                ("back", Tier::Back),
+            ] {
+                let member = IoMember {
+                    name,
+                    log: self.log.clone(),
+                };
+                ctx.spawn_actor(member, group);
+            }
+            // Overrides the sequence of the `GroupBy` type, `Middle` is not listed.
+            ctx.termination_sequence(vec![Tier::Back, Tier::Front]);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Tiered {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<IoMember> for Tiered {
+        async fn handle(
+            &mut self,
+            _id: IdOf<IoMember>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<Tiered> for IoMember {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<Tiered> for IoMember {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.log.lock().unwrap().push(self.name);
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_termination_sequence() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let address = System::spawn(Tiered { log: log.clone() });
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        // Listed groups go first in the configured order, the rest after them.
+        assert_eq!(*log.lock().unwrap(), vec!["back", "front", "middle"]);
+        Ok(())
+    }
+
+    #[derive(Clone, Copy)]
+    enum Ending {
+        Finish,
+        Interrupt,
+        Fail,
+        Panic,
+    }
+
+    struct Ended(Ending);
+
+    impl Actor for Ended {
+        type GroupBy = ();
+
+        fn catch_panics(&self) -> bool {
+            true
+        }
+    }
+
+    struct Explode;
+
+    impl Action for Explode {}
+
+    #[async_trait]
+    impl ActionHandler<Explode> for Ended {
+        async fn handle(&mut self, _: Explode, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            panic!("exploded");
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<Endings> for Ended {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            match self.0 {
+                Ending::Finish => ctx.shutdown(),
+                Ending::Interrupt => {}
+                Ending::Fail => return Err(Error::msg("broken")),
+                Ending::Panic => ctx.address().act(Explode)?,
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<Endings> for Ended {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct Endings {
+        reasons: Vec<TerminationReason>,
+        done: Option<oneshot::Sender<Vec<TerminationReason>>>,
+    }
+
+    impl Actor for Endings {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Endings {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let endings = [
+                Ending::Finish,
+                Ending::Interrupt,
+                Ending::Fail,
+                Ending::Panic,
+            ];
+            for ending in endings {
+                let mut child = ctx.spawn_actor(Ended(ending), ());
+                if let Ending::Interrupt = ending {
+                    ctx.interrupt(&mut child)?;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Endings {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Ended> for Endings {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Ended>,
+            reason: TerminationReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.reasons.push(reason);
+            if self.reasons.len() == 4 {
+                if let Some(done) = self.done.take() {
+                    done.send(self.reasons.clone()).ok();
+                }
+                ctx.shutdown();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_termination_reasons() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let address = System::spawn(Endings {
+            reasons: Vec::new(),
+            done: Some(tx),
+        });
+        let reasons = timeout(Duration::from_secs(5), rx).await??;
+        let expected = [
+            TerminationReason::Normal,
+            TerminationReason::Interrupted,
+            TerminationReason::Failed("broken".into()),
+            TerminationReason::Panicked("exploded".into()),
+        ];
+        assert_eq!(reasons.len(), expected.len());
+        for reason in &expected {
+            assert!(reasons.contains(reason), "{:?} not reached", reason);
+        }
+        address.join().await;
+        Ok(())
+    }
+}
@@ -1,24 +1,158 @@
 //! This module contains `Address` to interact with an `Actor`.
 
-use super::{ActionRecipient, InteractionRecipient};
-use crate::actor_runtime::{Actor, Status};
+use super::mailbox::{
+    self, MailboxKind, MailboxPermit, MailboxReceiver, MailboxSender, SendError, SendTimeoutError,
+    TrySendError, WeakMailboxSender,
+};
+use super::{ActionRecipient, AddressSink, InteractionRecipient, Joinable};
+use crate::actor_runtime::{Actor, ActorStatus};
 use crate::compat::watch;
+use crate::dead_letters;
+#[cfg(feature = "durable")]
+use crate::durable::DurableMailbox;
 use crate::forwarders::AttachStream;
+use crate::handlers::{response_stream, InteractStream, ResponseStream, StreamInteraction};
 use crate::handlers::{
-    Action, ActionHandler, Consumer, Envelope, Handler, InstantAction, InstantActionHandler,
-    Interact, Interaction, InteractionHandler, InteractionTask, InterruptedBy, Operation, Parcel,
-    Priority, Scheduled, ScheduledItem, StreamAcceptor, TerminateBy, TerminatedBy,
+    Action, ActionHandler, Consumer, Eliminated, Envelope, Handler, InstantAction,
+    InstantActionHandler, Interact, Interaction, InteractionHandler, InteractionTask,
+    InterruptedBy, Operation, Parcel, Priority, ScheduleHandle, Scheduled, ScheduledItem,
+    StreamAcceptor, TerminateBy, TerminatedBy,
 };
 use crate::ids::{Id, IdOf};
-use crate::lifecycle::Interrupt;
+use crate::lifecycle::{
+    Adopt, Flush, Interrupt, InterruptReason, LifecycleEvent, LifecycleStage, SetPaused,
+    SupervisorSlot, LIFECYCLE_EVENTS_CAPACITY,
+};
 use crate::lite_runtime::Tag;
+#[cfg(feature = "metrics")]
+use crate::metrics::{Metrics, MetricsCollector};
+use crate::system::{System, WaitStatus};
 use anyhow::Error;
-use futures::Stream;
+use futures::channel::oneshot;
+use futures::future::{self, BoxFuture, Either};
+use futures::{FutureExt, Stream};
 use std::fmt;
 use std::hash::{Hash, Hasher};
-use std::time::Instant;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::broadcast::{self, error::RecvError};
 use tokio::sync::mpsc;
 
+/// The error of the `ask` request.
+#[derive(Debug, Error)]
+pub enum AskError {
+    /// The request wasn't delivered to the `Actor`.
+    #[error("the request wasn't delivered: {0}")]
+    Send(#[from] SendError),
+    /// The `Actor` dropped the reply sender without replying.
+    #[error("the actor dropped the request without a reply")]
+    NoReply,
+    /// The reply wasn't received in time.
+    #[error("the reply wasn't received in time")]
+    Timeout,
+}
+
+/// The error of an interaction with an `Actor`.
+#[derive(Debug, Error)]
+pub enum InteractError {
+    /// The request wasn't delivered to the `Actor`.
+    #[error("the request wasn't delivered: {0}")]
+    Send(#[from] SendError),
+    /// The `Actor` dropped the request without a response.
+    #[error("the actor dropped the request without a response")]
+    NoReply,
+    /// The response wasn't received in time.
+    #[error("the response wasn't received in time")]
+    Timeout,
+    /// The handler of the `Actor` cancelled the interaction.
+    #[error("the interaction was cancelled")]
+    Cancelled,
+    /// The handler of the `Actor` returned an error.
+    #[error("the handler failed: {0}")]
+    Handler(#[source] Error),
+}
+
+/// The error of `Address::send_all` that was interrupted by the closed mailbox.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("the mailbox closed after {sent} sent actions")]
+pub struct SendAllError {
+    /// The number of actions sent before the failure.
+    pub sent: usize,
+}
+
+/// The error of an interaction that keeps the request if it wasn't delivered.
+#[derive(Debug, Error)]
+pub enum InteractFailure<I> {
+    /// The request wasn't delivered to the `Actor` and is returned back.
+    #[error("the request wasn't delivered: {reason}")]
+    Undelivered {
+        /// The unsent request.
+        request: I,
+        /// Why the request wasn't delivered.
+        reason: SendError,
+    },
+    /// The request was delivered, but the interaction failed.
+    #[error(transparent)]
+    Failed(#[from] InteractError),
+}
+
+impl<I> InteractFailure<I> {
+    /// Returns the request if it wasn't delivered.
+    pub fn into_request(self) -> Option<I> {
+        match self {
+            Self::Undelivered { request, .. } => Some(request),
+            Self::Failed(_) => None,
+        }
+    }
+}
+
+impl<I> From<InteractFailure<I>> for InteractError {
+    fn from(failure: InteractFailure<I>) -> Self {
+        match failure {
+            InteractFailure::Undelivered { reason, .. } => Self::Send(reason),
+            InteractFailure::Failed(err) => err,
+        }
+    }
+}
+
+/// The error that a handler returns if it cancelled an interaction.
+///
+/// The caller receives it as `InteractError::Cancelled`.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("the interaction was cancelled")]
+pub struct Cancelled;
+
+impl InteractError {
+    /// Returns `true` if the interaction can succeed if retried.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Send(err) => err.is_transient(),
+            Self::Timeout => true,
+            Self::NoReply | Self::Cancelled | Self::Handler(_) => false,
+        }
+    }
+
+    /// Wraps the error of the handler.
+    pub(crate) fn from_handler(err: Error) -> Self {
+        if err.is::<Cancelled>() {
+            Self::Cancelled
+        } else {
+            Self::Handler(err)
+        }
+    }
+
+    /// Converts into an `anyhow::Error` that is the error of the handler
+    /// itself if the handler failed.
+    pub fn into_error(self) -> Error {
+        match self {
+            Self::Handler(err) => err,
+            other => other.into(),
+        }
+    }
+}
+
 /// Pre-created `Address` that can be used in spawning an actor.
 pub struct AddressPair<A: Actor> {
     pub(crate) joint: AddressJoint<A>,
@@ -28,52 +162,163 @@ pub struct AddressPair<A: Actor> {
 impl<A: Actor> AddressPair<A> {
     /// Create a new independent pair
     pub fn new() -> Self {
+        Self::with_mailbox(MailboxKind::Unbounded)
+    }
+
+    /// Create a new independent pair with the bounded mailbox.
+    ///
+    /// The pre-created pair ignores `Actor::mailbox_kind`,
+    /// because the mailbox is created here. The zero `capacity`
+    /// creates a rendezvous mailbox.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_mailbox(MailboxKind::Bounded(capacity))
+    }
+
+    /// Create a new independent pair with the mailbox of the `kind`.
+    pub fn with_mailbox(kind: MailboxKind) -> Self {
         let id = Id::unique();
         let (hp_msg_tx, hp_msg_rx) = mpsc::unbounded_channel();
-        let (msg_tx, msg_rx) = mpsc::unbounded_channel();
-        let (join_tx, join_rx) = watch::channel(Status::Alive);
+        let (msg_tx, msg_rx) = mailbox::channel(kind);
+        let (join_tx, join_rx) = watch::channel(ActorStatus::Awake);
+        let (kill_tx, kill_rx) = watch::channel(false);
+        #[cfg(feature = "metrics")]
+        let metrics = Arc::new(MetricsCollector::default());
         let joint = AddressJoint {
             msg_rx,
             hp_msg_rx,
             join_tx,
+            kill_rx,
+            #[cfg(feature = "metrics")]
+            metrics: metrics.clone(),
         };
         let address = Address {
             id,
             hp_msg_tx,
             msg_tx,
             join_rx,
+            kill_tx: Arc::new(kill_tx),
+            interrupts: Arc::new(AtomicUsize::new(0)),
+            supervisor: Arc::new(Mutex::new(SupervisorSlot::new())),
+            events: Arc::new(OnceLock::new()),
+            hp_limit: Arc::new(OnceLock::new()),
+            log_target: Arc::new(OnceLock::new()),
+            #[cfg(feature = "durable")]
+            durable: Arc::new(OnceLock::new()),
+            #[cfg(feature = "metrics")]
+            metrics,
         };
         Self { joint, address }
     }
 
+    /// Create a pair with the mailbox that is suitable for the `actor`.
+    pub(crate) fn for_actor(actor: &A) -> Self {
+        let pair = Self::with_mailbox(actor.mailbox_kind());
+        pair.address.configure(actor);
+        pair
+    }
+
+    /// Create a pair for an `Actor` that will be created later.
+    ///
+    /// The mailbox is created with `Actor::created_mailbox_kind` and other
+    /// settings are applied with `Address::configure` when the instance exists.
+    pub(crate) fn for_created() -> Self {
+        Self::with_mailbox(A::created_mailbox_kind())
+    }
+
     /// Gets address of the pair.
     pub fn address(&self) -> &Address<A> {
         &self.address
     }
 }
 
+impl<A: Actor> Default for AddressPair<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Receiver for data sent by `Address`.
 pub(crate) struct AddressJoint<A: Actor> {
     /// `Receiver` that have to be used to receive incoming messages.
-    pub msg_rx: mpsc::UnboundedReceiver<Envelope<A>>,
+    pub msg_rx: MailboxReceiver<A>,
     /// High-priority receiver
     pub hp_msg_rx: mpsc::UnboundedReceiver<Parcel<A>>,
     /// Sends a signal when the `Actor` completely stopped.
-    pub join_tx: watch::Sender<Status>,
+    pub join_tx: watch::Sender<ActorStatus>,
+    /// Receives a signal to drop the `Actor` immediately.
+    pub kill_rx: watch::Receiver<bool>,
+    /// Counters of processed messages.
+    #[cfg(feature = "metrics")]
+    pub metrics: Arc<MetricsCollector>,
+}
+
+/// Counts queued high-priority messages sent by users.
+#[derive(Debug, Clone)]
+struct HpLimit {
+    capacity: usize,
+    queued: Arc<AtomicUsize>,
+}
+
+impl HpLimit {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            queued: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn acquire(&self) -> Option<HpPermit> {
+        self.queued
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < self.capacity).then_some(n + 1)
+            })
+            .ok()?;
+        Some(HpPermit {
+            queued: self.queued.clone(),
+        })
+    }
+}
+
+/// The slot of a queued high-priority message that is released when
+/// the message is handled or dropped.
+pub(crate) struct HpPermit {
+    queued: Arc<AtomicUsize>,
+}
+
+impl Drop for HpPermit {
+    fn drop(&mut self) {
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// `Address` to send messages to `Actor`.
 ///
 /// Can be compared each other to identify senders to
-/// the same `Actor`.
+/// the same `Actor`. Equality and hashing are based on the `Id`
+/// of the `Actor`, so addresses can be used as keys of maps and sets.
 pub struct Address<A: Actor> {
     // Plain `Id` used (not `IdOf`), because it's `Sync`.
     id: Id,
     /// High-priority messages sender
     hp_msg_tx: mpsc::UnboundedSender<Parcel<A>>,
     /// Ordinary priority messages sender
-    msg_tx: mpsc::UnboundedSender<Envelope<A>>,
-    join_rx: watch::Receiver<Status>,
+    msg_tx: MailboxSender<A>,
+    join_rx: watch::Receiver<ActorStatus>,
+    kill_tx: Arc<watch::Sender<bool>>,
+    /// The number of interruptions that were sent, but not handled yet.
+    interrupts: Arc<AtomicUsize>,
+    /// The supervisor that will be notified when the `Actor` is finished.
+    supervisor: Arc<Mutex<SupervisorSlot<A>>>,
+    /// Subscribers of lifecycle events. It's created by the first subscriber.
+    events: Arc<OnceLock<broadcast::Sender<LifecycleEvent>>>,
+    /// The limit of queued high-priority messages sent by users.
+    hp_limit: Arc<OnceLock<HpLimit>>,
+    /// The log target of the `Actor`.
+    log_target: Arc<OnceLock<String>>,
+    #[cfg(feature = "durable")]
+    durable: Arc<OnceLock<DurableMailbox>>,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<MetricsCollector>,
 }
 
 impl<A: Actor> Clone for Address<A> {
@@ -83,6 +328,16 @@ impl<A: Actor> Clone for Address<A> {
             hp_msg_tx: self.hp_msg_tx.clone(),
             msg_tx: self.msg_tx.clone(),
             join_rx: self.join_rx.clone(),
+            kill_tx: self.kill_tx.clone(),
+            interrupts: self.interrupts.clone(),
+            supervisor: self.supervisor.clone(),
+            events: self.events.clone(),
+            hp_limit: self.hp_limit.clone(),
+            log_target: self.log_target.clone(),
+            #[cfg(feature = "durable")]
+            durable: self.durable.clone(),
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone(),
         }
     }
 }
@@ -109,6 +364,26 @@ impl<A: Actor> Hash for Address<A> {
 }
 
 impl<A: Actor> Address<A> {
+    /// Creates a `WeakAddress` that doesn't keep senders of the `Actor`.
+    pub fn downgrade(&self) -> WeakAddress<A> {
+        WeakAddress {
+            id: self.id.clone(),
+            hp_msg_tx: self.hp_msg_tx.downgrade(),
+            msg_tx: self.msg_tx.downgrade(),
+            join_rx: self.join_rx.clone(),
+            kill_tx: self.kill_tx.clone(),
+            interrupts: self.interrupts.clone(),
+            supervisor: self.supervisor.clone(),
+            events: self.events.clone(),
+            hp_limit: self.hp_limit.clone(),
+            log_target: self.log_target.clone(),
+            #[cfg(feature = "durable")]
+            durable: self.durable.clone(),
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone(),
+        }
+    }
+
     /// Returns a typed id of the `Actor`.
     pub fn id(&self) -> IdOf<A> {
         IdOf::new(self.id.clone())
@@ -118,8 +393,139 @@ impl<A: Actor> Address<A> {
         &self.id
     }
 
+    /// Subscribes to lifecycle events of the `Actor`.
+    ///
+    /// The stream ends after the `LifecycleStage::Done` event. Events that
+    /// happened before the subscription are not included and the oldest
+    /// events are skipped if the subscriber lags more than
+    /// `LIFECYCLE_EVENTS_CAPACITY` events behind.
+    pub fn lifecycle_events(&self) -> impl Stream<Item = LifecycleEvent> {
+        let rx = self
+            .events
+            .get_or_init(|| broadcast::channel(LIFECYCLE_EVENTS_CAPACITY).0)
+            .subscribe();
+        let log_target = self.log_target().to_string();
+        futures::stream::unfold(Some((rx, log_target)), |state| async move {
+            let (mut rx, log_target) = state?;
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        let next = if matches!(event.stage, LifecycleStage::Done(_)) {
+                            None
+                        } else {
+                            Some((rx, log_target))
+                        };
+                        break Some((event, next));
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        log::warn!(target: &log_target, "Lifecycle events subscriber skipped {} events", skipped);
+                    }
+                    Err(RecvError::Closed) => {
+                        break None;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Sends the event to subscribers if there are any.
+    pub(crate) fn lifecycle_event(&self, stage: LifecycleStage) {
+        if let Some(events) = self.events.get() {
+            let event = LifecycleEvent {
+                id: self.id.clone(),
+                stage,
+            };
+            events.send(event).ok();
+        }
+    }
+
+    /// Applies settings of the `actor` that are shared by all addresses.
+    ///
+    /// Settings that were already applied are kept.
+    pub(crate) fn configure(&self, actor: &A) {
+        self.log_target
+            .get_or_init(|| actor.log_target().to_string());
+        if let Some(capacity) = actor.hp_capacity() {
+            self.hp_limit.get_or_init(|| HpLimit::new(capacity));
+        }
+        #[cfg(feature = "durable")]
+        if let Some(mailbox) = actor.durable_mailbox() {
+            self.durable.get_or_init(|| mailbox);
+        }
+    }
+
+    /// The log target of the `Actor` or its type name if
+    /// the `Actor` wasn't created yet.
+    pub(crate) fn log_target(&self) -> &str {
+        self.log_target
+            .get()
+            .map(String::as_str)
+            .unwrap_or_else(|| std::any::type_name::<A>())
+    }
+
+    #[cfg(feature = "durable")]
+    pub(crate) fn durable_mailbox(&self) -> Option<&DurableMailbox> {
+        self.durable.get()
+    }
+
+    pub(crate) fn supervisor_slot(&self) -> MutexGuard<'_, SupervisorSlot<A>> {
+        self.supervisor
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Stops handling of ordinary priority messages by the `Actor`.
+    ///
+    /// Messages accumulate in the mailbox, high-priority messages
+    /// (including the interruption) are still handled. If the mailbox
+    /// is bounded use `act_wait` to wait for the capacity instead of
+    /// failing when it's full.
+    pub fn pause(&self) -> Result<(), SendError> {
+        self.service(SetPaused::new(true))
+    }
+
+    /// Continues handling of ordinary priority messages by the `Actor`.
+    pub fn resume(&self) -> Result<(), SendError> {
+        self.service(SetPaused::new(false))
+    }
+
+    /// Moves the `Actor` under the `new_supervisor`.
+    ///
+    /// The `new_supervisor` starts tracking the `Actor` in the `group` and
+    /// receives the `Eliminated` event when it finishes. The previous supervisor
+    /// stops tracking it and won't be notified. Fails with `SendError::Closed`
+    /// if the `Actor` has finished already or the `new_supervisor` is not alive.
+    pub fn reparent<S>(
+        &mut self,
+        new_supervisor: Address<S>,
+        group: S::GroupBy,
+    ) -> Result<(), SendError>
+    where
+        S: Actor + Eliminated<A>,
+        A: InterruptedBy<S>,
+    {
+        let mut slot = self.supervisor_slot();
+        if slot.is_finished() {
+            return Err(SendError::Closed);
+        }
+        new_supervisor.service(Adopt::<A, S>::new(self.clone(), group))?;
+        if let Err(err) = slot.release() {
+            log::warn!(
+                target: self.log_target(),
+                "The previous supervisor of {} is not available: {}",
+                self.id,
+                err
+            );
+        }
+        slot.assign(new_supervisor, self.id.clone());
+        Ok(())
+    }
+
     /// Just sends an `Action` to the `Actor`.
-    pub fn act<I>(&self, input: I) -> Result<(), Error>
+    ///
+    /// If the mailbox of the `Actor` is bounded and full the call fails
+    /// with `SendError::Full`.
+    pub fn act<I>(&self, input: I) -> Result<(), SendError>
     where
         I: Action,
         A: ActionHandler<I>,
@@ -128,8 +534,150 @@ impl<A: Actor> Address<A> {
         self.normal_priority_send(envelope)
     }
 
+    /// Sends an `Action` to the `Actor` if its mailbox has free capacity.
+    ///
+    /// Never waits and returns the `Action` back if the mailbox is full or closed.
+    /// That lets the caller decide to drop, buffer or retry it.
+    pub fn try_act<I>(&self, input: I) -> Result<(), TrySendError<I>>
+    where
+        I: Action,
+        A: ActionHandler<I>,
+    {
+        self.msg_tx
+            .try_send(input, Envelope::new)
+            .inspect_err(|err| {
+                if !err.is_full() {
+                    dead_letters::report(&self.id, std::any::type_name::<I>());
+                }
+            })?;
+        #[cfg(feature = "metrics")]
+        self.metrics.sent();
+        Ok(())
+    }
+
+    /// Sends an `Action` to the `Actor` waiting for the free capacity
+    /// of its mailbox no longer than the `timeout`.
+    ///
+    /// The error tells the timeout elapsed or the `Actor` doesn't
+    /// receive messages anymore.
+    pub async fn act_timeout<I>(&self, input: I, timeout: Duration) -> Result<(), SendTimeoutError>
+    where
+        I: Action,
+        A: ActionHandler<I>,
+    {
+        let envelope = Envelope::new(input);
+        self.msg_tx.send_timeout(envelope, timeout).await?;
+        #[cfg(feature = "metrics")]
+        self.metrics.sent();
+        Ok(())
+    }
+
+    /// Sends a batch of `Action`s that will be handled contiguously and in order.
+    ///
+    /// The batch takes a single place in the mailbox, that's why messages of
+    /// other senders will never be handled between the actions of the batch.
+    /// Waits for the free capacity if the bounded mailbox is full.
+    ///
+    /// All the actions are handled even if some of them failed.
+    pub async fn act_batch<I>(&self, inputs: Vec<I>) -> Result<(), SendError>
+    where
+        I: Action,
+        A: ActionHandler<I>,
+    {
+        let envelope = Envelope::batch(inputs);
+        self.normal_priority_send_wait(envelope).await
+    }
+
+    /// Sends an `Action` to the `Actor` waiting for the free capacity
+    /// of its mailbox if it's bounded and full.
+    pub async fn act_wait<I>(&self, input: I) -> Result<(), SendError>
+    where
+        I: Action,
+        A: ActionHandler<I>,
+    {
+        let envelope = Envelope::new(input);
+        self.normal_priority_send_wait(envelope).await
+    }
+
+    /// Sends `Action`s one by one in order waiting for the free capacity
+    /// of the mailbox if it's bounded and full.
+    ///
+    /// Returns the number of sent actions. Stops at the first failure, because
+    /// the closed mailbox will never accept anything, and reports how many actions
+    /// were sent before to let the caller resume with the rest.
+    pub async fn send_all<I, T>(&self, msgs: T) -> Result<usize, SendAllError>
+    where
+        I: Action,
+        A: ActionHandler<I>,
+        T: IntoIterator<Item = I>,
+    {
+        let mut sent = 0;
+        for msg in msgs {
+            self.act_wait(msg)
+                .await
+                .map_err(|_| SendAllError { sent })?;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    /// Waits until the `Actor` handles all messages sent before to the ordinary queue.
+    ///
+    /// A sentinel is put to the mailbox and the method resolves when it's handled.
+    /// High-priority and scheduled messages are not covered. Fails with
+    /// `InteractError::NoReply` if the `Actor` stopped before reaching the sentinel.
+    pub async fn flush(&self) -> Result<(), InteractError> {
+        let (tx, rx) = oneshot::channel();
+        self.normal_priority_send_wait(Envelope::new(Flush::new(tx)))
+            .await?;
+        rx.await.map_err(|_| InteractError::NoReply)
+    }
+
+    /// Sends an `Action` that carries a reply sender and waits for the reply.
+    ///
+    /// The `make` function wraps the sender into the `Action` and the handler
+    /// has to send the reply with it. Fails with `AskError::NoReply` if
+    /// the `Actor` dropped the sender without replying.
+    pub async fn ask<I, R, F>(&self, make: F) -> Result<R, AskError>
+    where
+        I: Action,
+        A: ActionHandler<I>,
+        F: FnOnce(oneshot::Sender<R>) -> I,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.act(make(tx))?;
+        rx.await.map_err(|_| AskError::NoReply)
+    }
+
+    /// The same as `ask`, but waits for the reply no longer than the `timeout`.
+    pub async fn ask_timeout<I, R, F>(&self, make: F, timeout: Duration) -> Result<R, AskError>
+    where
+        I: Action,
+        A: ActionHandler<I>,
+        F: FnOnce(oneshot::Sender<R>) -> I,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.act(make(tx))?;
+        let delay = crate::compat::delay(timeout).boxed();
+        match future::select(rx, delay).await {
+            Either::Left((reply, _)) => reply.map_err(|_| AskError::NoReply),
+            Either::Right(((), _)) => Err(AskError::Timeout),
+        }
+    }
+
     /// Just sends an `Action` to the `Actor`.
-    pub fn instant<I>(&self, input: I) -> Result<(), Error>
+    pub fn instant<I>(&self, input: I) -> Result<(), SendError>
+    where
+        I: InstantAction,
+        A: InstantActionHandler<I>,
+    {
+        let parcel = Parcel::new(Operation::Forward, input);
+        self.bounded_high_priority_send(parcel)
+    }
+
+    /// Sends a service message of the framework that is never
+    /// limited by `Actor::hp_capacity`.
+    pub(crate) fn service<I>(&self, input: I) -> Result<(), SendError>
     where
         I: InstantAction,
         A: InstantActionHandler<I>,
@@ -138,8 +686,52 @@ impl<A: Actor> Address<A> {
         self.high_priority_send(parcel)
     }
 
+    /// Sends an ordinary `Action` through the high-priority queue.
+    ///
+    /// The `Action` is handled by the same `ActionHandler`, but before the
+    /// messages waiting in the mailbox and even if the `Actor` is paused.
+    /// The order between actions of the same type sent with `act_hp`
+    /// and `act` is not guaranteed. Fails with `SendError::Full` if
+    /// the `Actor::hp_capacity` limit is reached.
+    pub fn act_hp<I>(&self, input: I) -> Result<(), SendError>
+    where
+        I: Action,
+        A: ActionHandler<I>,
+    {
+        let parcel = Parcel::from_envelope(Envelope::new(input));
+        self.bounded_high_priority_send(parcel)
+    }
+
     /// Just sends an `Action` to the `Actor`.
-    pub fn schedule<I>(&self, input: I, deadline: Instant) -> Result<(), Error>
+    pub fn schedule<I>(&self, input: I, deadline: Instant) -> Result<(), SendError>
+    where
+        I: Send + 'static,
+        A: Scheduled<I>,
+    {
+        self.schedule_item(input, deadline, None)
+    }
+
+    /// Schedules an event that can be canceled with the returned `ScheduleHandle`.
+    pub fn schedule_cancelable<I>(
+        &self,
+        input: I,
+        deadline: Instant,
+    ) -> Result<ScheduleHandle, SendError>
+    where
+        I: Send + 'static,
+        A: Scheduled<I>,
+    {
+        let handle = ScheduleHandle::default();
+        self.schedule_item(input, deadline, Some(handle.clone()))?;
+        Ok(handle)
+    }
+
+    fn schedule_item<I>(
+        &self,
+        input: I,
+        deadline: Instant,
+        handle: Option<ScheduleHandle>,
+    ) -> Result<(), SendError>
     where
         I: Send + 'static,
         A: Scheduled<I>,
@@ -148,38 +740,96 @@ impl<A: Actor> Address<A> {
         let wrapped = ScheduledItem {
             timestamp: deadline,
             item: input,
+            handle,
         };
         let parcel = Parcel::new(operation, wrapped);
-        self.high_priority_send(parcel)
+        self.bounded_high_priority_send(parcel)
     }
 
     /// Send a `Parcel` to unpacking.
-    pub fn unpack_parcel(&self, parcel: Parcel<A>) -> Result<(), Error> {
+    pub fn unpack_parcel(&self, parcel: Parcel<A>) -> Result<(), SendError> {
         self.high_priority_send(parcel)
     }
 
-    fn high_priority_send(&self, parcel: Parcel<A>) -> Result<(), Error> {
-        self.hp_msg_tx
-            .send(parcel)
-            .map_err(|_| Error::msg("can't send a high-priority service message"))
+    /// Sends a high-priority message of a user that takes a slot of `hp_limit`.
+    fn bounded_high_priority_send(&self, mut parcel: Parcel<A>) -> Result<(), SendError> {
+        if let Some(limit) = self.hp_limit.get() {
+            parcel.permit = Some(limit.acquire().ok_or(SendError::Full)?);
+        }
+        self.high_priority_send(parcel)
     }
 
-    fn normal_priority_send(&self, envelope: Envelope<A>) -> Result<(), Error> {
-        self.msg_tx
-            .send(envelope)
-            // TODO: Improve that
-            .map_err(|err| Error::msg(err.to_string()))
+    fn high_priority_send(&self, parcel: Parcel<A>) -> Result<(), SendError> {
+        let message = parcel.envelope.message_type();
+        let interrupt = parcel.interrupt;
+        if interrupt {
+            // Counted before sending, because the runtime can receive it immediately.
+            self.interrupts.fetch_add(1, Ordering::SeqCst);
+        }
+        self.hp_msg_tx.send(parcel).map_err(|_| {
+            if interrupt {
+                self.interrupt_received();
+            }
+            dead_letters::report(&self.id, message);
+            SendError::Closed
+        })
+    }
+
+    fn normal_priority_send(&self, envelope: Envelope<A>) -> Result<(), SendError> {
+        let message = envelope.message_type();
+        self.msg_tx.send(envelope).inspect_err(|_| {
+            if self.msg_tx.is_closed() {
+                dead_letters::report(&self.id, message);
+            }
+        })?;
+        #[cfg(feature = "metrics")]
+        self.metrics.sent();
+        Ok(())
+    }
+
+    async fn normal_priority_send_wait(&self, envelope: Envelope<A>) -> Result<(), SendError> {
+        let message = envelope.message_type();
+        self.msg_tx.send_wait(envelope).await.inspect_err(|_| {
+            if self.msg_tx.is_closed() {
+                dead_letters::report(&self.id, message);
+            }
+        })?;
+        #[cfg(feature = "metrics")]
+        self.metrics.sent();
+        Ok(())
+    }
+
+    pub(crate) fn reserve(&self) -> BoxFuture<'static, Result<MailboxPermit<A>, Error>> {
+        self.msg_tx.reserve()
+    }
+
+    pub(crate) fn send_reserved(&self, permit: MailboxPermit<A>, envelope: Envelope<A>) {
+        permit.send(envelope);
+        #[cfg(feature = "metrics")]
+        self.metrics.sent();
+    }
+
+    /// Converts the `Address` into a `Sink` of messages of the `M` type.
+    ///
+    /// The sink waits for the free capacity of a bounded mailbox.
+    /// Closing of the sink doesn't terminate the `Actor`.
+    pub fn into_sink<M>(self) -> AddressSink<A, M>
+    where
+        M: Action,
+        A: ActionHandler<M>,
+    {
+        AddressSink::new(self)
     }
 
     /// Send `Handler` as an event
-    pub fn send_event(&self, handler: impl Handler<A>) -> Result<(), Error> {
+    pub fn send_event(&self, handler: impl Handler<A>) -> Result<(), SendError> {
         let priority = handler.priority();
         let envelope = Envelope::from_handler(handler);
         match priority {
             Priority::Normal => self.normal_priority_send(envelope),
             Priority::Instant => {
                 let parcel = Parcel::from_envelope(envelope);
-                self.high_priority_send(parcel)
+                self.bounded_high_priority_send(parcel)
             }
         }
     }
@@ -206,36 +856,160 @@ impl<A: Actor> Address<A> {
         InteractionTask::new(self, request)
     }
 
+    /// Sends the `StreamInteraction` request and returns the stream of responses.
+    ///
+    /// The stream ends when the `Actor` dropped the responder or terminated.
+    pub fn interact_stream<I>(&self, request: I) -> Result<ResponseStream<I::Item>, SendError>
+    where
+        I: StreamInteraction,
+        A: ActionHandler<InteractStream<I>>,
+    {
+        let (responder, stream) = response_stream(I::capacity());
+        self.act(InteractStream { request, responder })?;
+        Ok(stream)
+    }
+
+    /// Interacts with the `Actor` and waits for the response no longer than the `timeout`.
+    ///
+    /// Fails with `InteractError::Timeout` if the `Actor` didn't respond in time.
+    pub async fn interact_timeout<I>(
+        &self,
+        request: I,
+        timeout: Duration,
+    ) -> Result<I::Output, InteractError>
+    where
+        I: Interaction,
+        A: ActionHandler<Interact<I>>,
+    {
+        self.interact(request).recv_timeout(timeout).await
+    }
+
+    /// Returns the current lifecycle status of the `Actor`.
+    ///
+    /// It never blocks, because the status is updated by the runtime of the `Actor`.
+    pub fn status(&self) -> ActorStatus {
+        *self.join_rx.borrow()
+    }
+
+    /// The number of messages the bounded mailbox of the `Actor` can accept now.
+    ///
+    /// Returns `None` if the mailbox is unbounded. The value is approximate,
+    /// because other producers and the `Actor` use the mailbox concurrently,
+    /// but it shrinks when the `Actor` lags behind.
+    pub fn capacity_available(&self) -> Option<usize> {
+        self.msg_tx.capacity()
+    }
+
+    /// Returns `true` if the mailbox of the `Actor` doesn't accept messages anymore.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.msg_tx.is_closed()
+    }
+
+    /// Returns `true` if the `Actor` is not terminated yet and accepts
+    /// high-priority messages.
+    ///
+    /// The result is a hint only: the `Actor` can terminate right after
+    /// the check, so sending can fail even if it returned `true`. Use it
+    /// to prune stale addresses or to skip optional messages, but always
+    /// handle errors of sending.
+    pub fn is_connected(&self) -> bool {
+        !self.status().is_done() && !self.hp_msg_tx.is_closed()
+    }
+
+    /// Returns the snapshot of message counters of the `Actor`.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Metrics {
+        self.metrics.snapshot()
+    }
+
     /// Waits when the `Actor` will be terminated.
     ///
     /// It consumes address, because it useless after termination.
     /// Also it prevents blocking queue if `Actor` uses it to detect
     /// the right time for termination.
     pub async fn join(self) {
-        let mut rx = self.join_rx.clone();
-        drop(self);
-        while rx.changed().await.is_ok() {
-            if *rx.borrow() == Status::Stop {
-                break;
+        Joinable::from(self).join().await
+    }
+
+    /// Drops the `Actor` immediately without waiting for its current handler,
+    /// the finalization and the termination of children.
+    ///
+    /// Children are killed as well. Use it as the last resort only,
+    /// because the `Actor` can be interrupted at any `await` point.
+    pub fn kill(&self) {
+        self.kill_tx.send_replace(true);
+    }
+
+    /// Interrupts the `Actor` and waits until it and all its descendants terminated.
+    ///
+    /// Every `Actor` of the tree has to interrupt its children with the `shutdown`
+    /// call of its `Context`. If the tree hasn't terminated before the `deadline`
+    /// the `Actor` is killed with all its descendants and `WaitStatus::TimedOut`
+    /// is returned. A killed `Actor` is done only after its killed child actors
+    /// are done, that's why all actors of the tree are done when it returns.
+    /// Tasks of the tree are stopped, but not awaited.
+    pub async fn shutdown_tree(&self, deadline: Duration) -> Result<WaitStatus, SendError>
+    where
+        A: InterruptedBy<System>,
+    {
+        self.interrupt_by::<System>()?;
+        let joiner = self.clone().join().boxed();
+        let timeout = crate::compat::delay(deadline).boxed();
+        match future::select(joiner, timeout).await {
+            Either::Left(_) => Ok(WaitStatus::Finished),
+            Either::Right((_, joiner)) => {
+                log::warn!(
+                    target: self.log_target(),
+                    "The tree of {:?} hasn't terminated in time, killing it",
+                    self
+                );
+                self.kill();
+                joiner.await;
+                Ok(WaitStatus::TimedOut)
             }
         }
     }
 
+    pub(crate) fn status_receiver(&self) -> watch::Receiver<ActorStatus> {
+        self.join_rx.clone()
+    }
+
     /// Sends an `Interrupt` event.
     ///
     /// It required a `Context` parameter just to restrict using it in
     /// methods other from handlers.
-    pub(crate) fn interrupt_by<T>(&self) -> Result<(), Error>
+    pub(crate) fn interrupt_by<T>(&self) -> Result<(), SendError>
     where
         A: InterruptedBy<T>,
         T: Actor,
     {
-        let parcel = Parcel::new(Operation::Forward, Interrupt::new());
+        self.interrupt_with(InterruptReason::default())
+    }
+
+    /// Sends an `Interrupt` event with the `reason`.
+    pub(crate) fn interrupt_with<T>(&self, reason: InterruptReason) -> Result<(), SendError>
+    where
+        A: InterruptedBy<T>,
+        T: Actor,
+    {
+        let parcel = Parcel::interrupt(Interrupt::<T>::new(reason));
         self.high_priority_send(parcel)
     }
 
+    /// Marks the oldest pending interruption as received by the runtime.
+    pub(crate) fn interrupt_received(&self) {
+        self.interrupts
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .ok();
+    }
+
+    /// Returns `true` if an interruption was sent, but not handled yet.
+    pub(crate) fn is_interrupt_pending(&self) -> bool {
+        self.interrupts.load(Ordering::SeqCst) > 0
+    }
+
     /// Send termination signal to the actor through the normal priority queue.
-    pub fn terminate_by<T>(&self) -> Result<(), Error>
+    pub fn terminate_by<T>(&self) -> Result<(), SendError>
     where
         A: TerminatedBy<T>,
         T: 'static,
@@ -251,7 +1025,7 @@ impl<A: Actor> Address<A> {
     ///
     /// It spawns a routine that groups multiple items into a single chunk
     /// to reduce amount as `async` calls of a handler.
-    pub fn attach<S, M>(&mut self, stream: S, tag: M) -> Result<(), Error>
+    pub fn attach<S, M>(&mut self, stream: S, tag: M) -> Result<(), SendError>
     where
         A: Consumer<S::Item> + StreamAcceptor<S::Item>,
         S: Stream + Send + Unpin + 'static,
@@ -292,6 +1066,90 @@ impl<A: Actor> Address<A> {
     }
 }
 
+/// Weak version of the `Address` that doesn't hold senders of the `Actor`.
+///
+/// When all `Address` instances dropped queues of the `Actor` become drained
+/// even if weak addresses still exist. It works like `Arc` and `Weak` pair.
+pub struct WeakAddress<A: Actor> {
+    id: Id,
+    hp_msg_tx: mpsc::WeakUnboundedSender<Parcel<A>>,
+    msg_tx: WeakMailboxSender<A>,
+    join_rx: watch::Receiver<ActorStatus>,
+    kill_tx: Arc<watch::Sender<bool>>,
+    /// The number of interruptions that were sent, but not handled yet.
+    interrupts: Arc<AtomicUsize>,
+    /// The supervisor that will be notified when the `Actor` is finished.
+    supervisor: Arc<Mutex<SupervisorSlot<A>>>,
+    /// Subscribers of lifecycle events. It's created by the first subscriber.
+    events: Arc<OnceLock<broadcast::Sender<LifecycleEvent>>>,
+    /// The limit of queued high-priority messages sent by users.
+    hp_limit: Arc<OnceLock<HpLimit>>,
+    /// The log target of the `Actor`.
+    log_target: Arc<OnceLock<String>>,
+    #[cfg(feature = "durable")]
+    durable: Arc<OnceLock<DurableMailbox>>,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<MetricsCollector>,
+}
+
+impl<A: Actor> Clone for WeakAddress<A> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            hp_msg_tx: self.hp_msg_tx.clone(),
+            msg_tx: self.msg_tx.clone(),
+            join_rx: self.join_rx.clone(),
+            kill_tx: self.kill_tx.clone(),
+            interrupts: self.interrupts.clone(),
+            supervisor: self.supervisor.clone(),
+            events: self.events.clone(),
+            hp_limit: self.hp_limit.clone(),
+            log_target: self.log_target.clone(),
+            #[cfg(feature = "durable")]
+            durable: self.durable.clone(),
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl<A: Actor> fmt::Debug for WeakAddress<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WeakAddress").field(&self.id).finish()
+    }
+}
+
+impl<A: Actor> WeakAddress<A> {
+    /// Returns a typed id of the `Actor`.
+    pub fn id(&self) -> IdOf<A> {
+        IdOf::new(self.id.clone())
+    }
+
+    /// Tries to get the `Address` back.
+    ///
+    /// Returns `None` if all `Address` instances were dropped.
+    pub fn upgrade(&self) -> Option<Address<A>> {
+        let hp_msg_tx = self.hp_msg_tx.upgrade()?;
+        let msg_tx = self.msg_tx.upgrade()?;
+        Some(Address {
+            id: self.id.clone(),
+            hp_msg_tx,
+            msg_tx,
+            join_rx: self.join_rx.clone(),
+            kill_tx: self.kill_tx.clone(),
+            interrupts: self.interrupts.clone(),
+            supervisor: self.supervisor.clone(),
+            events: self.events.clone(),
+            hp_limit: self.hp_limit.clone(),
+            log_target: self.log_target.clone(),
+            #[cfg(feature = "durable")]
+            durable: self.durable.clone(),
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone(),
+        })
+    }
+}
+
 impl<T, A> From<Address<A>> for Box<dyn ActionRecipient<T>>
 where
     T: Action,
@@ -311,3 +1169,794 @@ where
         Box::new(address)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::linkage::{SendError, SendTimeoutError, TrySendError};
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::channel::oneshot;
+    use futures::StreamExt;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    #[tokio::test]
+    async fn test_bounded_mailbox() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        // The actor is not spawned to keep messages in the mailbox.
+        let pair = AddressPair::<OrderedActor>::with_capacity(2);
+        let address = pair.address();
+        assert_eq!(address.capacity_available(), Some(2));
+        address.act(Seq(1))?;
+        address.act(Seq(1))?;
+        assert_eq!(address.capacity_available(), Some(0));
+        assert!(address.act(Seq(1)).is_err());
+        let err = address.try_act(Seq(1)).unwrap_err();
+        assert!(err.is_full());
+        let err = address
+            .act_timeout(Seq(1), Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SendTimeoutError::Timeout));
+        // High-priority messages are not limited.
+        address.act_hp(Seq(0))?;
+        let unbounded = AddressPair::<OrderedActor>::new();
+        assert_eq!(unbounded.address().capacity_available(), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_try_act() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let AddressPair { joint, address } = AddressPair::<OrderedActor>::with_capacity(1);
+        address.try_act(Seq(1)).map_err(|err| err.reason())?;
+        let err = address.try_act(Seq(2)).unwrap_err();
+        assert!(err.is_full());
+        assert!(matches!(err.reason(), SendError::Full));
+        assert_eq!(err.into_inner().0, 2);
+        // The message is returned back if the actor doesn't receive messages.
+        drop(joint);
+        let err = address.try_act(Seq(3)).unwrap_err();
+        assert!(!err.is_full());
+        assert!(matches!(err.reason(), SendError::Closed));
+        assert_eq!(err.into_inner().0, 3);
+
+        let address = System::spawn(OrderedActor::default());
+        address.try_act(Seq(4)).map_err(|err| err.reason())?;
+        let seen = address.ask(GetSeen).await?;
+        assert_eq!(seen, vec![4]);
+        System::interrupt(&address)?;
+        let observer = address.clone();
+        address.join().await;
+        let err = observer.try_act(Seq(5)).unwrap_err();
+        assert!(matches!(err, TrySendError::Closed(Seq(5))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_act_timeout() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let AddressPair { mut joint, address } = AddressPair::<OrderedActor>::with_capacity(1);
+        address.act(Seq(1))?;
+        let err = address
+            .act_timeout(Seq(2), Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SendTimeoutError::Timeout));
+        // The message is accepted when the capacity was freed in time.
+        let receiver = tokio::spawn(async move {
+            sleep(Duration::from_millis(20)).await;
+            assert!(joint.msg_rx.try_recv().is_some());
+            joint
+        });
+        address.act_timeout(Seq(3), Duration::from_secs(5)).await?;
+        drop(receiver.await?);
+        // Closed mailbox fails immediately and not by the timeout.
+        let err = timeout(
+            Duration::from_secs(1),
+            address.act_timeout(Seq(4), Duration::from_secs(5)),
+        )
+        .await?
+        .unwrap_err();
+        assert!(matches!(err, SendTimeoutError::Closed));
+        Ok(())
+    }
+
+    struct Lagging;
+
+    impl Actor for Lagging {
+        type GroupBy = ();
+
+        fn mailbox_kind(&self) -> MailboxKind {
+            MailboxKind::Bounded(3)
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Lagging {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Lagging {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct Hold {
+        started: oneshot::Sender<()>,
+        release: oneshot::Receiver<()>,
+    }
+
+    impl Action for Hold {}
+
+    #[async_trait]
+    impl ActionHandler<Hold> for Lagging {
+        async fn handle(&mut self, msg: Hold, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            msg.started.send(()).ok();
+            msg.release.await.ok();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Bump> for Lagging {
+        async fn handle(&mut self, _msg: Bump, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_capacity_available() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(Lagging);
+        assert_eq!(address.capacity_available(), Some(3));
+        let (started_tx, started_rx) = oneshot::channel();
+        let (release_tx, release_rx) = oneshot::channel();
+        address.act(Hold {
+            started: started_tx,
+            release: release_rx,
+        })?;
+        started_rx.await?;
+        // The capacity shrinks while the actor lags behind.
+        address.act(Bump)?;
+        address.act(Bump)?;
+        assert_eq!(address.capacity_available(), Some(1));
+        release_tx.send(()).ok();
+        address.flush().await?;
+        assert_eq!(address.capacity_available(), Some(3));
+        System::interrupt(&address)?;
+        address.join().await;
+
+        let unbounded = System::spawn(OrderedActor::default());
+        unbounded.act(Seq(1))?;
+        assert_eq!(unbounded.capacity_available(), None);
+        System::interrupt(&unbounded)?;
+        unbounded.join().await;
+        Ok(())
+    }
+
+    struct AskDouble(u8, oneshot::Sender<u8>);
+
+    impl Action for AskDouble {}
+
+    #[async_trait]
+    impl ActionHandler<AskDouble> for OrderedActor {
+        async fn handle(&mut self, msg: AskDouble, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            let AskDouble(value, reply) = msg;
+            if value > 0 {
+                reply.send(value * 2).ok();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ask() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let reply = address.ask(|tx| AskDouble(2, tx)).await?;
+        assert_eq!(reply, 4);
+        let err = address.ask(|tx| AskDouble(0, tx)).await.unwrap_err();
+        assert!(matches!(err, AskError::NoReply));
+        let reply = address
+            .ask_timeout(|tx| AskDouble(3, tx), Duration::from_secs(1))
+            .await?;
+        assert_eq!(reply, 6);
+        assert_eq!(address.status(), ActorStatus::Running);
+        System::interrupt(&address)?;
+        let observer = address.clone();
+        address.join().await;
+        assert_eq!(observer.status(), ActorStatus::Done);
+        Ok(())
+    }
+
+    struct RawSeq(u8);
+
+    impl Action for RawSeq {}
+
+    #[tokio::test]
+    async fn test_act_batch() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        address.act(Seq(0))?;
+        address.act_batch(vec![Seq(1), Seq(2), Seq(3)]).await?;
+        let mut recipient = address
+            .action_recipient::<Seq>()
+            .contramap(|raw: RawSeq| Seq(raw.0 * 2));
+        recipient.act(RawSeq(4))?;
+        let seen = address.ask(GetSeen).await?;
+        assert_eq!(seen, vec![0, 1, 2, 3, 8]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pause_resume() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        address.pause()?;
+        address.act(Seq(1))?;
+        let res = timeout(Duration::from_millis(50), address.ask(GetSeen)).await;
+        assert!(res.is_err());
+        address.resume()?;
+        let seen = address.ask(GetSeen).await?;
+        assert_eq!(seen, vec![1]);
+        address.pause()?;
+        // The interruption is handled while paused.
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    struct BrokenQuery;
+
+    impl Interaction for BrokenQuery {
+        type Output = ();
+    }
+
+    #[async_trait]
+    impl InteractionHandler<BrokenQuery> for OrderedActor {
+        async fn handle(&mut self, _: BrokenQuery, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Err(Error::msg("broken"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_structured_errors() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let err = address.interact(BrokenQuery).recv().await.unwrap_err();
+        assert!(matches!(&err, InteractError::Handler(_)));
+        assert!(!err.is_transient());
+        assert_eq!(err.into_error().to_string(), "broken");
+        System::interrupt(&address)?;
+        address.clone().join().await;
+        assert_eq!(address.act(Seq(1)), Err(SendError::Closed));
+        let err = address.interact(BrokenQuery).recv().await.unwrap_err();
+        assert!(matches!(err, InteractError::Send(SendError::Closed)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_interact_timeout() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let err = address
+            .interact_timeout(
+                SlowQuery(Duration::from_millis(200)),
+                Duration::from_millis(10),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, InteractError::Timeout));
+        assert!(err.is_transient());
+        let reply = address
+            .interact_timeout(SlowQuery(Duration::from_millis(1)), Duration::from_secs(1))
+            .await?;
+        assert_eq!(reply, 7);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+
+    #[test]
+    fn test_weak_address() {
+        let AddressPair { joint, address } = AddressPair::<OrderedActor>::new();
+        let weak = address.downgrade();
+        assert_eq!(weak.upgrade(), Some(address.clone()));
+        drop(address);
+        assert!(weak.upgrade().is_none());
+        drop(joint);
+    }
+
+    #[test]
+    // The hash depends on the `Id` only.
+    #[allow(clippy::mutable_key_type)]
+    fn test_address_as_key() {
+        let first = AddressPair::<OrderedActor>::new();
+        let second = AddressPair::<OrderedActor>::new();
+        let mut set = std::collections::HashSet::new();
+        set.insert(first.address().clone());
+        set.insert(first.address().clone());
+        set.insert(second.address().clone());
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(first.address()));
+        assert_ne!(first.address(), second.address());
+    }
+
+    struct Boss {
+        worker: Option<oneshot::Sender<Address<Worker>>>,
+        eliminated: Option<oneshot::Sender<TerminationReason>>,
+    }
+
+    impl Actor for Boss {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Boss"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Boss {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            if let Some(tx) = self.worker.take() {
+                let worker = ctx.spawn_actor(Worker, ());
+                tx.send(worker).ok();
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Boss {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Worker> for Boss {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Worker>,
+            reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            if let Some(tx) = self.eliminated.take() {
+                tx.send(reason).ok();
+            }
+            Ok(())
+        }
+    }
+
+    struct Worker;
+
+    impl Actor for Worker {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Worker"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<Boss> for Worker {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<Boss> for Worker {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reparent() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (worker_tx, worker_rx) = oneshot::channel();
+        let (old_tx, mut old_rx) = oneshot::channel();
+        let old_boss = System::spawn(Boss {
+            worker: Some(worker_tx),
+            eliminated: Some(old_tx),
+        });
+        let (new_tx, new_rx) = oneshot::channel();
+        let new_boss = System::spawn(Boss {
+            worker: None,
+            eliminated: Some(new_tx),
+        });
+        let mut worker = worker_rx.await?;
+        worker.reparent(new_boss.clone(), ())?;
+        // The new supervisor interrupts the worker on its own termination.
+        System::interrupt(&new_boss)?;
+        let reason = timeout(Duration::from_secs(5), new_rx).await??;
+        assert_eq!(reason, TerminationReason::Interrupted);
+        new_boss.join().await;
+        worker.clone().join().await;
+        assert!(worker.reparent(old_boss.clone(), ()).is_err());
+        System::interrupt(&old_boss)?;
+        old_boss.join().await;
+        assert!(!matches!(old_rx.try_recv(), Ok(Some(_))));
+        Ok(())
+    }
+
+    struct SlowCounter {
+        handled: Arc<AtomicUsize>,
+    }
+
+    impl Actor for SlowCounter {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "SlowCounter"
+        }
+
+        fn slow_handler_threshold(&self) -> Option<Duration> {
+            Some(Duration::from_millis(1))
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for SlowCounter {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for SlowCounter {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Bump> for SlowCounter {
+        async fn handle(&mut self, _: Bump, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            sleep(Duration::from_millis(5)).await;
+            self.handled.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flush() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let handled = Arc::new(AtomicUsize::new(0));
+        let address = System::spawn(SlowCounter {
+            handled: handled.clone(),
+        });
+        for _ in 0..5 {
+            address.act(Bump)?;
+        }
+        address.flush().await?;
+        assert_eq!(handled.load(Ordering::SeqCst), 5);
+        System::interrupt(&address)?;
+        address.join().await;
+
+        // The sentinel is dropped if the actor stops before reaching it.
+        let stopper = System::spawn(Stopper {
+            pinged: Arc::new(AtomicBool::new(false)),
+        });
+        stopper.act(StopLater)?;
+        let err = stopper.flush().await.unwrap_err();
+        assert!(matches!(err, InteractError::NoReply));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_act_hp() -> Result<(), Error> {
+        use crate::testing::TestProbe;
+        let mut probe = TestProbe::new(OrderedActor::default());
+        probe.start::<System>().await?;
+        probe.address().act(Seq(1))?;
+        probe.address().act(Seq(2))?;
+        probe.address().act_hp(Seq(3))?;
+        assert_eq!(probe.run_pending().await?, 3);
+        assert_eq!(probe.actor().seen, vec![3, 1, 2]);
+        Ok(())
+    }
+
+    struct TreeRoot {
+        child: Arc<Mutex<Option<Address<Stubborn>>>>,
+    }
+
+    impl Actor for TreeRoot {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "TreeRoot"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for TreeRoot {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let address = ctx.spawn_actor(Stubborn, ());
+            *self.child.lock().unwrap() = Some(address);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for TreeRoot {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<Stubborn> for TreeRoot {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Stubborn>,
+            _reason: TerminationReason,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    struct Stubborn;
+
+    impl Actor for Stubborn {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Stubborn"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<TreeRoot> for Stubborn {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<TreeRoot> for Stubborn {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            // Ignores the interruption.
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_tree() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let status = address.shutdown_tree(Duration::from_secs(5)).await?;
+        assert_eq!(status, WaitStatus::Finished);
+
+        let child = Arc::new(Mutex::new(None));
+        let address = System::spawn(TreeRoot {
+            child: child.clone(),
+        });
+        address.flush().await?;
+        let status = address.shutdown_tree(Duration::from_millis(50)).await?;
+        assert_eq!(status, WaitStatus::TimedOut);
+        // The stubborn child is killed with the root.
+        let child = child.lock().unwrap().take().unwrap();
+        timeout(Duration::from_secs(5), child.join()).await?;
+        Ok(())
+    }
+
+    struct Echo(u32);
+
+    impl Interaction for Echo {
+        type Output = u32;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<Echo> for OrderedActor {
+        async fn handle(&mut self, msg: Echo, _ctx: &mut Context<Self>) -> Result<u32, Error> {
+            Ok(msg.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interact_failover() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let primary = System::spawn(OrderedActor::default());
+        let backup = System::spawn(OrderedActor::default());
+        System::interrupt(&primary)?;
+        primary.clone().join().await;
+        let failure = primary
+            .interact(Echo(7))
+            .recv_or_return()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            failure,
+            InteractFailure::Undelivered {
+                reason: SendError::Closed,
+                ..
+            }
+        ));
+        let request = failure.into_request().unwrap();
+        assert_eq!(
+            backup.interact(request).recv_or_return().await.ok(),
+            Some(7)
+        );
+        let err = primary.interact(Echo(8)).recv().await.unwrap_err();
+        assert!(matches!(err, InteractError::Send(SendError::Closed)));
+        System::interrupt(&backup)?;
+        backup.join().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_all() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let sent = address.send_all((1..=3).map(Published)).await?;
+        assert_eq!(sent, 3);
+        assert_eq!(address.ask(GetSeen).await?, vec![1, 2, 3]);
+        System::interrupt(&address)?;
+        address.clone().join().await;
+        let err = address.send_all(vec![Published(4)]).await.unwrap_err();
+        assert_eq!(err, SendAllError { sent: 0 });
+        Ok(())
+    }
+
+    struct Eventful;
+
+    impl Actor for Eventful {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Eventful"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Eventful {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.spawn_task(FnTask(async move { Ok(()) }), (), ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl FnTaskEliminated<(), ()> for Eventful {
+        async fn handle(
+            &mut self,
+            _id: Id,
+            _tag: (),
+            _result: Result<(), TaskError>,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_events() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(Eventful);
+        let events = address.lifecycle_events();
+        let events: Vec<_> = timeout(Duration::from_secs(5), events.collect()).await?;
+        let id: Id = address.id().into();
+        assert!(events.iter().all(|event| event.id == id));
+        let stages: Vec<_> = events.into_iter().map(|event| event.stage).collect();
+        assert_eq!(stages[0], LifecycleStage::Awake);
+        assert_eq!(stages[1], LifecycleStage::Running);
+        assert!(matches!(stages[2], LifecycleStage::ChildEliminated(_)));
+        assert_eq!(
+            stages.last(),
+            Some(&LifecycleStage::Done(TerminationReason::Normal))
+        );
+        address.join().await;
+        Ok(())
+    }
+
+    struct HpLimited;
+
+    impl Actor for HpLimited {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "HpLimited"
+        }
+
+        fn hp_capacity(&self) -> Option<usize> {
+            Some(2)
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for HpLimited {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for HpLimited {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InstantActionHandler<UrgentTouch> for HpLimited {
+        async fn handle(&mut self, _: UrgentTouch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Touch> for HpLimited {
+        async fn handle(&mut self, _: Touch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hp_capacity() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(HpLimited);
+        // The actor doesn't run until the test yields.
+        address.instant(UrgentTouch)?;
+        address.instant(UrgentTouch)?;
+        let err = address.instant(UrgentTouch).unwrap_err();
+        assert!(matches!(err, SendError::Full));
+        sleep(Duration::from_millis(50)).await;
+        address.instant(UrgentTouch)?;
+        address.act_hp(Touch)?;
+        let err = address.act_hp(Touch).unwrap_err();
+        assert!(matches!(err, SendError::Full));
+        // Service messages are not limited.
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_is_connected() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(Session);
+        assert!(address.is_connected());
+        System::interrupt(&address)?;
+        address.clone().join().await;
+        assert!(!address.is_connected());
+        assert!(address.act(Touch).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_tree_confirms_descendants() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let child = Arc::new(Mutex::new(None));
+        let address = System::spawn(TreeRoot {
+            child: child.clone(),
+        });
+        address.flush().await?;
+        let child = child.lock().unwrap().take().unwrap();
+        let status = address.shutdown_tree(Duration::from_millis(50)).await?;
+        assert_eq!(status, WaitStatus::TimedOut);
+        // The killed child is done before the root is.
+        assert_eq!(child.status(), ActorStatus::Done);
+        Ok(())
+    }
+}
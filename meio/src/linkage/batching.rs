@@ -0,0 +1,229 @@
+//! Contains a recipient that coalesces actions into batches.
+
+use super::ActionRecipient;
+use crate::handlers::Action;
+use crate::ids::Id;
+use anyhow::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::time::timeout_at;
+
+impl<T: Action> Action for Vec<T> {}
+
+/// The recipient that accumulates actions and sends them as a `Vec`
+/// to the inner recipient.
+///
+/// A batch is flushed when it reaches `max_items` or when the `period`
+/// elapsed after the first action in the batch. The remainder is flushed
+/// when all clones of the recipient are dropped.
+pub struct BatchingRecipient<T: Action> {
+    id: Id,
+    tx: mpsc::UnboundedSender<T>,
+}
+
+impl<T: Action> BatchingRecipient<T> {
+    /// Creates a new recipient and spawns a flushing routine for it.
+    pub fn new(
+        recipient: impl ActionRecipient<Vec<T>>,
+        max_items: usize,
+        period: Duration,
+    ) -> Self {
+        let id = recipient.id_ref().clone();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let batcher = Batcher {
+            rx,
+            recipient: Box::new(recipient),
+            max_items: max_items.max(1),
+            period,
+            buffer: Vec::new(),
+        };
+        crate::compat::spawn_async(batcher.routine());
+        Self { id, tx }
+    }
+}
+
+impl<T: Action> Clone for BatchingRecipient<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+impl<T: Action> fmt::Debug for BatchingRecipient<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BatchingRecipient").field(&self.id).finish()
+    }
+}
+
+impl<T: Action> ActionRecipient<T> for BatchingRecipient<T> {
+    fn act(&mut self, msg: T) -> Result<(), Error> {
+        self.tx
+            .send(msg)
+            .map_err(|_| Error::msg("the batching routine is finished"))
+    }
+
+    fn id_ref(&self) -> &Id {
+        &self.id
+    }
+
+    fn dyn_clone(&self) -> Box<dyn ActionRecipient<T>> {
+        Box::new(self.clone())
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        self.id.hash(&mut Box::new(state));
+    }
+
+    fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+struct Batcher<T: Action> {
+    rx: mpsc::UnboundedReceiver<T>,
+    recipient: Box<dyn ActionRecipient<Vec<T>>>,
+    max_items: usize,
+    period: Duration,
+    buffer: Vec<T>,
+}
+
+impl<T: Action> Batcher<T> {
+    async fn routine(mut self) {
+        let mut deadline = None;
+        loop {
+            let item = match deadline {
+                Some(at) => match timeout_at(at, self.rx.recv()).await {
+                    Ok(item) => item,
+                    Err(_elapsed) => {
+                        if self.flush().await.is_err() {
+                            break;
+                        }
+                        deadline = None;
+                        continue;
+                    }
+                },
+                None => self.rx.recv().await,
+            };
+            match item {
+                Some(item) => {
+                    if self.buffer.is_empty() {
+                        deadline = Some((Instant::now() + self.period).into());
+                    }
+                    self.buffer.push(item);
+                    if self.buffer.len() >= self.max_items && self.flush().await.is_err() {
+                        break;
+                    }
+                }
+                None => {
+                    self.flush().await.ok();
+                    break;
+                }
+            }
+            if self.buffer.is_empty() {
+                deadline = None;
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.buffer);
+        let res = self.recipient.act_wait(batch).await;
+        if let Err(err) = &res {
+            log::warn!("Can't flush a batch to {:?}: {}", self.recipient, err);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    #[derive(Default)]
+    struct BatchCollector {
+        batches: Vec<Vec<Seq>>,
+    }
+
+    impl Actor for BatchCollector {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "BatchCollector"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for BatchCollector {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for BatchCollector {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Vec<Seq>> for BatchCollector {
+        async fn handle(&mut self, msg: Vec<Seq>, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.batches.push(msg);
+            Ok(())
+        }
+    }
+
+    struct GetBatches;
+
+    impl Interaction for GetBatches {
+        type Output = Vec<usize>;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<GetBatches> for BatchCollector {
+        async fn handle(
+            &mut self,
+            _msg: GetBatches,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Vec<usize>, Error> {
+            Ok(self.batches.iter().map(Vec::len).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batching_recipient() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(BatchCollector::default());
+        let mut recipient = BatchingRecipient::new(address.clone(), 3, Duration::from_millis(50));
+        for n in 0..4 {
+            recipient.act(Seq(n))?;
+        }
+        // The full batch is sent immediately and the rest after the period.
+        sleep(Duration::from_millis(20)).await;
+        assert_eq!(address.interact(GetBatches).recv().await?, vec![3]);
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(address.interact(GetBatches).recv().await?, vec![3, 1]);
+        // The remainder is flushed when the recipient is dropped.
+        recipient.act(Seq(5))?;
+        drop(recipient);
+        sleep(Duration::from_millis(20)).await;
+        assert_eq!(address.interact(GetBatches).recv().await?, vec![3, 1, 1]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use crate::actor_runtime::Actor;
+use crate::handlers::{Action, ActionHandler};
+use crate::linkage::{Address, SendError};
+use std::fmt;
+
+/// The group of addresses of the same `Actor` type that receive the same actions.
+pub struct Broadcast<A: Actor> {
+    addresses: Vec<Address<A>>,
+}
+
+impl<A: Actor> Default for Broadcast<A> {
+    fn default() -> Self {
+        Self {
+            addresses: Vec::new(),
+        }
+    }
+}
+
+impl<A: Actor> fmt::Debug for Broadcast<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.addresses).finish()
+    }
+}
+
+impl<A: Actor> From<Vec<Address<A>>> for Broadcast<A> {
+    fn from(addresses: Vec<Address<A>>) -> Self {
+        Self { addresses }
+    }
+}
+
+impl<A: Actor> Broadcast<A> {
+    /// Creates a new empty group.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an `Address` to the group.
+    pub fn insert(&mut self, address: Address<A>) {
+        self.addresses.push(address);
+    }
+
+    /// Sends a clone of the action to every member of the group.
+    ///
+    /// It doesn't stop if sending to a member failed and returns
+    /// results in the same order the members were added.
+    pub fn act_all<T>(&self, action: T) -> Vec<Result<(), SendError>>
+    where
+        T: Action + Clone,
+        A: ActionHandler<T>,
+    {
+        self.addresses
+            .iter()
+            .map(|address| address.act(action.clone()))
+            .collect()
+    }
+
+    /// Size of the group.
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    /// Is this group empty?
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+
+    #[tokio::test]
+    async fn test_broadcast() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let first = System::spawn(OrderedActor::default());
+        let closed = System::spawn(OrderedActor::default());
+        let last = System::spawn(OrderedActor::default());
+        System::interrupt(&closed)?;
+        closed.clone().join().await;
+        let group = Broadcast::from(vec![first.clone(), closed, last.clone()]);
+        assert_eq!(group.len(), 3);
+        // The closed member doesn't stop sending to the others.
+        let results = group.act_all(Published(5));
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(SendError::Closed)));
+        assert!(results[2].is_ok());
+        for address in [first, last] {
+            assert_eq!(address.ask(GetSeen).await?, vec![5]);
+            System::interrupt(&address)?;
+            address.join().await;
+        }
+        Ok(())
+    }
+}
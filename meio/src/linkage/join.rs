@@ -0,0 +1,89 @@
+//! Contains a type-erased handle to wait for actors of different types.
+
+use super::Address;
+use crate::actor_runtime::{Actor, ActorStatus};
+use crate::compat::watch;
+use crate::ids::Id;
+use futures::future;
+use std::fmt;
+
+/// The handle to wait for the termination of an `Actor` of any type.
+///
+/// It doesn't keep the `Actor` alive.
+#[derive(Clone)]
+pub struct Joinable {
+    id: Id,
+    join_rx: watch::Receiver<ActorStatus>,
+}
+
+impl fmt::Debug for Joinable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Joinable").field(&self.id).finish()
+    }
+}
+
+impl<A: Actor> From<Address<A>> for Joinable {
+    fn from(address: Address<A>) -> Self {
+        Self {
+            id: address.raw_id().clone(),
+            join_rx: address.status_receiver(),
+        }
+    }
+}
+
+impl Joinable {
+    /// Returns `Id` of the `Actor`.
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    /// Returns `true` if the `Actor` is terminated.
+    pub fn is_done(&self) -> bool {
+        self.join_rx.borrow().is_done()
+    }
+
+    /// Waits for the `Actor` termination.
+    pub async fn join(mut self) {
+        loop {
+            if self.join_rx.borrow().is_done() {
+                break;
+            }
+            if self.join_rx.changed().await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Waits until all the actors are terminated.
+pub async fn join_all<I, T>(actors: I)
+where
+    I: IntoIterator<Item = T>,
+    T: Into<Joinable>,
+{
+    let joins = actors.into_iter().map(|actor| actor.into().join());
+    future::join_all(joins).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_join_all() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let first = System::spawn(OrderedActor::default());
+        let second = System::spawn(Stopper::default());
+        let handles: Vec<Joinable> = vec![first.clone().into(), second.clone().into()];
+        System::interrupt(&first)?;
+        System::interrupt(&second)?;
+        timeout(Duration::from_secs(5), crate::join_all(handles)).await?;
+        assert!(first.status().is_done());
+        assert!(second.status().is_done());
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+//! Contains a generic `Link` to an `Actor`.
+
+use super::{Address, InteractError, SendError};
+use crate::actor_runtime::Actor;
+use crate::handlers::{Action, ActionHandler, Interact, Interaction};
+use std::fmt;
+
+/// A generic `Link` to an `Actor`.
+///
+/// It's a base for domain-specific links. Wrap it into a struct
+/// and add methods that send private message types with `send`
+/// and `request`:
+///
+/// ```ignore
+/// #[derive(From)]
+/// pub struct ServerLink {
+///     link: Link<Server>,
+/// }
+///
+/// impl ServerLink {
+///     pub fn add_route(&mut self, route: Route) -> Result<(), Error> {
+///         self.link.send(AddRoute { route })
+///     }
+/// }
+/// ```
+///
+/// Such a link can be created with `address.link()`.
+pub struct Link<A: Actor> {
+    address: Address<A>,
+}
+
+impl<A: Actor> Clone for Link<A> {
+    fn clone(&self) -> Self {
+        Self {
+            address: self.address.clone(),
+        }
+    }
+}
+
+impl<A: Actor> fmt::Debug for Link<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Link").field(&self.address).finish()
+    }
+}
+
+impl<A: Actor> From<Address<A>> for Link<A> {
+    fn from(address: Address<A>) -> Self {
+        Self { address }
+    }
+}
+
+impl<A: Actor> From<Link<A>> for Address<A> {
+    fn from(link: Link<A>) -> Self {
+        link.address
+    }
+}
+
+impl<A: Actor> Link<A> {
+    /// Returns the `Address` of the `Actor`.
+    pub fn address(&self) -> &Address<A> {
+        &self.address
+    }
+
+    /// Sends an `Action` to the `Actor`.
+    pub fn send<M>(&mut self, msg: M) -> Result<(), SendError>
+    where
+        M: Action,
+        A: ActionHandler<M>,
+    {
+        self.address.act(msg)
+    }
+
+    /// Sends an `Interaction` to the `Actor` and waits for the response.
+    pub async fn request<I>(&mut self, request: I) -> Result<I::Output, InteractError>
+    where
+        I: Interaction,
+        A: ActionHandler<Interact<I>>,
+    {
+        self.address.interact(request).recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_generic_link() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let mut link: Link<OrderedActor> = address.link();
+        link.send(Seq(1))?;
+        let reply = link.request(SlowQuery(Duration::from_millis(1))).await?;
+        assert_eq!(reply, 7);
+        System::interrupt(link.address())?;
+        address.join().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,416 @@
+//! The queue of ordinary priority messages of an `Actor`.
+
+use crate::actor_runtime::Actor;
+use crate::handlers::Envelope;
+use anyhow::Error;
+use futures::future::{self, BoxFuture, FutureExt};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+/// The kind of the ordinary priority queue (mailbox) of an `Actor`.
+///
+/// High-priority messages are always sent over an unbounded channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailboxKind {
+    /// The mailbox accepts any number of messages.
+    Unbounded,
+    /// The mailbox accepts up to the given number of messages.
+    ///
+    /// The zero capacity makes the mailbox a `Rendezvous`.
+    Bounded(usize),
+    /// The mailbox hands messages off to the `Actor` directly.
+    ///
+    /// Waiting sends (like `act_wait`) complete only when the `Actor`
+    /// dequeued the message. Non-blocking sends succeed only if no
+    /// other message is waiting for the handoff.
+    Rendezvous,
+}
+
+impl From<Option<usize>> for MailboxKind {
+    fn from(capacity: Option<usize>) -> Self {
+        match capacity {
+            Some(capacity) => Self::Bounded(capacity),
+            None => Self::Unbounded,
+        }
+    }
+}
+
+/// Creates a new mailbox of the `kind`.
+pub(crate) fn channel<A: Actor>(kind: MailboxKind) -> (MailboxSender<A>, MailboxReceiver<A>) {
+    match kind {
+        MailboxKind::Bounded(capacity) if capacity > 0 => {
+            let (tx, rx) = mpsc::channel(capacity);
+            (MailboxSender::Bounded(tx), MailboxReceiver::Bounded(rx))
+        }
+        MailboxKind::Unbounded => {
+            let (tx, rx) = mpsc::unbounded_channel();
+            (MailboxSender::Unbounded(tx), MailboxReceiver::Unbounded(rx))
+        }
+        // A channel can't have the zero capacity.
+        MailboxKind::Bounded(_) | MailboxKind::Rendezvous => {
+            // The only slot is taken until the envelope is dequeued.
+            let (tx, rx) = mpsc::channel(1);
+            (MailboxSender::Rendezvous(tx), MailboxReceiver::Bounded(rx))
+        }
+    }
+}
+
+/// Sends envelopes to the mailbox.
+pub(crate) enum MailboxSender<A: Actor> {
+    Bounded(mpsc::Sender<Envelope<A>>),
+    Unbounded(mpsc::UnboundedSender<Envelope<A>>),
+    Rendezvous(mpsc::Sender<Envelope<A>>),
+}
+
+impl<A: Actor> Clone for MailboxSender<A> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Bounded(tx) => Self::Bounded(tx.clone()),
+            Self::Unbounded(tx) => Self::Unbounded(tx.clone()),
+            Self::Rendezvous(tx) => Self::Rendezvous(tx.clone()),
+        }
+    }
+}
+
+/// Waits until the only slot of a rendezvous mailbox is free,
+/// that means the previously sent envelope was dequeued.
+async fn wait_handoff<A: Actor>(tx: &mpsc::Sender<Envelope<A>>) {
+    // The closed mailbox won't dequeue anything anymore.
+    tx.reserve().await.ok();
+}
+
+impl<A: Actor> MailboxSender<A> {
+    /// Sends an envelope without waiting.
+    ///
+    /// Fails if the bounded mailbox is full.
+    pub fn send(&self, envelope: Envelope<A>) -> Result<(), SendError> {
+        match self {
+            Self::Bounded(tx) | Self::Rendezvous(tx) => {
+                tx.try_send(envelope).map_err(|err| match err {
+                    mpsc::error::TrySendError::Full(_) => SendError::Full,
+                    mpsc::error::TrySendError::Closed(_) => SendError::Closed,
+                })
+            }
+            Self::Unbounded(tx) => tx.send(envelope).map_err(|_| SendError::Closed),
+        }
+    }
+
+    /// Waits for the free capacity of the mailbox and sends an envelope.
+    ///
+    /// For the rendezvous mailbox waits until the envelope is dequeued.
+    pub async fn send_wait(&self, envelope: Envelope<A>) -> Result<(), SendError> {
+        match self {
+            Self::Bounded(tx) => tx.send(envelope).await.map_err(|_| SendError::Closed),
+            Self::Unbounded(tx) => tx.send(envelope).map_err(|_| SendError::Closed),
+            Self::Rendezvous(tx) => {
+                tx.send(envelope).await.map_err(|_| SendError::Closed)?;
+                wait_handoff(tx).await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Waits for the free capacity of the mailbox no longer than the `timeout`.
+    ///
+    /// For the rendezvous mailbox the `timeout` also covers the handoff,
+    /// but the envelope that was accepted before the timeout is still handled.
+    pub async fn send_timeout(
+        &self,
+        envelope: Envelope<A>,
+        timeout: Duration,
+    ) -> Result<(), SendTimeoutError> {
+        match self {
+            Self::Bounded(tx) => {
+                let send = tx.send(envelope).boxed();
+                let delay = crate::compat::delay(timeout).boxed();
+                match future::select(send, delay).await {
+                    future::Either::Left((res, _)) => res.map_err(|_| SendTimeoutError::Closed),
+                    future::Either::Right(((), _)) => Err(SendTimeoutError::Timeout),
+                }
+            }
+            Self::Unbounded(tx) => tx.send(envelope).map_err(|_| SendTimeoutError::Closed),
+            Self::Rendezvous(tx) => {
+                let handoff = async {
+                    tx.send(envelope)
+                        .await
+                        .map_err(|_| SendTimeoutError::Closed)?;
+                    wait_handoff(tx).await;
+                    Ok(())
+                };
+                let delay = crate::compat::delay(timeout).boxed();
+                match future::select(handoff.boxed(), delay).await {
+                    future::Either::Left((res, _)) => res,
+                    future::Either::Right(((), _)) => Err(SendTimeoutError::Timeout),
+                }
+            }
+        }
+    }
+
+    /// Sends an envelope created from the `input` if the mailbox has free capacity.
+    ///
+    /// Returns the `input` back if the envelope can't be sent.
+    pub fn try_send<I, F>(&self, input: I, wrap: F) -> Result<(), TrySendError<I>>
+    where
+        F: FnOnce(I) -> Envelope<A>,
+    {
+        match self {
+            Self::Bounded(tx) | Self::Rendezvous(tx) => match tx.try_reserve() {
+                Ok(permit) => {
+                    permit.send(wrap(input));
+                    Ok(())
+                }
+                Err(mpsc::error::TrySendError::Full(())) => Err(TrySendError::Full(input)),
+                Err(mpsc::error::TrySendError::Closed(())) => Err(TrySendError::Closed(input)),
+            },
+            Self::Unbounded(tx) => {
+                if tx.is_closed() {
+                    Err(TrySendError::Closed(input))
+                } else {
+                    // If the mailbox closed right after the check above the envelope
+                    // will be dropped the same way like it was in the closed queue.
+                    tx.send(wrap(input)).ok();
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Waits for the free capacity of the mailbox and reserves it.
+    pub fn reserve(&self) -> BoxFuture<'static, Result<MailboxPermit<A>, Error>> {
+        match self {
+            Self::Bounded(tx) | Self::Rendezvous(tx) => {
+                let tx = tx.clone();
+                async move {
+                    tx.reserve_owned()
+                        .await
+                        .map(MailboxPermit::Bounded)
+                        .map_err(|err| Error::msg(err.to_string()))
+                }
+                .boxed()
+            }
+            Self::Unbounded(tx) => {
+                let res = if tx.is_closed() {
+                    Err(Error::msg("the mailbox is closed"))
+                } else {
+                    Ok(MailboxPermit::Unbounded(tx.clone()))
+                };
+                future::ready(res).boxed()
+            }
+        }
+    }
+
+    /// The number of free places in the bounded mailbox.
+    pub fn capacity(&self) -> Option<usize> {
+        match self {
+            Self::Bounded(tx) | Self::Rendezvous(tx) => Some(tx.capacity()),
+            Self::Unbounded(_) => None,
+        }
+    }
+
+    /// Returns `true` if the mailbox doesn't receive envelopes anymore.
+    pub fn is_closed(&self) -> bool {
+        match self {
+            Self::Bounded(tx) | Self::Rendezvous(tx) => tx.is_closed(),
+            Self::Unbounded(tx) => tx.is_closed(),
+        }
+    }
+
+    /// Creates a sender that doesn't keep the mailbox open.
+    pub fn downgrade(&self) -> WeakMailboxSender<A> {
+        match self {
+            Self::Bounded(tx) => WeakMailboxSender::Bounded(tx.downgrade()),
+            Self::Unbounded(tx) => WeakMailboxSender::Unbounded(tx.downgrade()),
+            Self::Rendezvous(tx) => WeakMailboxSender::Rendezvous(tx.downgrade()),
+        }
+    }
+}
+
+/// The reserved capacity of the mailbox.
+pub(crate) enum MailboxPermit<A: Actor> {
+    Bounded(mpsc::OwnedPermit<Envelope<A>>),
+    Unbounded(mpsc::UnboundedSender<Envelope<A>>),
+}
+
+impl<A: Actor> MailboxPermit<A> {
+    /// Sends an envelope using the reserved capacity.
+    pub fn send(self, envelope: Envelope<A>) {
+        match self {
+            Self::Bounded(permit) => {
+                permit.send(envelope);
+            }
+            Self::Unbounded(tx) => {
+                // The closed mailbox drops envelopes the same way.
+                tx.send(envelope).ok();
+            }
+        }
+    }
+}
+
+/// The error of sending a message to the mailbox of an `Actor`.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum SendError {
+    /// The bounded mailbox has no free capacity.
+    #[error("the mailbox is full")]
+    Full,
+    /// The `Actor` doesn't receive messages anymore.
+    #[error("the mailbox is closed")]
+    Closed,
+}
+
+impl SendError {
+    /// Returns `true` if sending can succeed later.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Full)
+    }
+}
+
+/// The error of a non-blocking sending that contains the unsent message.
+#[derive(Debug, Error)]
+pub enum TrySendError<T> {
+    /// The bounded mailbox has no free capacity.
+    #[error("the mailbox is full")]
+    Full(T),
+    /// The `Actor` doesn't receive messages anymore.
+    #[error("the mailbox is closed")]
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Returns the message that wasn't sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(value) | Self::Closed(value) => value,
+        }
+    }
+
+    /// Returns `true` if the mailbox was full.
+    pub fn is_full(&self) -> bool {
+        matches!(self, Self::Full(_))
+    }
+
+    /// Returns the reason without the message.
+    pub fn reason(&self) -> SendError {
+        match self {
+            Self::Full(_) => SendError::Full,
+            Self::Closed(_) => SendError::Closed,
+        }
+    }
+}
+
+/// The error of a sending that waits for the free capacity of the mailbox.
+#[derive(Debug, Error)]
+pub enum SendTimeoutError {
+    /// The mailbox hasn't accepted the message in time.
+    #[error("the mailbox hasn't accepted the message in time")]
+    Timeout,
+    /// The `Actor` doesn't receive messages anymore.
+    #[error("the mailbox is closed")]
+    Closed,
+}
+
+/// Sender that doesn't keep the mailbox open.
+pub(crate) enum WeakMailboxSender<A: Actor> {
+    Bounded(mpsc::WeakSender<Envelope<A>>),
+    Unbounded(mpsc::WeakUnboundedSender<Envelope<A>>),
+    Rendezvous(mpsc::WeakSender<Envelope<A>>),
+}
+
+impl<A: Actor> Clone for WeakMailboxSender<A> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Bounded(tx) => Self::Bounded(tx.clone()),
+            Self::Unbounded(tx) => Self::Unbounded(tx.clone()),
+            Self::Rendezvous(tx) => Self::Rendezvous(tx.clone()),
+        }
+    }
+}
+
+impl<A: Actor> WeakMailboxSender<A> {
+    /// Returns the sender if any other sender still exists.
+    pub fn upgrade(&self) -> Option<MailboxSender<A>> {
+        match self {
+            Self::Bounded(tx) => tx.upgrade().map(MailboxSender::Bounded),
+            Self::Unbounded(tx) => tx.upgrade().map(MailboxSender::Unbounded),
+            Self::Rendezvous(tx) => tx.upgrade().map(MailboxSender::Rendezvous),
+        }
+    }
+}
+
+/// Receives envelopes from the mailbox.
+pub(crate) enum MailboxReceiver<A: Actor> {
+    Bounded(mpsc::Receiver<Envelope<A>>),
+    Unbounded(mpsc::UnboundedReceiver<Envelope<A>>),
+}
+
+impl<A: Actor> MailboxReceiver<A> {
+    /// Closes the mailbox for new envelopes, but keeps the buffered ones.
+    pub fn close(&mut self) {
+        match self {
+            Self::Bounded(rx) => rx.close(),
+            Self::Unbounded(rx) => rx.close(),
+        }
+    }
+
+    /// Receives the next envelope if it's available immediately.
+    pub fn try_recv(&mut self) -> Option<Envelope<A>> {
+        match self {
+            Self::Bounded(rx) => rx.try_recv().ok(),
+            Self::Unbounded(rx) => rx.try_recv().ok(),
+        }
+    }
+
+    /// Receives the next envelope.
+    pub async fn recv(&mut self) -> Option<Envelope<A>> {
+        match self {
+            Self::Bounded(rx) => rx.recv().await,
+            Self::Unbounded(rx) => rx.recv().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_rendezvous_mailbox() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let actor = OrderedActor {
+            rendezvous: true,
+            ..Default::default()
+        };
+        let address = System::spawn(actor);
+        address.pause()?;
+        let sender = address.clone();
+        let mut handoff = tokio::spawn(async move { sender.act_wait(Seq(1)).await });
+        // The message waits in the mailbox until the actor takes it.
+        assert!(timeout(Duration::from_millis(50), &mut handoff)
+            .await
+            .is_err());
+        assert!(address.try_act(Seq(2)).unwrap_err().is_full());
+        // High-priority messages are not blocked.
+        address.resume()?;
+        handoff.await??;
+        assert_eq!(address.ask(GetSeen).await?, vec![1]);
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_zero_capacity() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        // The zero capacity is a rendezvous and doesn't panic.
+        let pair = AddressPair::<OrderedActor>::with_capacity(0);
+        let address = pair.address();
+        assert_eq!(address.capacity_available(), Some(1));
+        address.act(Seq(1))?;
+        assert!(address.try_act(Seq(2)).unwrap_err().is_full());
+        Ok(())
+    }
+}
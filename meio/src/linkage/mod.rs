@@ -3,14 +3,40 @@
 // TODO: Improve imports here (use them directly and prelude only)
 
 mod address;
-pub(crate) use address::AddressJoint;
-pub use address::{Address, AddressPair};
+mod mailbox;
+pub use address::{
+    Address, AddressPair, AskError, Cancelled, InteractError, InteractFailure, SendAllError,
+    WeakAddress,
+};
+pub(crate) use address::{AddressJoint, HpPermit};
+pub use mailbox::{MailboxKind, SendError, SendTimeoutError, TrySendError};
+
+mod join;
+pub use join::{join_all, Joinable};
+
+mod link;
+pub use link::Link;
 
 mod recipient;
 pub use recipient::{ActionRecipient, InteractionRecipient};
 
+mod batching;
+pub use batching::BatchingRecipient;
+
+mod broadcast;
+pub use broadcast::Broadcast;
+
 mod distributor;
 pub use distributor::Distributor;
 
+mod sink;
+pub use sink::AddressSink;
+
+mod pool;
+pub use pool::{Dispatch, Pool};
+
+mod throttle;
+pub use throttle::{Overflow, Throttle};
+
 mod task_distributor;
 pub use task_distributor::TaskDistributor;
@@ -0,0 +1,332 @@
+use crate::actor_runtime::{Actor, Context};
+use crate::handlers::{Action, ActionHandler, Eliminated, InterruptedBy, StartedBy};
+use crate::ids::IdOf;
+use crate::linkage::{Address, SendError, TrySendError};
+use std::cmp::Reverse;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The strategy of choosing a worker of a `Pool`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dispatch {
+    /// Workers take actions in turn.
+    #[default]
+    RoundRobin,
+    /// A random worker takes an action.
+    Random,
+    /// The worker with the most free capacity of a bounded mailbox
+    /// takes an action. Works as `RoundRobin` for unbounded mailboxes.
+    LeastLoaded,
+}
+
+/// The set of identical workers supervised by an `Actor`.
+///
+/// The pool is owned by the supervisor: it's not `Clone` and actions are
+/// dispatched by handlers of the supervisor (or sent to `workers` directly).
+/// Finished workers are not replaced automatically: the supervisor spawns
+/// workers with `spawn` and has to call `replace` in its `Eliminated` handler
+/// to remove a finished worker from the rotation and to start a new one instead.
+pub struct Pool<A: Actor> {
+    factory: Box<dyn FnMut() -> A + Send>,
+    dispatch: Dispatch,
+    workers: Vec<Address<A>>,
+    next: AtomicUsize,
+    random: RandomState,
+}
+
+impl<A: Actor> fmt::Debug for Pool<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("dispatch", &self.dispatch)
+            .field("workers", &self.workers)
+            .finish()
+    }
+}
+
+impl<A: Actor> Pool<A> {
+    /// Creates an empty pool that uses the `factory` to create workers.
+    pub fn new<F>(factory: F, dispatch: Dispatch) -> Self
+    where
+        F: FnMut() -> A + Send + 'static,
+    {
+        Self {
+            factory: Box::new(factory),
+            dispatch,
+            workers: Vec::new(),
+            next: AtomicUsize::new(0),
+            random: RandomState::new(),
+        }
+    }
+
+    /// Spawns `size` workers as children of the supervisor.
+    pub fn spawn<S>(&mut self, size: usize, ctx: &mut Context<S>, group: S::GroupBy)
+    where
+        S: Actor + Eliminated<A>,
+        A: StartedBy<S> + InterruptedBy<S>,
+    {
+        for _ in 0..size {
+            self.spawn_worker(ctx, group.clone());
+        }
+    }
+
+    fn spawn_worker<S>(&mut self, ctx: &mut Context<S>, group: S::GroupBy)
+    where
+        S: Actor + Eliminated<A>,
+        A: StartedBy<S> + InterruptedBy<S>,
+    {
+        let worker = (self.factory)();
+        let address = ctx.spawn_actor(worker, group);
+        self.workers.push(address);
+    }
+
+    /// Removes the finished worker and spawns a new one instead
+    /// if the supervisor is not terminating.
+    ///
+    /// Returns `false` if the worker doesn't belong to the pool.
+    pub fn replace<S>(&mut self, id: &IdOf<A>, ctx: &mut Context<S>, group: S::GroupBy) -> bool
+    where
+        S: Actor + Eliminated<A>,
+        A: StartedBy<S> + InterruptedBy<S>,
+    {
+        let before = self.workers.len();
+        self.workers.retain(|address| address.id() != *id);
+        if self.workers.len() == before {
+            return false;
+        }
+        if !ctx.is_terminating() {
+            log::debug!(target: ctx.log_target(), "Replacing the worker {} of the pool", id);
+            self.spawn_worker(ctx, group);
+        }
+        true
+    }
+
+    /// Sends the `Action` to a worker chosen by the `Dispatch` strategy.
+    ///
+    /// If the worker has a full mailbox or doesn't receive messages anymore
+    /// the next one is tried. Fails with `SendError::Full` if all the live
+    /// workers are full.
+    pub fn act<T>(&self, mut action: T) -> Result<(), SendError>
+    where
+        T: Action,
+        A: ActionHandler<T>,
+    {
+        let len = self.workers.len();
+        let start = if len > 0 { self.choose() } else { 0 };
+        let mut reason = SendError::Closed;
+        for offset in 0..len {
+            let address = &self.workers[(start + offset) % len];
+            if address.is_closed() {
+                continue;
+            }
+            match address.try_act(action) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(returned)) => {
+                    reason = SendError::Full;
+                    action = returned;
+                }
+                Err(TrySendError::Closed(returned)) => {
+                    action = returned;
+                }
+            }
+        }
+        Err(reason)
+    }
+
+    fn choose(&self) -> usize {
+        let len = self.workers.len();
+        let turn = self.next.fetch_add(1, Ordering::Relaxed);
+        match self.dispatch {
+            Dispatch::RoundRobin => turn % len,
+            Dispatch::Random => (self.random.hash_one(turn) % len as u64) as usize,
+            Dispatch::LeastLoaded => {
+                // Starts from the next turn to rotate workers with the same load.
+                let start = turn % len;
+                (0..len)
+                    .map(|offset| (start + offset) % len)
+                    .filter_map(|idx| Some((idx, self.workers[idx].capacity_available()?)))
+                    .min_by_key(|(_, capacity)| Reverse(*capacity))
+                    .map(|(idx, _)| idx)
+                    .unwrap_or(start)
+            }
+        }
+    }
+
+    /// Addresses of the workers.
+    pub fn workers(&self) -> &[Address<A>] {
+        &self.workers
+    }
+
+    /// Amount of workers.
+    pub fn len(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Is this pool empty?
+    pub fn is_empty(&self) -> bool {
+        self.workers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::channel::oneshot;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    struct PoolOwner {
+        pool: Pool<PoolWorker>,
+    }
+
+    impl Actor for PoolOwner {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "PoolOwner"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for PoolOwner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.pool.spawn(3, ctx, ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for PoolOwner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Eliminated<PoolWorker> for PoolOwner {
+        async fn handle(
+            &mut self,
+            id: IdOf<PoolWorker>,
+            _reason: TerminationReason,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.pool.replace(&id, ctx, ());
+            Ok(())
+        }
+    }
+
+    #[derive(Clone)]
+    struct Job;
+
+    impl Action for Job {}
+
+    #[async_trait]
+    impl ActionHandler<Job> for PoolOwner {
+        async fn handle(&mut self, msg: Job, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.pool.act(msg)?;
+            Ok(())
+        }
+    }
+
+    struct GetWorkers(oneshot::Sender<Vec<Address<PoolWorker>>>);
+
+    impl Action for GetWorkers {}
+
+    #[async_trait]
+    impl ActionHandler<GetWorkers> for PoolOwner {
+        async fn handle(&mut self, msg: GetWorkers, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            msg.0.send(self.pool.workers().to_vec()).ok();
+            Ok(())
+        }
+    }
+
+    struct PoolWorker {
+        handled: Arc<Mutex<Vec<Id>>>,
+    }
+
+    impl Actor for PoolWorker {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "PoolWorker"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<PoolOwner> for PoolWorker {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<PoolOwner> for PoolWorker {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Job> for PoolWorker {
+        async fn handle(&mut self, _: Job, ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.handled.lock().unwrap().push(ctx.id().clone());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<StopLater> for PoolWorker {
+        async fn handle(&mut self, _: StopLater, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.stop();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pool() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let factory_handled = handled.clone();
+        let factory = move || PoolWorker {
+            handled: factory_handled.clone(),
+        };
+        let address = System::spawn(PoolOwner {
+            pool: Pool::new(factory, Dispatch::RoundRobin),
+        });
+        for _ in 0..6 {
+            address.act(Job)?;
+        }
+        address.flush().await?;
+        let workers = address.ask(GetWorkers).await?;
+        assert_eq!(workers.len(), 3);
+        for worker in &workers {
+            worker.flush().await?;
+            let id: Id = worker.id().into();
+            let count = handled.lock().unwrap().iter().filter(|h| **h == id).count();
+            assert_eq!(count, 2);
+        }
+        // The dead worker is replaced with a new one.
+        let dead = workers[0].id();
+        workers[0].act(StopLater)?;
+        let mut replaced = Vec::new();
+        for _ in 0..100 {
+            replaced = address.ask(GetWorkers).await?;
+            if replaced.iter().all(|worker| worker.id() != dead) {
+                break;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(replaced.len(), 3);
+        assert!(replaced.iter().all(|worker| worker.id() != dead));
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+}
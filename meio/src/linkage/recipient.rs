@@ -8,8 +8,10 @@ use crate::actor_runtime::Actor;
 use crate::handlers::{Action, ActionHandler, Interact, Interaction, InteractionTask};
 use crate::ids::Id;
 use anyhow::Error;
-use std::fmt::Debug;
+use futures::future::{self, BoxFuture, FutureExt};
+use std::fmt::{self, Debug};
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 /// Abstract `Address` to the `Actor` that can handle a specific message type.
 pub trait ActionRecipient<T: Action>: Debug + Send + 'static {
@@ -25,6 +27,95 @@ pub trait ActionRecipient<T: Action>: Debug + Send + 'static {
 
     #[doc(hidden)]
     fn dyn_hash(&self, state: &mut dyn Hasher);
+
+    /// Returns `true` if the recipient doesn't accept actions anymore.
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    /// Sends an `Action` waiting for the free capacity of a bounded mailbox.
+    fn act_wait(&mut self, msg: T) -> BoxFuture<'static, Result<(), Error>> {
+        future::ready(self.act(msg)).boxed()
+    }
+
+    /// Converts the recipient to a recipient of other messages
+    /// that are mapped by the `f` function before sending.
+    fn contramap<N, F>(self, f: F) -> Box<dyn ActionRecipient<N>>
+    where
+        Self: Sized,
+        N: Action,
+        F: Fn(N) -> T + Send + Sync + 'static,
+    {
+        Box::new(MappedRecipient {
+            recipient: Box::new(self),
+            mapper: Arc::new(f),
+        })
+    }
+}
+
+impl<T: Action> dyn ActionRecipient<T> {
+    /// Converts the recipient to a recipient of other messages
+    /// that are mapped by the `f` function before sending.
+    pub fn contramap<N, F>(self: Box<Self>, f: F) -> Box<dyn ActionRecipient<N>>
+    where
+        N: Action,
+        F: Fn(N) -> T + Send + Sync + 'static,
+    {
+        Box::new(MappedRecipient {
+            recipient: self,
+            mapper: Arc::new(f),
+        })
+    }
+}
+
+/// The recipient that maps messages before sending them to another recipient.
+struct MappedRecipient<T: Action, F> {
+    recipient: Box<dyn ActionRecipient<T>>,
+    mapper: Arc<F>,
+}
+
+impl<T: Action, F> Debug for MappedRecipient<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MappedRecipient")
+            .field(&self.recipient)
+            .finish()
+    }
+}
+
+impl<T, N, F> ActionRecipient<N> for MappedRecipient<T, F>
+where
+    T: Action,
+    N: Action,
+    F: Fn(N) -> T + Send + Sync + 'static,
+{
+    fn act(&mut self, msg: N) -> Result<(), Error> {
+        let msg = (self.mapper)(msg);
+        self.recipient.act(msg)
+    }
+
+    fn id_ref(&self) -> &Id {
+        self.recipient.id_ref()
+    }
+
+    fn dyn_clone(&self) -> Box<dyn ActionRecipient<N>> {
+        Box::new(Self {
+            recipient: self.recipient.clone(),
+            mapper: self.mapper.clone(),
+        })
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        self.recipient.dyn_hash(state);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.recipient.is_closed()
+    }
+
+    fn act_wait(&mut self, msg: N) -> BoxFuture<'static, Result<(), Error>> {
+        let msg = (self.mapper)(msg);
+        self.recipient.act_wait(msg)
+    }
 }
 
 impl<T: Action> Clone for Box<dyn ActionRecipient<T>> {
@@ -53,7 +144,7 @@ where
     A: Actor + ActionHandler<T>,
 {
     fn act(&mut self, msg: T) -> Result<(), Error> {
-        Address::act(self, msg)
+        Address::act(self, msg).map_err(Error::from)
     }
 
     fn id_ref(&self) -> &Id {
@@ -67,6 +158,15 @@ where
     fn dyn_hash(&self, state: &mut dyn Hasher) {
         ActionRecipient::id_ref(self).hash(&mut Box::new(state));
     }
+
+    fn is_closed(&self) -> bool {
+        Address::is_closed(self)
+    }
+
+    fn act_wait(&mut self, msg: T) -> BoxFuture<'static, Result<(), Error>> {
+        let address = self.clone();
+        async move { address.act_wait(msg).await.map_err(Error::from) }.boxed()
+    }
 }
 
 /// Abstract `Address` to the `Actor` that can handle an interaction.
@@ -126,3 +226,38 @@ where
         InteractionRecipient::id_ref(self).hash(&mut Box::new(state));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+
+    struct Raw(u8);
+
+    impl Action for Raw {}
+
+    struct Doubled(u8);
+
+    impl Action for Doubled {}
+
+    #[tokio::test]
+    async fn test_contramap() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let recipient = address.action_recipient::<Seq>();
+        let mut doubled = recipient.clone().contramap(|msg: Doubled| Seq(msg.0 * 2));
+        let mut raw = doubled.clone().contramap(|msg: Raw| Doubled(msg.0 + 1));
+        doubled.act(Doubled(1))?;
+        raw.act(Raw(2))?;
+        raw.act_wait(Raw(3)).await?;
+        assert_eq!(address.ask(GetSeen).await?, vec![2, 6, 8]);
+        // Converted recipients point to the same `Actor`.
+        assert_eq!(raw.id_ref(), recipient.id_ref());
+        System::interrupt(&address)?;
+        address.join().await;
+        assert!(raw.is_closed());
+        assert!(raw.act(Raw(4)).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+//! Contains a `Sink` adapter for an `Address`.
+
+use super::mailbox::MailboxPermit;
+use super::Address;
+use crate::actor_runtime::Actor;
+use crate::handlers::{Action, ActionHandler, Envelope};
+use anyhow::Error;
+use futures::future::BoxFuture;
+use futures::task::{Context, Poll};
+use futures::{FutureExt, Sink};
+use std::fmt;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+/// The `Sink` that sends messages of the `M` type to an `Actor`.
+///
+/// Created by `Address::into_sink`.
+pub struct AddressSink<A: Actor, M> {
+    address: Address<A>,
+    reserving: Option<BoxFuture<'static, Result<MailboxPermit<A>, Error>>>,
+    permit: Option<MailboxPermit<A>>,
+    closed: bool,
+    _message: PhantomData<fn(M)>,
+}
+
+impl<A: Actor, M> fmt::Debug for AddressSink<A, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AddressSink").field(&self.address).finish()
+    }
+}
+
+impl<A: Actor, M> AddressSink<A, M> {
+    pub(crate) fn new(address: Address<A>) -> Self {
+        Self {
+            address,
+            reserving: None,
+            permit: None,
+            closed: false,
+            _message: PhantomData,
+        }
+    }
+
+    /// Returns the `Address` of the `Actor`.
+    pub fn address(&self) -> &Address<A> {
+        &self.address
+    }
+}
+
+impl<A, M> Sink<M> for AddressSink<A, M>
+where
+    A: Actor + ActionHandler<M>,
+    M: Action,
+{
+    type Error = Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        if self.closed {
+            return Poll::Ready(Err(Error::msg("the sink is closed")));
+        }
+        if self.permit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        let mut reserving = match self.reserving.take() {
+            Some(reserving) => reserving,
+            None => self.address.reserve(),
+        };
+        match reserving.poll_unpin(cx) {
+            Poll::Ready(Ok(permit)) => {
+                self.permit = Some(permit);
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Pending => {
+                self.reserving = Some(reserving);
+                Poll::Pending
+            }
+        }
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: M) -> Result<(), Error> {
+        let permit = self
+            .permit
+            .take()
+            .ok_or_else(|| Error::msg("poll_ready has to be called before start_send"))?;
+        self.address.send_reserved(permit, Envelope::new(item));
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        // Messages are in the mailbox already.
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.closed = true;
+        self.reserving = None;
+        self.permit = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use futures::{stream, StreamExt};
+
+    #[tokio::test]
+    async fn test_address_sink() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let items = stream::iter(vec![Seq(1), Seq(2), Seq(3)]).map(Ok);
+        items.forward(address.clone().into_sink()).await?;
+        let seen = address.ask(GetSeen).await?;
+        assert_eq!(seen, vec![1, 2, 3]);
+        // Closing of the sink doesn't stop the actor.
+        assert!(!address.status().is_done());
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
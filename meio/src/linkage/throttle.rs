@@ -0,0 +1,247 @@
+//! Contains a recipient that limits the rate of actions.
+
+use super::ActionRecipient;
+use crate::handlers::Action;
+use crate::ids::Id;
+use anyhow::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Instant};
+
+/// What to do with actions that exceed the rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Drops excess actions and counts them.
+    Drop,
+    /// Delays excess actions until the rate allows them.
+    Delay,
+}
+
+/// The token bucket shared by clones of a `Throttle`.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32, burst: u32) -> Self {
+        let burst = burst.max(1) as f64;
+        Self {
+            rate: rate.max(1) as f64,
+            burst,
+            tokens: burst,
+            refilled: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.refilled = now;
+    }
+
+    /// Takes a token if it's available.
+    fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Takes a token in advance and returns the time when it's available.
+    fn reserve(&mut self, now: Instant) -> Instant {
+        self.refill(now);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            now
+        } else {
+            now + Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+enum Output<T: Action> {
+    Direct(Box<dyn ActionRecipient<T>>),
+    Delayed(mpsc::UnboundedSender<(Instant, T)>),
+}
+
+impl<T: Action> Clone for Output<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Direct(recipient) => Self::Direct(recipient.clone()),
+            Self::Delayed(tx) => Self::Delayed(tx.clone()),
+        }
+    }
+}
+
+/// The recipient that passes at most `rate` actions per second
+/// to the inner recipient with bursts up to `burst` actions.
+///
+/// Clones of the throttle share the limit.
+pub struct Throttle<T: Action> {
+    id: Id,
+    bucket: Arc<Mutex<TokenBucket>>,
+    dropped: Arc<AtomicU64>,
+    output: Output<T>,
+}
+
+impl<T: Action> Throttle<T> {
+    /// Creates a new throttle.
+    ///
+    /// With the `Overflow::Delay` policy it spawns a routine
+    /// that delivers delayed actions in order.
+    pub fn new(
+        recipient: impl ActionRecipient<T>,
+        rate: u32,
+        burst: u32,
+        overflow: Overflow,
+    ) -> Self {
+        let id = recipient.id_ref().clone();
+        let output = match overflow {
+            Overflow::Drop => Output::Direct(Box::new(recipient)),
+            Overflow::Delay => {
+                let (tx, rx) = mpsc::unbounded_channel();
+                crate::compat::spawn_async(delay_routine(rx, Box::new(recipient)));
+                Output::Delayed(tx)
+            }
+        };
+        Self {
+            id,
+            bucket: Arc::new(Mutex::new(TokenBucket::new(rate, burst))),
+            dropped: Arc::new(AtomicU64::new(0)),
+            output,
+        }
+    }
+
+    /// The number of dropped actions.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+async fn delay_routine<T: Action>(
+    mut rx: mpsc::UnboundedReceiver<(Instant, T)>,
+    mut recipient: Box<dyn ActionRecipient<T>>,
+) {
+    while let Some((deadline, msg)) = rx.recv().await {
+        sleep_until(deadline).await;
+        if let Err(err) = recipient.act_wait(msg).await {
+            log::warn!(target: "Throttle", "Can't deliver a delayed action to {:?}: {}", recipient, err);
+            break;
+        }
+    }
+}
+
+impl<T: Action> Clone for Throttle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            bucket: self.bucket.clone(),
+            dropped: self.dropped.clone(),
+            output: self.output.clone(),
+        }
+    }
+}
+
+impl<T: Action> fmt::Debug for Throttle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Throttle").field(&self.id).finish()
+    }
+}
+
+impl<T: Action> ActionRecipient<T> for Throttle<T> {
+    fn act(&mut self, msg: T) -> Result<(), Error> {
+        let now = Instant::now();
+        let mut bucket = self.bucket.lock().unwrap_or_else(|err| err.into_inner());
+        match &mut self.output {
+            Output::Direct(recipient) => {
+                if bucket.try_take(now) {
+                    drop(bucket);
+                    recipient.act(msg)
+                } else {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    Ok(())
+                }
+            }
+            Output::Delayed(tx) => {
+                // The lock is held while sending to keep the order of deadlines.
+                let deadline = bucket.reserve(now);
+                tx.send((deadline, msg))
+                    .map_err(|_| Error::msg("the throttling routine is finished"))
+            }
+        }
+    }
+
+    fn id_ref(&self) -> &Id {
+        &self.id
+    }
+
+    fn dyn_clone(&self) -> Box<dyn ActionRecipient<T>> {
+        Box::new(self.clone())
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        self.id.hash(&mut Box::new(state));
+    }
+
+    fn is_closed(&self) -> bool {
+        match &self.output {
+            Output::Direct(recipient) => recipient.is_closed(),
+            Output::Delayed(tx) => tx.is_closed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::linkage::Overflow;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use std::time::Duration;
+    use tokio::time::{advance, sleep};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let mut dropping = Throttle::new(address.clone(), 10, 2, Overflow::Drop);
+        for n in 0..5 {
+            dropping.act(Seq(n))?;
+        }
+        assert_eq!(dropping.dropped(), 3);
+        assert_eq!(address.ask(GetSeen).await?, vec![0, 1]);
+        // A token is refilled every 100ms.
+        advance(Duration::from_millis(100)).await;
+        dropping.act(Seq(2))?;
+        dropping.act(Seq(3))?;
+        assert_eq!(dropping.dropped(), 4);
+        assert_eq!(address.ask(GetSeen).await?, vec![0, 1, 2]);
+        let mut delaying = Throttle::new(address.clone(), 20, 1, Overflow::Delay);
+        for n in 4..7 {
+            delaying.act(Seq(n))?;
+        }
+        // Only the burst passes at once, the rest arrives every 50ms.
+        // The paused clock is advanced by `sleep` only when all tasks are idle.
+        sleep(Duration::from_millis(20)).await;
+        assert_eq!(address.ask(GetSeen).await?, vec![0, 1, 2, 4]);
+        sleep(Duration::from_millis(50)).await;
+        assert_eq!(address.ask(GetSeen).await?, vec![0, 1, 2, 4, 5]);
+        sleep(Duration::from_millis(50)).await;
+        assert_eq!(delaying.dropped(), 0);
+        assert_eq!(address.ask(GetSeen).await?, vec![0, 1, 2, 4, 5, 6]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
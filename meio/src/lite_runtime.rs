@@ -12,7 +12,8 @@ use futures::{
 };
 use std::hash::{Hash, Hasher};
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 use thiserror::Error;
 
@@ -33,6 +34,11 @@ pub trait LiteTask: Sized + Send + 'static {
     /// The log target for the `LiteTask`.
     fn log_target(&self) -> &str;
 
+    /// Called before the start with the `Progress` that is shared with
+    /// the `TaskAddress`. Keep it and call `Progress::report` to let
+    /// the supervisor detect that the task is stuck.
+    fn bind_progress(&mut self, _progress: Progress) {}
+
     /// Routine of the task that can contain loops.
     /// It can taks into accout provided receiver to implement graceful interruption.
     ///
@@ -88,7 +94,7 @@ pub trait LiteTask: Sized + Send + 'static {
     }
 }
 
-pub(crate) fn spawn<T, S, M>(task: T, tag: M, supervisor: Option<Address<S>>) -> TaskAddress<T>
+pub(crate) fn spawn<T, S, M>(mut task: T, tag: M, supervisor: Option<Address<S>>) -> TaskAddress<T>
 where
     T: LiteTask,
     S: Actor + TaskEliminated<T, M>,
@@ -96,6 +102,7 @@ where
 {
     let id = Id::unique();
     let (stop_sender, stop_receiver) = make_stop_channel(id.clone());
+    task.bind_progress(stop_sender.progress.clone());
     let id_of = IdOf::<T>::new(id.clone());
     let done_notifier = {
         match supervisor {
@@ -133,6 +140,7 @@ fn make_stop_channel<T>(id: Id) -> (TaskAddress<T>, StopReceiver) {
     let address = TaskAddress {
         id: IdOf::new(id),
         stop_sender,
+        progress: Progress::new(),
     };
     let receiver = StopReceiver { status: rx };
     (address, receiver)
@@ -157,6 +165,42 @@ impl<T> From<TaskAddress<T>> for StopSender {
     }
 }
 
+/// The time of the last progress of a task.
+///
+/// It's shared by the task and its `TaskAddress`.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    /// Milliseconds since the `base` instant.
+    last: Arc<AtomicU64>,
+}
+
+impl Progress {
+    fn new() -> Self {
+        let progress = Self {
+            last: Arc::new(AtomicU64::new(0)),
+        };
+        progress.report();
+        progress
+    }
+
+    fn base() -> Instant {
+        static BASE: OnceLock<Instant> = OnceLock::new();
+        *BASE.get_or_init(Instant::now)
+    }
+
+    /// Marks that the task is making progress now.
+    pub fn report(&self) {
+        let millis = Self::base().elapsed().as_millis() as u64;
+        self.last.store(millis, Ordering::Relaxed);
+    }
+
+    /// The time passed since the last reported progress (or the spawning).
+    pub fn elapsed(&self) -> Duration {
+        let last = Duration::from_millis(self.last.load(Ordering::Relaxed));
+        Self::base().elapsed().saturating_sub(last)
+    }
+}
+
 /// Address of a spawned task.
 ///
 /// It can be used to interrupt the task.
@@ -164,6 +208,7 @@ impl<T> From<TaskAddress<T>> for StopSender {
 pub struct TaskAddress<T> {
     id: IdOf<T>,
     stop_sender: StopSender,
+    progress: Progress,
 }
 
 impl<T> Clone for TaskAddress<T> {
@@ -171,6 +216,7 @@ impl<T> Clone for TaskAddress<T> {
         Self {
             id: self.id(),
             stop_sender: self.stop_sender.clone(),
+            progress: self.progress.clone(),
         }
     }
 }
@@ -182,9 +228,67 @@ impl<T> TaskAddress<T> {
     }
 
     /// Send a stop signal to the task.
+    ///
+    /// The `StopReceiver` of the task will be resolved and the `routine` interrupted.
     pub fn stop(&self) -> Result<(), Error> {
         self.stop_sender.stop()
     }
+
+    /// The progress of the task.
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    /// Returns `true` if the task hasn't reported progress during the `threshold`.
+    ///
+    /// Only tasks that report progress with the `Progress` they got in
+    /// `LiteTask::bind_progress` can be checked this way.
+    pub fn is_stale(&self, threshold: Duration) -> bool {
+        self.progress.elapsed() > threshold
+    }
+
+    /// Wraps the address to a guard that stops the task when dropped.
+    pub fn stop_on_drop(self) -> TaskGuard<T> {
+        TaskGuard {
+            address: self,
+            armed: true,
+        }
+    }
+}
+
+/// The guard that stops a task when dropped.
+#[derive(Debug)]
+pub struct TaskGuard<T> {
+    address: TaskAddress<T>,
+    armed: bool,
+}
+
+impl<T> TaskGuard<T> {
+    /// Id of the task.
+    pub fn id(&self) -> IdOf<T> {
+        self.address.id()
+    }
+
+    /// Send a stop signal to the task.
+    pub fn stop(&self) -> Result<(), Error> {
+        self.address.stop()
+    }
+
+    /// Releases the task and returns its address.
+    /// The task won't be stopped when the guard dropped.
+    pub fn release(mut self) -> TaskAddress<T> {
+        self.armed = false;
+        self.address.clone()
+    }
+}
+
+impl<T> Drop for TaskGuard<T> {
+    fn drop(&mut self) {
+        if self.armed {
+            // The task could be already finished.
+            self.address.stop().ok();
+        }
+    }
 }
 
 impl<T: LiteTask> PartialEq for TaskAddress<T> {
@@ -324,3 +428,68 @@ impl<T: LiteTask, M: Tag> LiteRuntime<T, M> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    struct Stuck {
+        progress: Option<Progress>,
+    }
+
+    #[async_trait]
+    impl LiteTask for Stuck {
+        type Output = ();
+
+        fn log_target(&self) -> &str {
+            "Stuck"
+        }
+
+        fn bind_progress(&mut self, progress: Progress) {
+            self.progress = Some(progress);
+        }
+
+        async fn interruptable_routine(self) -> Result<Self::Output, Error> {
+            for _ in 0..5 {
+                sleep(Duration::from_millis(10)).await;
+                if let Some(progress) = self.progress.as_ref() {
+                    progress.report();
+                }
+            }
+            futures::future::pending().await
+        }
+    }
+
+    #[async_trait]
+    impl TaskEliminated<Stuck, ()> for OrderedActor {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Stuck>,
+            _tag: (),
+            _result: Result<(), TaskError>,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_progress() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let task = Stuck { progress: None };
+        let address = crate::lite_runtime::spawn(task, (), None::<Address<OrderedActor>>);
+        let threshold = Duration::from_millis(30);
+        sleep(Duration::from_millis(35)).await;
+        assert!(!address.is_stale(threshold));
+        // The task stops reporting and the watchdog detects it.
+        sleep(Duration::from_millis(100)).await;
+        assert!(address.is_stale(threshold));
+        address.stop()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+//! Contains counters of messages processed by an `Actor`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The snapshot of the counters of an `Actor`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// How many messages were handled (including failed ones).
+    pub handled: u64,
+    /// How many handlers returned an error.
+    pub errored: u64,
+    /// How many ordinary priority messages wait in the mailbox.
+    pub mailbox_depth: u64,
+}
+
+/// Counters shared between addresses and the runtime of an `Actor`.
+#[derive(Debug, Default)]
+pub(crate) struct MetricsCollector {
+    sent: AtomicU64,
+    received: AtomicU64,
+    handled: AtomicU64,
+    errored: AtomicU64,
+}
+
+impl MetricsCollector {
+    pub fn sent(&self) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn received(&self) {
+        self.received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn handled(&self, succeed: bool) {
+        self.handled.fetch_add(1, Ordering::Relaxed);
+        if !succeed {
+            self.errored.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn snapshot(&self) -> Metrics {
+        let sent = self.sent.load(Ordering::Relaxed);
+        let received = self.received.load(Ordering::Relaxed);
+        Metrics {
+            handled: self.handled.load(Ordering::Relaxed),
+            errored: self.errored.load(Ordering::Relaxed),
+            mailbox_depth: sent.saturating_sub(received),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_metrics() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        address.act(Seq(1))?;
+        address.ask(GetSeen).await?;
+        address.ask(GetSeen).await?;
+        let metrics = address.metrics();
+        // The last handler could be not counted yet when the reply received.
+        assert!(metrics.handled >= 2);
+        assert_eq!(metrics.errored, 0);
+        assert_eq!(metrics.mailbox_depth, 0);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
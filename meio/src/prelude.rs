@@ -1,22 +1,54 @@
 //! Meio prelude module.
 
-pub use crate::actor_runtime::{Actor, Context, Status, TerminationSequence};
+pub use crate::actor_runtime::{
+    Actor, ActorStatus, Context, RestartPolicy, Status, TerminationSequence, DEFAULT_YIELD_INTERVAL,
+};
+pub use crate::dead_letters::DeadLetter;
+pub use crate::dedupe::DedupeWindow;
+#[cfg(feature = "durable")]
+pub use crate::durable::{DurableAction, DurableMailbox};
 pub use crate::handlers::{
     Action, ActionHandler, Consumer, Eliminated, InstantAction, InstantActionHandler, Interact,
-    Interaction, InteractionDone, InteractionHandler, InteractionResponder, InteractionTask,
-    InterruptedBy, Parcel, Scheduled, StartedBy, StreamAcceptor, TaskEliminated, TerminatedBy,
+    InteractStream, Interaction, InteractionDone, InteractionHandler, InteractionResponder,
+    InteractionTask, InterruptedBy, Parcel, ResponseStream, ScheduleHandle, Scheduled, StartedBy,
+    StreamAcceptor, StreamEnded, StreamInteraction, StreamInteractionHandler, StreamResponder,
+    TaskEliminated, TerminatedBy,
 };
 pub use crate::ids::{Id, IdOf};
+pub use crate::lifecycle::{
+    ChildInfo, ChildKind, InterruptReason, LifecycleEvent, LifecycleStage, Supervisor,
+    TerminationReason, LIFECYCLE_EVENTS_CAPACITY,
+};
 pub use crate::linkage::{
-    ActionRecipient, Address, AddressPair, Distributor, InteractionRecipient, TaskDistributor,
+    ActionRecipient, Address, AddressPair, AddressSink, AskError, BatchingRecipient, Broadcast,
+    Cancelled, Dispatch, Distributor, InteractError, InteractFailure, InteractionRecipient,
+    Joinable, Link, MailboxKind, Pool, SendAllError, SendError, TaskDistributor, Throttle,
+    WeakAddress,
 };
 pub use crate::lite_runtime::{
-    LiteTask, StopReceiver, StopSender, StopSignal, Tag, TaskAddress, TaskError,
+    LiteTask, Progress, StopReceiver, StopSender, StopSignal, Tag, TaskAddress, TaskError,
+    TaskGuard,
 };
+#[cfg(feature = "metrics")]
+pub use crate::metrics::Metrics;
+pub use crate::pubsub::Topic;
+pub use crate::registry::Registry;
+#[cfg(feature = "remote")]
+pub use crate::remote::{ActorRef, RemoteAddress, RemoteMessage, Resolved, Resolver, Transport};
 #[cfg(not(feature = "wasm"))]
 pub use crate::signal;
-pub use crate::system::System;
+pub use crate::state_machine::{Event, Machine, OnEvent, StateMachine, Transition};
+pub use crate::system::{System, WaitStatus};
 pub use crate::tasks::{
+    bridge::ChannelBridge,
+    cron::{CronTask, CronTick, Periodic, Schedule},
+    detached::Detached,
     fn_task::{FnTask, FnTaskEliminated},
     heartbeat,
+    interval::IntervalHandle,
+    one_shot::OneShotTask,
+    race::{Racer, TaskCompleted},
+    retry::{Backoff, Retry, RetryEliminated},
+    supervised::Supervised,
+    timeout::{TimedOut, Timeout},
 };
@@ -0,0 +1,132 @@
+//! Typed topics to publish events to subscribed actors.
+
+use crate::handlers::Action;
+use crate::ids::Id;
+use crate::linkage::ActionRecipient;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The topic that delivers a clone of every published event to all subscribers.
+///
+/// Clones of the topic share subscribers. Subscribers of terminated
+/// actors are removed automatically.
+pub struct Topic<E: Action> {
+    subscribers: Arc<Mutex<Vec<Box<dyn ActionRecipient<E>>>>>,
+}
+
+impl<E: Action> Clone for Topic<E> {
+    fn clone(&self) -> Self {
+        Self {
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
+
+impl<E: Action> Default for Topic<E> {
+    fn default() -> Self {
+        Self {
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl<E: Action> fmt::Debug for Topic<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.lock().iter()).finish()
+    }
+}
+
+impl<E: Action> Topic<E> {
+    /// Creates a new topic without subscribers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Box<dyn ActionRecipient<E>>>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Adds a subscriber to the topic.
+    pub fn subscribe(&self, recipient: impl ActionRecipient<E>) {
+        self.lock().push(Box::new(recipient));
+    }
+
+    /// Removes the subscriber by `Id` of its `Actor`.
+    ///
+    /// Returns `true` if the subscriber was found.
+    pub fn unsubscribe(&self, id: &Id) -> bool {
+        let mut subscribers = self.lock();
+        let before = subscribers.len();
+        subscribers.retain(|recipient| recipient.id_ref() != id);
+        subscribers.len() != before
+    }
+
+    /// The number of active subscribers.
+    pub fn len(&self) -> usize {
+        let mut subscribers = self.lock();
+        subscribers.retain(|recipient| !recipient.is_closed());
+        subscribers.len()
+    }
+
+    /// Are there no active subscribers?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<E: Action + Clone> Topic<E> {
+    /// Sends a clone of the `event` to every subscriber.
+    ///
+    /// Returns the number of subscribers that received the event.
+    pub fn publish(&self, event: E) -> usize {
+        let mut subscribers = self.lock();
+        subscribers.retain(|recipient| !recipient.is_closed());
+        let mut delivered = 0;
+        for recipient in subscribers.iter_mut() {
+            match recipient.act(event.clone()) {
+                Ok(()) => {
+                    delivered += 1;
+                }
+                Err(err) => {
+                    log::warn!(
+                        "Can't publish an event to {:?}: {}",
+                        recipient.id_ref(),
+                        err
+                    );
+                }
+            }
+        }
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+
+    #[tokio::test]
+    async fn test_topic() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let topic = Topic::new();
+        let first = System::spawn(OrderedActor::default());
+        let second = System::spawn(OrderedActor::default());
+        topic.subscribe(first.clone());
+        topic.subscribe(second.clone());
+        assert_eq!(topic.publish(Published(1)), 2);
+        assert_eq!(first.ask(GetSeen).await?, vec![1]);
+        assert_eq!(second.ask(GetSeen).await?, vec![1]);
+        System::interrupt(&first)?;
+        first.join().await;
+        assert_eq!(topic.publish(Published(2)), 1);
+        assert_eq!(topic.len(), 1);
+        assert_eq!(second.ask(GetSeen).await?, vec![1, 2]);
+        System::interrupt(&second)?;
+        second.join().await;
+        assert!(topic.is_empty());
+        Ok(())
+    }
+}
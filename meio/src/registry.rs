@@ -0,0 +1,190 @@
+//! Registry of actors with human-readable names.
+
+use crate::actor_runtime::Actor;
+use crate::handlers::StartedBy;
+use crate::ids::Id;
+use crate::linkage::{Address, Joinable};
+use crate::system::System;
+use anyhow::Error;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use thiserror::Error;
+
+/// The error of registering an `Actor` under a name that is taken.
+#[derive(Debug, Error)]
+#[error("the name {name} is already registered")]
+pub struct NameTaken {
+    /// The name that is taken.
+    pub name: String,
+}
+
+struct Entry {
+    joinable: Joinable,
+    address: Box<dyn Any + Send>,
+}
+
+type Entries = HashMap<String, Entry>;
+
+/// Keeps addresses of actors by names.
+///
+/// Clones of the registry share entries. An entry is removed
+/// when its `Actor` is terminated.
+#[derive(Clone, Default)]
+pub struct Registry {
+    entries: Arc<Mutex<Entries>>,
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.lock().keys()).finish()
+    }
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Registers the `address` under the `name`.
+    ///
+    /// Fails with `NameTaken` if an alive `Actor` has the same name.
+    pub fn register<A: Actor>(&self, name: &str, address: Address<A>) -> Result<(), Error> {
+        let mut entries = self.lock();
+        self.insert(&mut entries, name, address)
+    }
+
+    /// Spawns an `Actor` by the `System` and registers it under the `name`.
+    ///
+    /// The `Actor` is not spawned if the `name` is taken.
+    pub fn spawn<A>(&self, name: &str, actor: A) -> Result<Address<A>, Error>
+    where
+        A: Actor + StartedBy<System>,
+    {
+        let mut entries = self.lock();
+        Self::prune(&mut entries, name);
+        if entries.contains_key(name) {
+            return Err(NameTaken { name: name.into() }.into());
+        }
+        let address = System::spawn(actor);
+        self.insert(&mut entries, name, address.clone())?;
+        Ok(address)
+    }
+
+    /// Returns the `Address` registered under the `name`.
+    ///
+    /// Returns `None` if the name is not registered, the `Actor`
+    /// is terminated or has another type.
+    pub fn lookup<A: Actor>(&self, name: &str) -> Option<Address<A>> {
+        let mut entries = self.lock();
+        Self::prune(&mut entries, name);
+        entries
+            .get(name)?
+            .address
+            .downcast_ref::<Address<A>>()
+            .cloned()
+    }
+
+    /// Removes the `name` from the registry.
+    pub fn unregister(&self, name: &str) -> bool {
+        self.lock().remove(name).is_some()
+    }
+
+    /// The number of registered actors.
+    pub fn len(&self) -> usize {
+        let mut entries = self.lock();
+        entries.retain(|_, entry| !entry.joinable.is_done());
+        entries.len()
+    }
+
+    /// Returns `true` if no actors registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn insert<A: Actor>(
+        &self,
+        entries: &mut Entries,
+        name: &str,
+        address: Address<A>,
+    ) -> Result<(), Error> {
+        Self::prune(entries, name);
+        if entries.contains_key(name) {
+            return Err(NameTaken { name: name.into() }.into());
+        }
+        let joinable = Joinable::from(address.clone());
+        let entry = Entry {
+            joinable: joinable.clone(),
+            address: Box::new(address),
+        };
+        entries.insert(name.into(), entry);
+        let id = joinable.id().clone();
+        let name = name.to_string();
+        let registry = Arc::downgrade(&self.entries);
+        crate::compat::spawn_async(async move {
+            joinable.join().await;
+            if let Some(registry) = registry.upgrade() {
+                let mut entries = registry.lock().unwrap_or_else(|err| err.into_inner());
+                Self::remove_exact(&mut entries, &name, &id);
+            }
+        });
+        Ok(())
+    }
+
+    /// Removes the entry if its `Actor` is terminated.
+    fn prune(entries: &mut Entries, name: &str) {
+        let done = entries
+            .get(name)
+            .is_some_and(|entry| entry.joinable.is_done());
+        if done {
+            entries.remove(name);
+        }
+    }
+
+    /// Removes the entry only if it wasn't replaced by another `Actor`.
+    fn remove_exact(entries: &mut Entries, name: &str, id: &Id) {
+        if entries
+            .get(name)
+            .is_some_and(|entry| entry.joinable.id() == id)
+        {
+            entries.remove(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use crate::registry;
+    use anyhow::Error;
+
+    #[tokio::test]
+    async fn test_registry() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let registry = Registry::new();
+        let address = registry.spawn("ordered", OrderedActor::default())?;
+        assert_eq!(
+            registry.lookup::<OrderedActor>("ordered"),
+            Some(address.clone())
+        );
+        assert!(registry.lookup::<Stopper>("ordered").is_none());
+        let err = registry
+            .spawn("ordered", OrderedActor::default())
+            .unwrap_err();
+        assert!(err.downcast_ref::<registry::NameTaken>().is_some());
+        System::interrupt(&address)?;
+        address.join().await;
+        assert!(registry.lookup::<OrderedActor>("ordered").is_none());
+        let other = registry.spawn("ordered", OrderedActor::default())?;
+        System::interrupt(&other)?;
+        other.join().await;
+        Ok(())
+    }
+}
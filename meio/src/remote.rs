@@ -0,0 +1,492 @@
+//! Serializable references to actors that can be resolved on other nodes.
+//!
+//! An `ActorRef` is created by `Resolver::export` and can be sent over the
+//! wire. The `Resolver` of another node turns it back into `Resolved`:
+//! the ordinary `Address` if the `Actor` lives on the same node or
+//! `RemoteAddress` that sends actions through the `Transport`.
+//!
+//! Actions are delivered between nodes as `RemoteMessage` with the JSON
+//! payload. The receiving node passes them to `Resolver::deliver` and only
+//! accepts types registered with `Resolver::accept` for the type of the
+//! receiving `Actor`. Types of actions are matched by
+//! `std::any::type_name`, that's why nodes have to be built from the same
+//! sources.
+
+use crate::actor_runtime::Actor;
+use crate::handlers::{Action, ActionHandler};
+use crate::ids::Id;
+use crate::linkage::{Address, Joinable};
+use anyhow::Error;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// The serializable reference to an `Actor` on a node.
+///
+/// References are equal if they point to the same `Actor`, even
+/// if they were deserialized from different messages.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ActorRef<A> {
+    id: Id,
+    node: String,
+    #[serde(skip)]
+    _actor: PhantomData<fn() -> A>,
+}
+
+impl<A> Clone for ActorRef<A> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            node: self.node.clone(),
+            _actor: PhantomData,
+        }
+    }
+}
+
+impl<A> fmt::Debug for ActorRef<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActorRef")
+            .field("id", &self.id)
+            .field("node", &self.node)
+            .finish()
+    }
+}
+
+impl<A> PartialEq for ActorRef<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.uuid() == other.id.uuid() && self.node == other.node
+    }
+}
+
+impl<A> Eq for ActorRef<A> {}
+
+impl<A> Hash for ActorRef<A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.node.hash(state);
+    }
+}
+
+impl<A> ActorRef<A> {
+    /// The `Id` of the `Actor`.
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    /// The node where the `Actor` lives.
+    pub fn node(&self) -> &str {
+        &self.node
+    }
+}
+
+/// The action that is sent between nodes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteMessage {
+    /// The type of the action.
+    pub kind: String,
+    /// The serialized action.
+    pub payload: Value,
+}
+
+/// Delivers messages to actors of other nodes.
+///
+/// The receiving side passes messages to `Resolver::deliver`.
+pub trait Transport: Send + Sync + 'static {
+    /// Sends the message to the `Actor` with the `id` on the `node`.
+    fn send(&self, node: &str, id: &Id, message: RemoteMessage) -> Result<(), Error>;
+}
+
+/// The handle of an `Actor` of another node.
+pub struct RemoteAddress<A> {
+    target: ActorRef<A>,
+    transport: Arc<dyn Transport>,
+}
+
+impl<A> Clone for RemoteAddress<A> {
+    fn clone(&self) -> Self {
+        Self {
+            target: self.target.clone(),
+            transport: self.transport.clone(),
+        }
+    }
+}
+
+impl<A: Actor> RemoteAddress<A> {
+    /// The reference to the `Actor`.
+    pub fn actor_ref(&self) -> &ActorRef<A> {
+        &self.target
+    }
+
+    /// Serializes the action and sends it through the `Transport`.
+    pub fn act<I>(&self, input: I) -> Result<(), Error>
+    where
+        I: Action + Serialize,
+        A: ActionHandler<I>,
+    {
+        let message = RemoteMessage {
+            kind: std::any::type_name::<I>().to_string(),
+            payload: serde_json::to_value(input)?,
+        };
+        self.transport
+            .send(&self.target.node, &self.target.id, message)
+    }
+}
+
+/// The sending handle of an `ActorRef`.
+pub enum Resolved<A: Actor> {
+    /// The `Actor` lives on this node.
+    Local(Address<A>),
+    /// The `Actor` lives on another node.
+    Remote(RemoteAddress<A>),
+}
+
+impl<A: Actor> Resolved<A> {
+    /// Sends the action to the `Actor`.
+    ///
+    /// The action is serialized for remote actors only.
+    pub fn act<I>(&self, input: I) -> Result<(), Error>
+    where
+        I: Action + Serialize,
+        A: ActionHandler<I>,
+    {
+        match self {
+            Self::Local(address) => address.act(input).map_err(Error::from),
+            Self::Remote(address) => address.act(input),
+        }
+    }
+}
+
+struct Export {
+    joinable: Joinable,
+    actor: TypeId,
+    address: Box<dyn Any + Send>,
+}
+
+type Route = Box<dyn Fn(&Export, Value) -> Result<(), Error> + Send>;
+
+#[derive(Default)]
+struct Exports {
+    actors: HashMap<Uuid, Export>,
+    /// Routes by the type of an `Actor` and the type of an action.
+    routes: HashMap<(TypeId, String), Route>,
+}
+
+/// Exports local actors and resolves references to actors of any node.
+///
+/// Clones of the resolver share exports.
+#[derive(Clone)]
+pub struct Resolver {
+    node: Arc<String>,
+    transport: Option<Arc<dyn Transport>>,
+    exports: Arc<Mutex<Exports>>,
+}
+
+impl fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resolver")
+            .field("node", &self.node)
+            .finish()
+    }
+}
+
+impl Resolver {
+    /// Creates a resolver of the `node` that can resolve local references only.
+    pub fn new(node: impl Into<String>) -> Self {
+        Self {
+            node: Arc::new(node.into()),
+            transport: None,
+            exports: Arc::new(Mutex::new(Exports::default())),
+        }
+    }
+
+    /// Sets the `Transport` to reach actors of other nodes.
+    pub fn with_transport(mut self, transport: impl Transport) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// The node of the resolver.
+    pub fn node(&self) -> &str {
+        &self.node
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Exports> {
+        self.exports.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Makes the `Actor` reachable by the returned reference.
+    ///
+    /// The `Actor` is removed from exports when it's terminated.
+    pub fn export<A: Actor>(&self, address: &Address<A>) -> ActorRef<A> {
+        let id: Id = address.id().into();
+        let uuid = *id.uuid();
+        let joinable = Joinable::from(address.clone());
+        let export = Export {
+            joinable: joinable.clone(),
+            actor: TypeId::of::<A>(),
+            address: Box::new(address.clone()),
+        };
+        self.lock().actors.insert(uuid, export);
+        let exports = Arc::downgrade(&self.exports);
+        crate::compat::spawn_async(async move {
+            joinable.join().await;
+            if let Some(exports) = exports.upgrade() {
+                let mut exports = exports.lock().unwrap_or_else(|err| err.into_inner());
+                exports.actors.remove(&uuid);
+            }
+        });
+        ActorRef {
+            id,
+            node: self.node.to_string(),
+            _actor: PhantomData,
+        }
+    }
+
+    /// Accepts remote actions of the type `I` for exported actors of the type `A`.
+    pub fn accept<A, I>(&self)
+    where
+        A: ActionHandler<I>,
+        I: Action + DeserializeOwned,
+    {
+        let route: Route = Box::new(|export, payload| {
+            let address = export
+                .address
+                .downcast_ref::<Address<A>>()
+                .ok_or_else(|| Error::msg("the actor has another type"))?;
+            let input: I = serde_json::from_value(payload)?;
+            address.act(input)?;
+            Ok(())
+        });
+        let kind = std::any::type_name::<I>().to_string();
+        self.lock().routes.insert((TypeId::of::<A>(), kind), route);
+    }
+
+    /// Returns the handle to send actions to the referenced `Actor`.
+    ///
+    /// Fails if a local `Actor` is not exported (or terminated) or if
+    /// the `Actor` lives on another node, but the `Transport` is not set.
+    pub fn resolve<A: Actor>(&self, actor_ref: &ActorRef<A>) -> Result<Resolved<A>, Error> {
+        if actor_ref.node == *self.node {
+            let exports = self.lock();
+            let address = exports
+                .actors
+                .get(actor_ref.id.uuid())
+                .filter(|export| !export.joinable.is_done())
+                .and_then(|export| export.address.downcast_ref::<Address<A>>())
+                .cloned()
+                .ok_or_else(|| Error::msg("the actor is not exported"))?;
+            Ok(Resolved::Local(address))
+        } else {
+            let transport = self
+                .transport
+                .clone()
+                .ok_or_else(|| Error::msg("no transport to reach other nodes"))?;
+            let remote = RemoteAddress {
+                target: actor_ref.clone(),
+                transport,
+            };
+            Ok(Resolved::Remote(remote))
+        }
+    }
+
+    /// Delivers a message received by the `Transport` to the local `Actor`.
+    pub fn deliver(&self, id: &Id, message: RemoteMessage) -> Result<(), Error> {
+        let exports = self.lock();
+        let export = exports
+            .actors
+            .get(id.uuid())
+            .ok_or_else(|| Error::msg("the actor is not exported"))?;
+        let key = (export.actor, message.kind);
+        let route = exports
+            .routes
+            .get(&key)
+            .ok_or_else(|| Error::msg(format!("the type {} is not accepted", key.1)))?;
+        route(export, message.payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Greeting(String);
+
+    impl Action for Greeting {}
+
+    struct Greeter {
+        greetings: Vec<String>,
+    }
+
+    impl Actor for Greeter {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Greeter"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Greeter {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Greeter {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Greeting> for Greeter {
+        async fn handle(&mut self, msg: Greeting, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.greetings.push(msg.0);
+            Ok(())
+        }
+    }
+
+    struct GetGreetings;
+
+    impl Interaction for GetGreetings {
+        type Output = Vec<String>;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<GetGreetings> for Greeter {
+        async fn handle(
+            &mut self,
+            _: GetGreetings,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Vec<String>, Error> {
+            Ok(self.greetings.clone())
+        }
+    }
+
+    #[derive(Default)]
+    struct Listener {
+        greetings: Vec<String>,
+    }
+
+    impl Actor for Listener {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Listener"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Listener {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Listener {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Greeting> for Listener {
+        async fn handle(&mut self, msg: Greeting, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.greetings.push(msg.0);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InteractionHandler<GetGreetings> for Listener {
+        async fn handle(
+            &mut self,
+            _: GetGreetings,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Vec<String>, Error> {
+            Ok(self.greetings.clone())
+        }
+    }
+
+    struct Loopback(Resolver);
+
+    impl Transport for Loopback {
+        fn send(&self, node: &str, id: &Id, message: RemoteMessage) -> Result<(), Error> {
+            assert_eq!(node, self.0.node());
+            let wire = serde_json::to_string(&message)?;
+            self.0.deliver(id, serde_json::from_str(&wire)?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_actor_ref() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let node_b = Resolver::new("b");
+        node_b.accept::<Greeter, Greeting>();
+        let address = System::spawn(Greeter {
+            greetings: Vec::new(),
+        });
+        let actor_ref = node_b.export(&address);
+        let wire = serde_json::to_string(&actor_ref)?;
+        let received: ActorRef<Greeter> = serde_json::from_str(&wire)?;
+        assert_eq!(received, actor_ref);
+
+        let node_a = Resolver::new("a").with_transport(Loopback(node_b.clone()));
+        let remote = node_a.resolve(&received)?;
+        assert!(matches!(remote, Resolved::Remote(_)));
+        remote.act(Greeting("remote".into()))?;
+        let local = node_b.resolve(&received)?;
+        assert!(matches!(local, Resolved::Local(_)));
+        local.act(Greeting("local".into()))?;
+
+        let greetings = address.interact(GetGreetings).recv().await?;
+        assert_eq!(greetings, vec!["remote", "local"]);
+        System::interrupt(&address)?;
+        address.join().await;
+        assert!(node_b.resolve(&received).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_accept_by_actor_type() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let node_b = Resolver::new("b");
+        node_b.accept::<Greeter, Greeting>();
+        node_b.accept::<Listener, Greeting>();
+        let greeter = System::spawn(Greeter {
+            greetings: Vec::new(),
+        });
+        let listener = System::spawn(Listener::default());
+        let node_a = Resolver::new("a").with_transport(Loopback(node_b.clone()));
+        node_a
+            .resolve(&node_b.export(&greeter))?
+            .act(Greeting("greeter".into()))?;
+        node_a
+            .resolve(&node_b.export(&listener))?
+            .act(Greeting("listener".into()))?;
+        let greetings = greeter.interact(GetGreetings).recv().await?;
+        assert_eq!(greetings, vec!["greeter"]);
+        let greetings = listener.interact(GetGreetings).recv().await?;
+        assert_eq!(greetings, vec!["listener"]);
+        System::interrupt(&greeter)?;
+        System::interrupt(&listener)?;
+        greeter.join().await;
+        listener.join().await;
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 //! Module contains stream to connect signals to actors.
 
 use crate::handlers::Action;
-use futures::{stream::BoxStream, FutureExt, StreamExt};
+use futures::stream::{self, BoxStream};
+use futures::{FutureExt, StreamExt};
 use tokio::signal;
 
 /// `Ctrl-C` signal handler.
@@ -23,3 +24,34 @@ impl CtrlC {
             .boxed()
     }
 }
+
+/// `SIGTERM` signal handler.
+pub struct Terminate;
+
+impl Action for Terminate {}
+
+impl Terminate {
+    /// Creates an attachable stream of `SIGTERM` for an `Actor`.
+    ///
+    /// The stream is empty on platforms that have no `SIGTERM` equivalent.
+    pub fn stream() -> BoxStream<'static, Terminate> {
+        #[cfg(unix)]
+        {
+            use signal::unix::{signal, SignalKind};
+            match signal(SignalKind::terminate()) {
+                Ok(signal) => stream::unfold(signal, |mut signal| async move {
+                    signal.recv().await.map(|()| (Terminate, signal))
+                })
+                .boxed(),
+                Err(err) => {
+                    log::error!("SIGTERM signal handler failed: {}", err);
+                    stream::empty().boxed()
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            stream::empty().boxed()
+        }
+    }
+}
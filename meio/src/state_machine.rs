@@ -0,0 +1,249 @@
+//! Actors that handle events depending on their current state.
+
+use crate::actor_runtime::{Actor, Context};
+use crate::dead_letters;
+use crate::handlers::{Action, ActionHandler};
+use anyhow::Error;
+use async_trait::async_trait;
+use std::fmt;
+
+/// The holder of the current state of a `StateMachine`.
+///
+/// It's empty until the `start` method called (usually in `StartedBy` handler)
+/// and all events are rejected before.
+pub struct Machine<S> {
+    state: Option<S>,
+}
+
+impl<S> Default for Machine<S> {
+    fn default() -> Self {
+        Self { state: None }
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for Machine<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Machine")
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl<S> Machine<S> {
+    /// Creates a machine without a state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the initial state.
+    pub fn start(&mut self, initial: S) {
+        self.state = Some(initial);
+    }
+
+    /// Returns the current state.
+    ///
+    /// It's `None` if the machine wasn't started or an event is being handled.
+    pub fn state(&self) -> Option<&S> {
+        self.state.as_ref()
+    }
+}
+
+/// What to do with the state after an event.
+#[derive(Debug)]
+pub enum Transition<S> {
+    /// Keep the current state.
+    Stay,
+    /// Replace the current state with the new one.
+    Goto(S),
+    /// The event is not valid for the current state.
+    ///
+    /// The state stays the same and the event is reported as a dead letter.
+    Reject,
+}
+
+/// The `Actor` that keeps its state in a `Machine`.
+pub trait StateMachine: Actor {
+    /// States of the machine.
+    type State: fmt::Debug + Send + Sync + 'static;
+
+    /// Returns the machine of the `Actor`.
+    fn machine(&mut self) -> &mut Machine<Self::State>;
+}
+
+/// The event that is routed to the `OnEvent` handler of a `StateMachine`.
+pub struct Event<E>(pub E);
+
+impl<E: Send + 'static> Action for Event<E> {}
+
+/// The handler of events of a `StateMachine`.
+#[async_trait]
+pub trait OnEvent<E: Send + 'static>: StateMachine {
+    /// Handles the event in the `state` and returns the transition.
+    ///
+    /// The state stays the same if the handler failed.
+    async fn on_event(
+        &mut self,
+        state: &Self::State,
+        event: E,
+        ctx: &mut Context<Self>,
+    ) -> Result<Transition<Self::State>, Error>;
+}
+
+#[async_trait]
+impl<T, E> ActionHandler<Event<E>> for T
+where
+    T: OnEvent<E>,
+    E: Send + 'static,
+{
+    async fn handle(&mut self, event: Event<E>, ctx: &mut Context<Self>) -> Result<(), Error> {
+        let message = std::any::type_name::<E>();
+        let state = match self.machine().state.take() {
+            Some(state) => state,
+            None => {
+                log::warn!(target: self.log_target(), "Event {} rejected, the machine is not started", message);
+                dead_letters::report(ctx.id(), message);
+                return Ok(());
+            }
+        };
+        let result = OnEvent::on_event(self, &state, event.0, ctx).await;
+        let next = match result {
+            Ok(Transition::Goto(next)) => {
+                log::trace!(target: self.log_target(), "Transition from {:?} to {:?}", state, next);
+                next
+            }
+            Ok(Transition::Stay) => state,
+            Ok(Transition::Reject) => {
+                log::debug!(target: self.log_target(), "Event {} rejected in {:?}", message, state);
+                dead_letters::report(ctx.id(), message);
+                state
+            }
+            Err(err) => {
+                self.machine().state = Some(state);
+                return Err(err);
+            }
+        };
+        self.machine().state = Some(next);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum DoorState {
+        Closed,
+        Open,
+    }
+
+    #[derive(Default)]
+    struct Door {
+        machine: Machine<DoorState>,
+    }
+
+    impl Actor for Door {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Door"
+        }
+    }
+
+    impl StateMachine for Door {
+        type State = DoorState;
+
+        fn machine(&mut self) -> &mut Machine<DoorState> {
+            &mut self.machine
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Door {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.machine.start(DoorState::Closed);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Door {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct Push;
+
+    #[async_trait]
+    impl OnEvent<Push> for Door {
+        async fn on_event(
+            &mut self,
+            state: &DoorState,
+            _event: Push,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Transition<DoorState>, Error> {
+            match state {
+                DoorState::Closed => Ok(Transition::Goto(DoorState::Open)),
+                DoorState::Open => Ok(Transition::Reject),
+            }
+        }
+    }
+
+    struct Pull;
+
+    #[async_trait]
+    impl OnEvent<Pull> for Door {
+        async fn on_event(
+            &mut self,
+            state: &DoorState,
+            _event: Pull,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Transition<DoorState>, Error> {
+            match state {
+                DoorState::Open => Ok(Transition::Goto(DoorState::Closed)),
+                DoorState::Closed => Ok(Transition::Stay),
+            }
+        }
+    }
+
+    struct GetDoorState;
+
+    impl Interaction for GetDoorState {
+        type Output = Option<DoorState>;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<GetDoorState> for Door {
+        async fn handle(
+            &mut self,
+            _: GetDoorState,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Option<DoorState>, Error> {
+            Ok(self.machine.state().copied())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_state_machine() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(Door::default());
+        let state = address.interact(GetDoorState).recv().await?;
+        assert_eq!(state, Some(DoorState::Closed));
+        address.act(Event(Push))?;
+        // Rejected, because the door is already open.
+        address.act(Event(Push))?;
+        let state = address.interact(GetDoorState).recv().await?;
+        assert_eq!(state, Some(DoorState::Open));
+        address.act(Event(Pull))?;
+        address.act(Event(Pull))?;
+        let state = address.interact(GetDoorState).recv().await?;
+        assert_eq!(state, Some(DoorState::Closed));
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
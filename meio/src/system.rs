@@ -1,15 +1,38 @@
 //! This module contains `System` actor.
 
 use crate::actor_runtime::{Actor, Context};
-use crate::handlers::{Eliminated, InterruptedBy, StartedBy};
+use crate::dead_letters::{self, DeadLetter};
+use crate::handlers::{ActionHandler, Eliminated, InterruptedBy, StartedBy};
 use crate::ids::IdOf;
-use crate::linkage::{Address, AddressPair};
+use crate::lifecycle::{InterruptReason, TerminationReason};
+use crate::linkage::{Address, AddressPair, SendError};
 #[cfg(not(feature = "wasm"))]
 use crate::signal;
 use anyhow::Error;
 use async_trait::async_trait;
+use futures::Future;
 #[cfg(not(feature = "wasm"))]
-use futures::{select, FutureExt, StreamExt};
+use futures::{future::Fuse, select, FutureExt, Stream, StreamExt};
+#[cfg(not(feature = "wasm"))]
+use std::time::Duration;
+
+/// How waiting for an `Actor` spawned by the `System` ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitStatus {
+    /// The `Actor` terminated completely.
+    Finished,
+    /// The waiting was cancelled by the repeated signal.
+    Forced,
+    /// The `Actor` hasn't terminated in time after the interruption.
+    TimedOut,
+}
+
+impl WaitStatus {
+    /// Returns `true` if the `Actor` terminated completely.
+    pub fn is_clean(&self) -> bool {
+        *self == Self::Finished
+    }
+}
 
 /// Virtual actor that represents the system/environment.
 pub enum System {}
@@ -24,24 +47,74 @@ impl Actor for System {
 
 #[async_trait]
 impl<T: Actor> Eliminated<T> for System {
-    async fn handle(&mut self, _id: IdOf<T>, _ctx: &mut Context<Self>) -> Result<(), Error> {
+    async fn handle(
+        &mut self,
+        _id: IdOf<T>,
+        _reason: TerminationReason,
+        _ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
         // TODO: Maybe change this in the future...
         unreachable!("The system has no Address and no one actor actually binded to it.")
     }
 }
 
 impl System {
+    /// Sets the `Actor` that receives `DeadLetter` notifications about messages
+    /// that can't be delivered, because the target `Actor` doesn't receive messages anymore.
+    ///
+    /// By default dead letters are just logged with the `trace` level.
+    pub fn set_dead_letters<A>(address: Address<A>)
+    where
+        A: Actor + ActionHandler<DeadLetter>,
+    {
+        dead_letters::set_recipient(Some(Box::new(address)));
+    }
+
+    /// Removes the recipient of `DeadLetter` notifications.
+    pub fn reset_dead_letters() {
+        dead_letters::set_recipient(None);
+    }
+
     /// Spawns a standalone `Actor` that has no `Supervisor`.
     pub fn spawn<A>(actor: A) -> Address<A>
     where
         A: Actor + StartedBy<Self>,
     {
-        let pair = AddressPair::new();
+        let pair = AddressPair::for_actor(&actor);
         let address = pair.address().clone();
         crate::actor_runtime::spawn(actor, Option::<Address<Self>>::None, pair);
         address
     }
 
+    /// Spawns a standalone `Actor` created by the `create` future
+    /// in the task of the `Actor`.
+    pub fn spawn_with<A, Fut>(create: Fut) -> Address<A>
+    where
+        A: Actor + StartedBy<Self>,
+        Fut: Future<Output = Result<A, Error>> + Send + 'static,
+    {
+        let pair = AddressPair::for_created();
+        let address = pair.address().clone();
+        crate::actor_runtime::spawn_created(create, Option::<Address<Self>>::None, pair);
+        address
+    }
+
+    /// Spawns a standalone `Actor` and waits until its `StartedBy` handler completed.
+    ///
+    /// Returns the error of the handler if the `Actor` failed to start.
+    pub async fn spawn_ready<A>(actor: A) -> Result<Address<A>, Error>
+    where
+        A: Actor + StartedBy<Self>,
+    {
+        let pair = AddressPair::for_actor(&actor);
+        let address = pair.address().clone();
+        let ready = crate::actor_runtime::spawn_ready(actor, Option::<Address<Self>>::None, pair);
+        ready
+            .await
+            .map_err(|_| Error::msg("the actor has terminated before the start"))??;
+        Ok(address)
+    }
+
     /// Spawns an `Actor` and wait for its termination (normally or by `SIGINT` interruption).
     #[cfg(not(feature = "wasm"))]
     pub async fn spawn_and_wait<A>(actor: A)
@@ -64,35 +137,271 @@ impl System {
     where
         A: Actor + InterruptedBy<Self>,
     {
-        let mut signals = signal::CtrlC::stream().fuse();
+        System::wait_or_interrupt_on(address, signal::CtrlC::stream()).await
+    }
+
+    /// The same as `wait_or_interrupt`, but listens for the provided stream of signals.
+    ///
+    /// Useful to combine `CtrlC` with `Terminate` signals to support
+    /// graceful shutdown in containers:
+    ///
+    /// ```no_run
+    /// # use futures::{stream, StreamExt};
+    /// # use meio::prelude::{signal, Actor, Address, InterruptedBy, System};
+    /// # async fn wait<A: Actor + InterruptedBy<System>>(address: Address<A>) {
+    /// let signals = stream::select(
+    ///     signal::CtrlC::stream().map(drop),
+    ///     signal::Terminate::stream().map(drop),
+    /// );
+    /// System::wait_or_interrupt_on(address, signals).await.ok();
+    /// # }
+    /// ```
+    #[cfg(not(feature = "wasm"))]
+    pub async fn wait_or_interrupt_on<A, S>(address: Address<A>, signals: S) -> Result<(), Error>
+    where
+        A: Actor + InterruptedBy<Self>,
+        S: Stream + Unpin,
+    {
+        System::wait_with_deadline(address, signals, None)
+            .await
+            .map(drop)
+    }
+
+    /// The same as `wait_or_interrupt_on`, but it doesn't wait for the termination
+    /// longer than the `timeout` after the interruption signal sent to the `Actor`.
+    ///
+    /// Returns `WaitStatus` to let know the termination was clean or not.
+    #[cfg(not(feature = "wasm"))]
+    pub async fn wait_or_interrupt_timeout<A, S>(
+        address: Address<A>,
+        signals: S,
+        timeout: Duration,
+    ) -> Result<WaitStatus, Error>
+    where
+        A: Actor + InterruptedBy<Self>,
+        S: Stream + Unpin,
+    {
+        System::wait_with_deadline(address, signals, Some(timeout)).await
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    async fn wait_with_deadline<A, S>(
+        address: Address<A>,
+        signals: S,
+        timeout: Option<Duration>,
+    ) -> Result<WaitStatus, Error>
+    where
+        A: Actor + InterruptedBy<Self>,
+        S: Stream + Unpin,
+    {
+        let mut signals = signals.fuse();
         let join_addr = address.clone();
         let mut joiner = join_addr.join().boxed().fuse();
+        let mut deadline = Fuse::terminated();
         let mut first_attempt = true;
-        loop {
+        let status = loop {
             select! {
                 _interrupt = signals.select_next_some() => {
-                    log::trace!("Ctrl-C received");
+                    log::trace!("Termination signal received");
                     if first_attempt {
                         first_attempt = false;
                         address.interrupt_by()?;
+                        if let Some(timeout) = timeout {
+                            deadline = crate::compat::delay(timeout).boxed().fuse();
+                        }
                     } else {
-                        break;
+                        break WaitStatus::Forced;
                     }
                 }
                 _done = joiner => {
                     log::trace!("Actor spawned by System done: {:?}", address);
-                    break;
+                    break WaitStatus::Finished;
+                }
+                _elapsed = deadline => {
+                    log::warn!("Actor spawned by System hasn't terminated in time: {:?}", address);
+                    break WaitStatus::TimedOut;
                 }
             }
-        }
-        Ok(())
+        };
+        Ok(status)
     }
 
     /// Interrupts an `Actor`.
-    pub fn interrupt<A>(address: &Address<A>) -> Result<(), Error>
+    pub fn interrupt<A>(address: &Address<A>) -> Result<(), SendError>
     where
         A: Actor + InterruptedBy<Self>,
     {
         address.interrupt_by()
     }
+
+    /// Interrupts an `Actor` with the `reason`.
+    pub fn interrupt_with<A>(address: &Address<A>, reason: InterruptReason) -> Result<(), SendError>
+    where
+        A: Actor + InterruptedBy<Self>,
+    {
+        address.interrupt_with(reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::channel::mpsc;
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    struct FailingStart;
+
+    impl Actor for FailingStart {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "FailingStart"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for FailingStart {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Err(Error::msg("config not found"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_ready() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn_ready(OrderedActor::default()).await?;
+        assert_eq!(address.status(), ActorStatus::Running);
+        System::interrupt(&address)?;
+        address.join().await;
+        let err = System::spawn_ready(FailingStart).await.unwrap_err();
+        assert_eq!(err.to_string(), "config not found");
+        Ok(())
+    }
+
+    struct Throttled;
+
+    impl Actor for Throttled {
+        type GroupBy = ();
+
+        fn mailbox_kind(&self) -> MailboxKind {
+            MailboxKind::Bounded(1)
+        }
+
+        fn created_mailbox_kind() -> MailboxKind {
+            MailboxKind::Bounded(1)
+        }
+
+        fn hp_capacity(&self) -> Option<usize> {
+            Some(1)
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Throttled {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            // Keeps queued messages in place while the test checks limits.
+            sleep(Duration::from_millis(100)).await;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Throttled {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Touch> for Throttled {
+        async fn handle(&mut self, _: Touch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InstantActionHandler<UrgentTouch> for Throttled {
+        async fn handle(&mut self, _: UrgentTouch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_with_settings() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn_with(async { Ok(Throttled) });
+        sleep(Duration::from_millis(20)).await;
+        address.act(Touch)?;
+        assert_eq!(address.act(Touch), Err(SendError::Full));
+        address.instant(UrgentTouch)?;
+        assert!(address.instant(UrgentTouch).is_err());
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    struct Stubborn;
+
+    impl Actor for Stubborn {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Stubborn {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Stubborn {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_or_interrupt_on() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(OrderedActor::default());
+        let (tx, rx) = mpsc::unbounded::<()>();
+        let wait = tokio::spawn(System::wait_or_interrupt_on(address.clone(), rx));
+        sleep(Duration::from_millis(20)).await;
+        assert!(!wait.is_finished());
+        // The signal of the custom stream interrupts the actor.
+        tx.unbounded_send(())?;
+        timeout(Duration::from_secs(1), wait).await???;
+        assert!(address.status().is_done());
+        // The second signal doesn't wait for the actor that ignores interruptions.
+        let address = System::spawn(Stubborn);
+        let signals = futures::stream::iter(vec![(), ()]);
+        timeout(
+            Duration::from_secs(1),
+            System::wait_or_interrupt_on(address.clone(), signals),
+        )
+        .await??;
+        assert!(!address.status().is_done());
+        address.kill();
+        address.join().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_or_interrupt_timeout() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(Stubborn);
+        let signals = futures::stream::iter(vec![()]);
+        let wait =
+            System::wait_or_interrupt_timeout(address.clone(), signals, Duration::from_millis(50));
+        let status = timeout(Duration::from_secs(5), wait).await??;
+        assert_eq!(status, WaitStatus::TimedOut);
+        address.kill();
+        address.join().await;
+        Ok(())
+    }
 }
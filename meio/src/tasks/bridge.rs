@@ -0,0 +1,215 @@
+//! A task that forwards items of a channel to an `Actor`.
+
+use crate::actor_runtime::{Actor, Context};
+use crate::handlers::{Action, TaskEliminated};
+use crate::ids::IdOf;
+use crate::linkage::ActionRecipient;
+use crate::lite_runtime::{LiteTask, TaskError};
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::fmt;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc;
+
+enum Source<M> {
+    Bounded(mpsc::Receiver<M>),
+    Unbounded(mpsc::UnboundedReceiver<M>),
+    Broadcast(BoxStream<'static, M>),
+}
+
+/// The lite task that sends items of a `tokio` channel as actions.
+///
+/// The task finishes when the channel is closed or the recipient
+/// doesn't receive messages anymore. Broadcast receivers that lagged
+/// behind skip the lost items and continue.
+pub struct ChannelBridge<M: Action> {
+    source: Source<M>,
+    recipient: Box<dyn ActionRecipient<M>>,
+}
+
+impl<M: Action> fmt::Debug for ChannelBridge<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelBridge")
+            .field("recipient", &self.recipient)
+            .finish()
+    }
+}
+
+impl<M: Action> ChannelBridge<M> {
+    fn new(source: Source<M>, recipient: impl ActionRecipient<M>) -> Self {
+        Self {
+            source,
+            recipient: Box::new(recipient),
+        }
+    }
+
+    /// Creates a bridge for a bounded `mpsc` channel.
+    pub fn from_mpsc(rx: mpsc::Receiver<M>, recipient: impl ActionRecipient<M>) -> Self {
+        Self::new(Source::Bounded(rx), recipient)
+    }
+
+    /// Creates a bridge for an unbounded `mpsc` channel.
+    pub fn from_unbounded(
+        rx: mpsc::UnboundedReceiver<M>,
+        recipient: impl ActionRecipient<M>,
+    ) -> Self {
+        Self::new(Source::Unbounded(rx), recipient)
+    }
+
+    /// Creates a bridge for a `broadcast` channel.
+    pub fn from_broadcast(rx: broadcast::Receiver<M>, recipient: impl ActionRecipient<M>) -> Self
+    where
+        M: Clone,
+    {
+        let items = stream::unfold(rx, |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(item) => break Some((item, rx)),
+                    Err(RecvError::Lagged(skipped)) => {
+                        log::warn!(target: "ChannelBridge", "Receiver lagged, {} items skipped", skipped);
+                    }
+                    Err(RecvError::Closed) => break None,
+                }
+            }
+        });
+        Self::new(Source::Broadcast(items.boxed()), recipient)
+    }
+
+    async fn next_item(&mut self) -> Option<M> {
+        match &mut self.source {
+            Source::Bounded(rx) => rx.recv().await,
+            Source::Unbounded(rx) => rx.recv().await,
+            Source::Broadcast(items) => items.next().await,
+        }
+    }
+}
+
+#[async_trait]
+impl<M: Action> LiteTask for ChannelBridge<M> {
+    type Output = ();
+
+    fn log_target(&self) -> &str {
+        "ChannelBridge"
+    }
+
+    async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+        while let Some(item) = self.next_item().await {
+            if let Err(err) = self.recipient.act_wait(item).await {
+                if self.recipient.is_closed() {
+                    log::debug!(target: self.log_target(), "Recipient closed, stopping: {}", err);
+                    break;
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<A, M> TaskEliminated<ChannelBridge<M>, ()> for A
+where
+    A: Actor,
+    M: Action,
+{
+    async fn handle(
+        &mut self,
+        id: IdOf<ChannelBridge<M>>,
+        _tag: (),
+        result: Result<(), TaskError>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        if let Err(err) = result {
+            if let Some(err) = err.into_other() {
+                log::warn!(target: self.log_target(), "Channel bridge {} failed: {}", id, err);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    struct Collector {
+        mpsc_rx: Option<tokio::sync::mpsc::Receiver<Item>>,
+        broadcast_rx: Option<tokio::sync::broadcast::Receiver<Item>>,
+        items: Arc<Mutex<Vec<u32>>>,
+    }
+
+    impl Actor for Collector {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Collector"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Collector {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let address = ctx.address().clone();
+            if let Some(rx) = self.mpsc_rx.take() {
+                ctx.spawn_task(ChannelBridge::from_mpsc(rx, address.clone()), (), ());
+            }
+            if let Some(rx) = self.broadcast_rx.take() {
+                ctx.spawn_task(ChannelBridge::from_broadcast(rx, address), (), ());
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Collector {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Item> for Collector {
+        async fn handle(&mut self, item: Item, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.items.lock().unwrap().push(item.0);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_channel_bridge() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let items = Arc::new(Mutex::new(Vec::new()));
+        let (mpsc_tx, mpsc_rx) = tokio::sync::mpsc::channel(4);
+        let (broadcast_tx, broadcast_rx) = tokio::sync::broadcast::channel(2);
+        // The broadcast receiver lags and skips the first items.
+        for n in 10..15 {
+            broadcast_tx.send(Item(n)).ok();
+        }
+        let address = System::spawn(Collector {
+            mpsc_rx: Some(mpsc_rx),
+            broadcast_rx: Some(broadcast_rx),
+            items: items.clone(),
+        });
+        for n in 1..4 {
+            mpsc_tx.send(Item(n)).await.ok();
+        }
+        drop(mpsc_tx);
+        drop(broadcast_tx);
+        sleep(Duration::from_millis(100)).await;
+        address.flush().await?;
+        let mut received = items.lock().unwrap().clone();
+        received.sort_unstable();
+        assert_eq!(received, vec![1, 2, 3, 13, 14]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
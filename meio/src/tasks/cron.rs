@@ -0,0 +1,186 @@
+//! A task that fires actions at wall-clock times.
+
+use crate::actor_runtime::Actor;
+use crate::handlers::{Action, ActionHandler};
+use crate::linkage::{ActionRecipient, Address};
+use crate::lite_runtime::LiteTask;
+use anyhow::Error;
+use async_trait::async_trait;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The longest sleep between checks of the wall clock.
+///
+/// The remaining time is recomputed after it to follow clock adjustments.
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Computes wall-clock times to fire at.
+pub trait Schedule: Send + 'static {
+    /// Returns the first fire time strictly after `from`
+    /// or `None` if the schedule is over.
+    fn next_after(&self, from: SystemTime) -> Option<SystemTime>;
+}
+
+/// Fires once at the time if it's not in the past.
+impl Schedule for SystemTime {
+    fn next_after(&self, from: SystemTime) -> Option<SystemTime> {
+        if *self > from {
+            Some(*self)
+        } else {
+            None
+        }
+    }
+}
+
+/// Fires every `period` aligned to the Unix epoch (UTC) and shifted by the `offset`.
+#[derive(Debug, Clone)]
+pub struct Periodic {
+    period: Duration,
+    offset: Duration,
+}
+
+impl Periodic {
+    /// Creates a new periodic schedule.
+    ///
+    /// The `period` must not be zero.
+    pub fn new(period: Duration, offset: Duration) -> Self {
+        assert!(!period.is_zero(), "the period of a schedule can't be zero");
+        Self { period, offset }
+    }
+
+    /// Fires every day at the time (UTC).
+    pub fn daily(hour: u32, minute: u32) -> Self {
+        let offset = Duration::from_secs(u64::from(hour) * 3600 + u64::from(minute) * 60);
+        Self::new(Duration::from_secs(24 * 3600), offset)
+    }
+
+    /// Fires every hour at the minute.
+    pub fn hourly(minute: u32) -> Self {
+        let offset = Duration::from_secs(u64::from(minute) * 60);
+        Self::new(Duration::from_secs(3600), offset)
+    }
+}
+
+impl Schedule for Periodic {
+    fn next_after(&self, from: SystemTime) -> Option<SystemTime> {
+        let since_epoch = from.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+        let period = self.period.as_nanos();
+        let offset = self.offset.as_nanos() % period;
+        let next = match since_epoch.checked_sub(offset) {
+            Some(shifted) => (shifted / period + 1) * period + offset,
+            None => offset,
+        };
+        let secs = u64::try_from(next / 1_000_000_000).ok()?;
+        let nanos = (next % 1_000_000_000) as u32;
+        UNIX_EPOCH.checked_add(Duration::new(secs, nanos))
+    }
+}
+
+/// The action sent by `CronTask` with the scheduled time.
+#[derive(Debug)]
+pub struct CronTick(pub SystemTime);
+
+impl Action for CronTick {}
+
+/// The lite task that sends `CronTick`s at the times of a `Schedule`.
+///
+/// The wall clock is checked at least every minute, that's why the task
+/// follows clock adjustments. If the clock jumped over several fire times
+/// only one tick is sent. The task finishes when the schedule is over or
+/// the recipient doesn't receive messages anymore.
+pub struct CronTask<S: Schedule> {
+    schedule: S,
+    recipient: Box<dyn ActionRecipient<CronTick>>,
+}
+
+impl<S: Schedule> CronTask<S> {
+    /// Creates a new task.
+    pub fn new<T>(schedule: S, address: Address<T>) -> Self
+    where
+        T: Actor + ActionHandler<CronTick>,
+    {
+        Self {
+            schedule,
+            recipient: Box::new(address),
+        }
+    }
+}
+
+impl<S: Schedule> fmt::Debug for CronTask<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CronTask")
+            .field("recipient", &self.recipient)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S: Schedule> LiteTask for CronTask<S> {
+    type Output = ();
+
+    fn log_target(&self) -> &str {
+        "CronTask"
+    }
+
+    async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+        let mut from = SystemTime::now();
+        while let Some(next) = self.schedule.next_after(from) {
+            while let Ok(remaining) = next.duration_since(SystemTime::now()) {
+                if remaining.is_zero() {
+                    break;
+                }
+                crate::compat::delay(remaining.min(MAX_SLEEP)).await;
+            }
+            if let Err(err) = self.recipient.act(CronTick(next)) {
+                if self.recipient.is_closed() {
+                    log::debug!(target: self.log_target(), "Recipient closed, stopping: {}", err);
+                    break;
+                }
+                log::error!(target: self.log_target(), "Can't send a tick: {}", err);
+            }
+            from = SystemTime::now().max(next);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[async_trait]
+    impl ActionHandler<CronTick> for OrderedActor {
+        async fn handle(&mut self, _: CronTick, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.seen.push(0);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cron_task() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let daily = Periodic::daily(2, 0);
+        let from = std::time::UNIX_EPOCH + Duration::from_secs(3 * 3600);
+        let next = daily.next_after(from).unwrap();
+        assert_eq!(next, from + Duration::from_secs(23 * 3600));
+        assert_eq!(
+            daily.next_after(next),
+            Some(next + Duration::from_secs(24 * 3600))
+        );
+
+        let address = System::spawn(OrderedActor::default());
+        let at = std::time::SystemTime::now() + Duration::from_millis(50);
+        let task = CronTask::new(at, address.clone());
+        // The one-time schedule is over after the first tick.
+        timeout(Duration::from_secs(1), task.interruptable_routine()).await??;
+        assert_eq!(address.ask(GetSeen).await?, vec![0]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! Contains a task that delivers the result of a future to an `Actor`.
+
+use crate::actor_runtime::{Actor, Context};
+use crate::handlers::{Action, ActionHandler, TaskEliminated};
+use crate::ids::IdOf;
+use crate::linkage::Address;
+use crate::lite_runtime::{LiteTask, TaskError};
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::{Future, FutureExt};
+
+/// The task spawned by `Context::spawn_detached`.
+///
+/// It awaits the future and sends the output to the `Actor` as an `Action`.
+/// The future is dropped if the task is stopped before it completed.
+pub struct Detached {
+    fut: BoxFuture<'static, Result<(), Error>>,
+}
+
+impl Detached {
+    pub(crate) fn new<A, F>(fut: F, address: Address<A>) -> Self
+    where
+        A: ActionHandler<F::Output>,
+        F: Future + Send + 'static,
+        F::Output: Action,
+    {
+        let fut = async move {
+            let output = fut.await;
+            address.act(output)?;
+            Ok(())
+        };
+        Self { fut: fut.boxed() }
+    }
+}
+
+#[async_trait]
+impl LiteTask for Detached {
+    type Output = ();
+
+    fn log_target(&self) -> &str {
+        "Detached"
+    }
+
+    async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+        self.fut.await
+    }
+}
+
+#[async_trait]
+impl<A> TaskEliminated<Detached, ()> for A
+where
+    A: Actor,
+{
+    async fn handle(
+        &mut self,
+        id: IdOf<Detached>,
+        _tag: (),
+        result: Result<(), TaskError>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        if let Err(err) = result {
+            if let Some(err) = err.into_other() {
+                log::warn!(target: self.log_target(), "Detached future {} failed: {}", id, err);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    struct FanOut {
+        log: Vec<&'static str>,
+    }
+
+    impl Actor for FanOut {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "FanOut"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for FanOut {
+        async fn handle(&mut self, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for FanOut {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    struct FanStart(Duration);
+
+    impl Action for FanStart {}
+
+    #[async_trait]
+    impl ActionHandler<FanStart> for FanOut {
+        async fn handle(&mut self, msg: FanStart, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.spawn_detached(
+                async move {
+                    sleep(msg.0).await;
+                    FanResult
+                },
+                (),
+            );
+            Ok(())
+        }
+    }
+
+    struct FanResult;
+
+    impl Action for FanResult {}
+
+    #[async_trait]
+    impl ActionHandler<FanResult> for FanOut {
+        async fn handle(&mut self, _: FanResult, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.log.push("result");
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Touch> for FanOut {
+        async fn handle(&mut self, _: Touch, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.log.push("touch");
+            Ok(())
+        }
+    }
+
+    struct GetFanLog;
+
+    impl Interaction for GetFanLog {
+        type Output = Vec<&'static str>;
+    }
+
+    #[async_trait]
+    impl InteractionHandler<GetFanLog> for FanOut {
+        async fn handle(
+            &mut self,
+            _: GetFanLog,
+            _ctx: &mut Context<Self>,
+        ) -> Result<Vec<&'static str>, Error> {
+            Ok(self.log.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_detached() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(FanOut { log: Vec::new() });
+        address.act(FanStart(Duration::from_millis(100)))?;
+        address.act(Touch)?;
+        let log = address.interact(GetFanLog).recv().await?;
+        assert_eq!(log, vec!["touch"]);
+        sleep(Duration::from_millis(200)).await;
+        let log = address.interact(GetFanLog).recv().await?;
+        assert_eq!(log, vec!["touch", "result"]);
+
+        // The pending future is dropped when the actor terminates.
+        address.act(FanStart(Duration::from_secs(60)))?;
+        address.interact(GetFanLog).recv().await?;
+        System::interrupt(&address)?;
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+}
@@ -31,14 +31,20 @@ impl HeartBeatHandle {
 }
 
 /// The lite task that sends ticks to a `Recipient`.
+///
+/// It finishes by itself when the `Recipient` doesn't receive messages anymore.
 #[derive(Debug)]
 pub struct HeartBeat {
     duration: watch::Receiver<Duration>,
+    jitter: Duration,
+    immediate: bool,
     recipient: Box<dyn ActionRecipient<Tick>>,
 }
 
 impl HeartBeat {
     /// Creates a new `HeartBeat` lite task.
+    ///
+    /// The first `Tick` will be sent after the `duration`.
     pub fn new<T>(duration: Duration, address: Address<T>) -> Self
     where
         T: Actor + ActionHandler<Tick>,
@@ -47,6 +53,27 @@ impl HeartBeat {
         Self::new_with_handle(&handle, address)
     }
 
+    /// Creates a new `HeartBeat` lite task that sends the first `Tick` immediately.
+    pub fn immediate<T>(duration: Duration, address: Address<T>) -> Self
+    where
+        T: Actor + ActionHandler<Tick>,
+    {
+        let mut heartbeat = Self::new(duration, address);
+        heartbeat.immediate = true;
+        heartbeat
+    }
+
+    /// Creates a new `HeartBeat` lite task that randomizes every
+    /// delay between ticks within `duration ± jitter`.
+    pub fn with_jitter<T>(duration: Duration, jitter: Duration, address: Address<T>) -> Self
+    where
+        T: Actor + ActionHandler<Tick>,
+    {
+        let mut heartbeat = Self::new(duration, address);
+        heartbeat.jitter = jitter;
+        heartbeat
+    }
+
     /// Creates a new `HeartBeat` lite task.
     /// With `HeartBeatHandle` to change `duration` on the fly.
     pub fn new_with_handle<T>(handle: &HeartBeatHandle, address: Address<T>) -> Self
@@ -56,6 +83,8 @@ impl HeartBeat {
         let rx = handle.duration.subscribe();
         Self {
             duration: rx,
+            jitter: Duration::ZERO,
+            immediate: false,
             recipient: Box::new(address),
         }
     }
@@ -75,11 +104,25 @@ impl LiteTask for HeartBeat {
         "HeartBeat"
     }
 
+    async fn pre_repeatable_routine(&mut self) -> Result<(), Error> {
+        if !self.immediate {
+            self.routine_wait(Instant::now(), true).await;
+        }
+        Ok(())
+    }
+
     async fn repeatable_routine(&mut self) -> Result<Option<Self::Output>, Error> {
         // IMPORTANT: Don't use `schedule` to avoid late beats: when the task was canceled,
         // but teh scheduled messages still remained in the actor's queue.
         let tick = Tick(Instant::now());
-        self.recipient.act(tick)?;
+        if let Err(err) = self.recipient.act(tick) {
+            if self.recipient.is_closed() {
+                // The recipient has terminated and nobody needs beats anymore.
+                log::debug!(target: self.log_target(), "Recipient closed, stopping: {}", err);
+                return Ok(Some(()));
+            }
+            return Err(err);
+        }
         // StopSender can be used to interrupt it
         Ok(None)
     }
@@ -87,8 +130,9 @@ impl LiteTask for HeartBeat {
     async fn routine_wait(&mut self, _last_attempt: Instant, _succeed: bool) {
         use tokio::time::{sleep_until, timeout_at};
         let now = Instant::now();
+        let fraction = random_fraction();
         loop {
-            let duration = *self.duration.borrow();
+            let duration = jittered(*self.duration.borrow(), self.jitter, fraction);
             let instant = (now + duration).into();
             let res = timeout_at(instant, self.duration.changed()).await;
             match res {
@@ -110,12 +154,28 @@ impl LiteTask for HeartBeat {
     }
 }
 
+/// Returns a random value in the `[0.0, 1.0]` range.
+fn random_fraction() -> f64 {
+    let value = uuid::Uuid::new_v4().as_u128() as u64;
+    value as f64 / u64::MAX as f64
+}
+
+/// Shifts the `duration` within `duration ± jitter` by the `fraction`.
+fn jittered(duration: Duration, jitter: Duration, fraction: f64) -> Duration {
+    if jitter.is_zero() {
+        duration
+    } else {
+        duration.saturating_sub(jitter) + (jitter * 2).mul_f64(fraction)
+    }
+}
+
 /// Handler of heartbeat events and a task
 #[async_trait]
 pub trait OnTick: Actor {
     /// Called when tick received.
     ///
-    /// Also `tick` will be called after lite task initialization.
+    /// Also `tick` will be called after lite task initialization
+    /// if the `HeartBeat` was created with the `immediate` constructor.
     async fn tick(&mut self, tick: Tick, ctx: &mut Context<Self>) -> Result<(), Error>;
     /// Called when the heartbeat task finished or interrupted.
     async fn done(&mut self, ctx: &mut Context<Self>) -> Result<(), Error>;
@@ -147,3 +207,163 @@ where
         OnTick::done(self, ctx).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::channel::oneshot;
+    use std::time::Duration;
+    use tokio::time::{timeout, Instant};
+
+    #[derive(Default)]
+    struct GuardedBeat {
+        guard: Option<TaskGuard<heartbeat::HeartBeat>>,
+    }
+
+    impl Actor for GuardedBeat {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "GuardedBeat"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for GuardedBeat {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let heartbeat =
+                heartbeat::HeartBeat::immediate(Duration::from_secs(60), ctx.address().clone());
+            let guard = ctx.spawn_task(heartbeat, (), ()).stop_on_drop();
+            self.guard = Some(guard);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl heartbeat::OnTick for GuardedBeat {
+        async fn tick(
+            &mut self,
+            _tick: heartbeat::Tick,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.guard.take();
+            Ok(())
+        }
+
+        async fn done(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_guard() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(GuardedBeat::default());
+        timeout(Duration::from_secs(5), address.join()).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_stops_on_closed_recipient() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(GuardedBeat::default());
+        let heartbeat = heartbeat::HeartBeat::immediate(Duration::from_millis(10), address.clone());
+        timeout(Duration::from_secs(5), address.join()).await?;
+        // The beat finishes by itself instead of failing on every tick.
+        timeout(Duration::from_secs(1), heartbeat.interruptable_routine()).await??;
+        Ok(())
+    }
+
+    #[test]
+    fn test_jittered() {
+        use crate::tasks::heartbeat::{jittered, random_fraction};
+        let duration = Duration::from_secs(10);
+        let jitter = Duration::from_secs(2);
+        assert_eq!(jittered(duration, jitter, 0.0), Duration::from_secs(8));
+        assert_eq!(jittered(duration, jitter, 1.0), Duration::from_secs(12));
+        for _ in 0..100 {
+            let delay = jittered(duration, jitter, random_fraction());
+            assert!(delay >= duration - jitter && delay <= duration + jitter);
+        }
+        // The delay can't be negative if the jitter exceeds the duration.
+        let delay = jittered(Duration::from_secs(1), jitter, 0.0);
+        assert_eq!(delay, Duration::ZERO);
+        assert_eq!(jittered(duration, Duration::ZERO, 1.0), duration);
+    }
+
+    struct FirstTick {
+        immediate: bool,
+        started: Instant,
+        first: Option<oneshot::Sender<Duration>>,
+    }
+
+    impl Actor for FirstTick {
+        type GroupBy = ();
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for FirstTick {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let duration = Duration::from_secs(60);
+            let address = ctx.address().clone();
+            let heartbeat = if self.immediate {
+                heartbeat::HeartBeat::immediate(duration, address)
+            } else {
+                heartbeat::HeartBeat::new(duration, address)
+            };
+            ctx.spawn_task(heartbeat, (), ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for FirstTick {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl heartbeat::OnTick for FirstTick {
+        async fn tick(
+            &mut self,
+            _tick: heartbeat::Tick,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            if let Some(first) = self.first.take() {
+                first.send(self.started.elapsed()).ok();
+            }
+            Ok(())
+        }
+
+        async fn done(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    async fn first_tick(immediate: bool) -> Result<Duration, Error> {
+        let (tx, rx) = oneshot::channel();
+        let address = System::spawn(FirstTick {
+            immediate,
+            started: Instant::now(),
+            first: Some(tx),
+        });
+        let elapsed = rx.await?;
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(elapsed)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_first_tick() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        assert!(first_tick(true).await? < Duration::from_secs(1));
+        assert!(first_tick(false).await? >= Duration::from_secs(60));
+        Ok(())
+    }
+}
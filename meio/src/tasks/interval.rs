@@ -0,0 +1,166 @@
+//! Periodic actions that an `Actor` sends to itself.
+
+use crate::actor_runtime::{Actor, Context};
+use crate::handlers::{Action, ActionHandler, TaskEliminated};
+use crate::ids::IdOf;
+use crate::linkage::Address;
+use crate::lite_runtime::{LiteTask, TaskError, TaskGuard};
+use anyhow::Error;
+use async_trait::async_trait;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tokio::time::sleep_until;
+
+/// The task that sends an action to an `Actor` every period.
+pub(crate) struct IntervalTask {
+    period: Duration,
+    tick: Box<dyn FnMut() -> Result<(), Error> + Send>,
+}
+
+impl IntervalTask {
+    pub fn new<A, M, F>(address: Address<A>, period: Duration, make_msg: F) -> Self
+    where
+        A: Actor + ActionHandler<M>,
+        M: Action,
+        F: Fn() -> M + Send + 'static,
+    {
+        let tick = move || address.act(make_msg()).map_err(Error::from);
+        Self {
+            period,
+            tick: Box::new(tick),
+        }
+    }
+}
+
+impl fmt::Debug for IntervalTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntervalTask")
+            .field("period", &self.period)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl LiteTask for IntervalTask {
+    type Output = ();
+
+    fn log_target(&self) -> &str {
+        "IntervalTask"
+    }
+
+    async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+        let mut next = Instant::now() + self.period;
+        loop {
+            sleep_until(next.into()).await;
+            (self.tick)()?;
+            next += self.period;
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Actor> TaskEliminated<IntervalTask, ()> for T {
+    async fn handle(
+        &mut self,
+        _id: IdOf<IntervalTask>,
+        _tag: (),
+        _result: Result<(), TaskError>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// The handle of a periodic action started by `Context::start_interval`.
+///
+/// The interval stops when the handle is canceled or dropped.
+#[derive(Debug)]
+pub struct IntervalHandle {
+    guard: TaskGuard<IntervalTask>,
+}
+
+impl IntervalHandle {
+    pub(crate) fn new(guard: TaskGuard<IntervalTask>) -> Self {
+        Self { guard }
+    }
+
+    /// Stops the interval.
+    pub fn cancel(self) {
+        if let Err(err) = self.guard.stop() {
+            log::error!("Can't stop the interval: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    #[derive(Default)]
+    struct IntervalActor {
+        handle: Option<IntervalHandle>,
+        ticks: u8,
+    }
+
+    impl Actor for IntervalActor {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "IntervalActor"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for IntervalActor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let handle = ctx.start_interval(Duration::from_millis(20), || Seq(1));
+            self.handle = Some(handle);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for IntervalActor {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<Seq> for IntervalActor {
+        async fn handle(&mut self, msg: Seq, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.ticks += msg.0;
+            if self.ticks == 3 {
+                if let Some(handle) = self.handle.take() {
+                    handle.cancel();
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<GetSeen> for IntervalActor {
+        async fn handle(&mut self, msg: GetSeen, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            msg.0.send(vec![self.ticks]).ok();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_interval() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let address = System::spawn(IntervalActor::default());
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(address.ask(GetSeen).await?, vec![3]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
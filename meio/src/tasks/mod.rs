@@ -1,4 +1,13 @@
 //! Contains ready-to-use tasks.
 
+pub mod bridge;
+pub mod cron;
+pub mod detached;
 pub mod fn_task;
 pub mod heartbeat;
+pub mod interval;
+pub mod one_shot;
+pub mod race;
+pub mod retry;
+pub mod supervised;
+pub mod timeout;
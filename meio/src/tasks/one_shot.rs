@@ -0,0 +1,76 @@
+//! A task that runs a `Future` once and sends its output to a recipient.
+
+use crate::actor_runtime::{Actor, Context};
+use crate::handlers::{Action, TaskEliminated};
+use crate::ids::IdOf;
+use crate::linkage::ActionRecipient;
+use crate::lite_runtime::{LiteTask, TaskError};
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::Future;
+
+/// The `LiteTask` that awaits a `Future` and sends the output
+/// to an `ActionRecipient`.
+///
+/// The `Future` is dropped if the task is interrupted.
+/// If the `Future` fails nothing is sent and the error
+/// is logged by the supervisor.
+pub struct OneShotTask<F, T: Action> {
+    future: F,
+    recipient: Box<dyn ActionRecipient<T>>,
+}
+
+impl<F, T> OneShotTask<F, T>
+where
+    F: Future<Output = Result<T, Error>> + Send + 'static,
+    T: Action,
+{
+    /// Creates a new task.
+    pub fn new(future: F, recipient: impl ActionRecipient<T>) -> Self {
+        Self {
+            future,
+            recipient: Box::new(recipient),
+        }
+    }
+}
+
+#[async_trait]
+impl<F, T> LiteTask for OneShotTask<F, T>
+where
+    F: Future<Output = Result<T, Error>> + Send + 'static,
+    T: Action,
+{
+    type Output = ();
+
+    fn log_target(&self) -> &str {
+        "OneShotTask"
+    }
+
+    async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+        let output = self.future.await?;
+        self.recipient.act_wait(output).await
+    }
+}
+
+#[async_trait]
+impl<A, F, T> TaskEliminated<OneShotTask<F, T>, ()> for A
+where
+    A: Actor,
+    F: Future<Output = Result<T, Error>> + Send + 'static,
+    T: Action,
+{
+    async fn handle(
+        &mut self,
+        id: IdOf<OneShotTask<F, T>>,
+        _tag: (),
+        result: Result<(), TaskError>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        if let Err(err) = result {
+            if let Some(err) = err.into_other() {
+                log::warn!(target: self.log_target(), "One-shot task {} failed: {}", id, err);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+//! Contains a wrapper of tasks that race with each other.
+
+use crate::actor_runtime::Context;
+use crate::handlers::{Action, ActionHandler, TaskEliminated};
+use crate::ids::IdOf;
+use crate::lite_runtime::{LiteTask, Progress, StopReceiver, Tag, TaskAddress, TaskError};
+use anyhow::Error;
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The task that takes part in a race started by `Context::spawn_race`.
+pub struct Racer<T> {
+    task: T,
+}
+
+impl<T: LiteTask> Racer<T> {
+    pub(crate) fn new(task: T) -> Self {
+        Self { task }
+    }
+}
+
+#[async_trait]
+impl<T: LiteTask> LiteTask for Racer<T> {
+    type Output = T::Output;
+
+    fn log_target(&self) -> &str {
+        self.task.log_target()
+    }
+
+    fn bind_progress(&mut self, progress: Progress) {
+        self.task.bind_progress(progress);
+    }
+
+    async fn routine(self, stop: StopReceiver) -> Result<Self::Output, Error> {
+        self.task.routine(stop).await
+    }
+}
+
+/// The state shared by all participants of a race.
+pub(crate) struct Race<T> {
+    finished: AtomicBool,
+    cancel_rest: bool,
+    racers: Mutex<Vec<TaskAddress<Racer<T>>>>,
+}
+
+impl<T> Race<T> {
+    pub(crate) fn new(cancel_rest: bool) -> Self {
+        Self {
+            finished: AtomicBool::new(false),
+            cancel_rest,
+            racers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn add(&self, racer: TaskAddress<Racer<T>>) {
+        self.racers.lock().unwrap().push(racer);
+    }
+}
+
+/// The tag of a participant of a race.
+pub struct RaceTag<T> {
+    which: usize,
+    race: Arc<Race<T>>,
+}
+
+impl<T> RaceTag<T> {
+    pub(crate) fn new(which: usize, race: Arc<Race<T>>) -> Self {
+        Self { which, race }
+    }
+}
+
+impl<T: LiteTask> Tag for RaceTag<T> {}
+
+/// The result of the first finished task of a race.
+pub struct TaskCompleted<T: LiteTask> {
+    /// The index of the task in the order of spawning.
+    pub which: usize,
+    /// The result of the task.
+    pub result: Result<T::Output, TaskError>,
+}
+
+impl<T: LiteTask> Action for TaskCompleted<T> {}
+
+#[async_trait]
+impl<A, T> TaskEliminated<Racer<T>, RaceTag<T>> for A
+where
+    A: ActionHandler<TaskCompleted<T>>,
+    T: LiteTask,
+{
+    async fn handle(
+        &mut self,
+        id: IdOf<Racer<T>>,
+        tag: RaceTag<T>,
+        result: Result<T::Output, TaskError>,
+        ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        let race = tag.race;
+        if race.finished.swap(true, Ordering::SeqCst) {
+            log::trace!(target: self.log_target(), "Task {} finished after the race", id);
+            return Ok(());
+        }
+        if race.cancel_rest {
+            let racers = race.racers.lock().unwrap();
+            for (which, racer) in racers.iter().enumerate() {
+                if which != tag.which {
+                    // Finished tasks don't receive the signal.
+                    racer.stop().ok();
+                }
+            }
+        }
+        let completed = TaskCompleted {
+            which: tag.which,
+            result,
+        };
+        ActionHandler::handle(self, completed, ctx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::channel::oneshot;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    struct Source {
+        delay: Duration,
+        finished: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl LiteTask for Source {
+        type Output = Duration;
+
+        fn log_target(&self) -> &str {
+            "Source"
+        }
+
+        async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+            sleep(self.delay).await;
+            self.finished.fetch_add(1, Ordering::SeqCst);
+            Ok(self.delay)
+        }
+    }
+
+    struct Racing {
+        winner: Option<oneshot::Sender<(usize, Duration)>>,
+        finished: Arc<AtomicUsize>,
+    }
+
+    impl Actor for Racing {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Racing"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Racing {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let sources = [300, 10, 200].into_iter().map(|millis| Source {
+                delay: Duration::from_millis(millis),
+                finished: self.finished.clone(),
+            });
+            let racers = ctx.spawn_race(sources, true, ());
+            assert_eq!(racers.len(), 3);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Racing {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<TaskCompleted<Source>> for Racing {
+        async fn handle(
+            &mut self,
+            msg: TaskCompleted<Source>,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            if let Some(winner) = self.winner.take() {
+                winner.send((msg.which, msg.result?)).ok();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_race() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let finished = Arc::new(AtomicUsize::new(0));
+        let address = System::spawn(Racing {
+            winner: Some(tx),
+            finished: finished.clone(),
+        });
+        let (which, delay) = timeout(Duration::from_secs(5), rx).await??;
+        assert_eq!(which, 1);
+        assert_eq!(delay, Duration::from_millis(10));
+        // Losers are stopped and never finish their routines.
+        sleep(Duration::from_millis(400)).await;
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
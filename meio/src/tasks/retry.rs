@@ -0,0 +1,197 @@
+//! Contains a `LiteTask` that retries a fallible operation.
+
+use crate::actor_runtime::{Actor, Context};
+use crate::handlers::TaskEliminated;
+use crate::ids::{Id, IdOf};
+use crate::lite_runtime::{LiteTask, Tag, TaskError};
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::Future;
+use std::time::Duration;
+
+/// The delay between attempts of the `Retry` task.
+#[derive(Debug, Clone)]
+pub enum Backoff {
+    /// The same delay between all attempts.
+    Fixed(Duration),
+    /// The delay doubled after every attempt, but never exceeds the `max` value.
+    Exponential {
+        /// The delay after the first attempt.
+        initial: Duration,
+        /// The maximal delay.
+        max: Duration,
+    },
+}
+
+impl Backoff {
+    /// Returns the delay after the `attempt` (starting from `1`).
+    pub fn delay(&self, attempt: usize) -> Duration {
+        match self {
+            Self::Fixed(duration) => *duration,
+            Self::Exponential { initial, max } => {
+                let power = attempt.saturating_sub(1).min(u32::MAX as usize) as u32;
+                let factor = 2u32.checked_pow(power).unwrap_or(u32::MAX);
+                initial.checked_mul(factor).unwrap_or(*max).min(*max)
+            }
+        }
+    }
+}
+
+/// The `LiteTask` that calls the `operation` till it succeed
+/// or the number of attempts exceeded.
+///
+/// The result of the last attempt is returned as the `Output`
+/// of the task. The task can be interrupted during a delay as well.
+pub struct Retry<F> {
+    operation: F,
+    max_attempts: usize,
+    backoff: Backoff,
+}
+
+impl<F> Retry<F> {
+    /// Creates a new `Retry` task.
+    ///
+    /// The `operation` will be called at least once even if `max_attempts` is `0`.
+    pub fn new(max_attempts: usize, backoff: Backoff, operation: F) -> Self {
+        Self {
+            operation,
+            max_attempts,
+            backoff,
+        }
+    }
+}
+
+#[async_trait]
+impl<F, Fut, O> LiteTask for Retry<F>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<O, Error>> + Send,
+    O: Send,
+{
+    type Output = O;
+
+    fn log_target(&self) -> &str {
+        "Retry"
+    }
+
+    async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+        let mut attempt = 1;
+        loop {
+            match (self.operation)().await {
+                Ok(output) => {
+                    break Ok(output);
+                }
+                Err(err) if attempt < self.max_attempts => {
+                    log::warn!(target: self.log_target(), "Attempt {} of {} failed: {}", attempt, self.max_attempts, err);
+                    crate::compat::delay(self.backoff.delay(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => {
+                    break Err(err);
+                }
+            }
+        }
+    }
+}
+
+/// Called when the `Retry` task terminated.
+#[async_trait]
+pub trait RetryEliminated<O, M: Tag>: Actor {
+    /// Called when the `Retry` task finished.
+    async fn handle(
+        &mut self,
+        id: Id,
+        tag: M,
+        result: Result<O, TaskError>,
+        ctx: &mut Context<Self>,
+    ) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl<T, F, M> TaskEliminated<Retry<F>, M> for T
+where
+    Retry<F>: LiteTask,
+    T: RetryEliminated<<Retry<F> as LiteTask>::Output, M>,
+    M: Tag,
+{
+    async fn handle(
+        &mut self,
+        id: IdOf<Retry<F>>,
+        tag: M,
+        result: Result<<Retry<F> as LiteTask>::Output, TaskError>,
+        ctx: &mut Context<Self>,
+    ) -> Result<(), Error> {
+        RetryEliminated::handle(self, id.into(), tag, result, ctx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    struct RetrySpawner {
+        attempts: Arc<AtomicUsize>,
+    }
+
+    impl Actor for RetrySpawner {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "RetrySpawner"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for RetrySpawner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let attempts = self.attempts.clone();
+            let operation = move || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    if attempt < 3 {
+                        Err(Error::msg("flaky operation failed"))
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            };
+            let backoff = Backoff::Fixed(Duration::from_millis(10));
+            ctx.spawn_task(Retry::new(5, backoff, operation), (), ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl RetryEliminated<usize, ()> for RetrySpawner {
+        async fn handle(
+            &mut self,
+            _id: Id,
+            _tag: (),
+            result: Result<usize, TaskError>,
+            ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            assert_eq!(result?, 3);
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let spawner = RetrySpawner {
+            attempts: attempts.clone(),
+        };
+        let address = System::spawn(spawner);
+        timeout(Duration::from_secs(5), address.join()).await?;
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+}
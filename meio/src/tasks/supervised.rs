@@ -0,0 +1,192 @@
+//! Contains a wrapper that restarts failed tasks.
+
+use crate::actor_runtime::RestartPolicy;
+use crate::lite_runtime::{LiteTask, Progress, StopReceiver};
+use crate::tasks::retry::Backoff;
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// The task spawned by `Context::spawn_task_supervised`.
+///
+/// Creates a fresh task with the factory after every failure
+/// while the `RestartPolicy` allows that. The actor is notified with
+/// `TaskEliminated` only when the task succeeded, was interrupted or
+/// failed permanently.
+pub struct Supervised<T> {
+    factory: Box<dyn FnMut() -> T + Send>,
+    policy: RestartPolicy,
+    backoff: Backoff,
+    progress: Option<Progress>,
+}
+
+impl<T: LiteTask> Supervised<T> {
+    pub(crate) fn new<F>(factory: F, policy: RestartPolicy, backoff: Backoff) -> Self
+    where
+        F: FnMut() -> T + Send + 'static,
+    {
+        Self {
+            factory: Box::new(factory),
+            policy,
+            backoff,
+            progress: None,
+        }
+    }
+
+    fn create(&mut self) -> T {
+        let mut task = (self.factory)();
+        if let Some(progress) = self.progress.clone() {
+            task.bind_progress(progress);
+        }
+        task
+    }
+}
+
+#[async_trait]
+impl<T: LiteTask> LiteTask for Supervised<T> {
+    type Output = T::Output;
+
+    fn log_target(&self) -> &str {
+        "Supervised"
+    }
+
+    fn bind_progress(&mut self, progress: Progress) {
+        self.progress = Some(progress);
+    }
+
+    async fn routine(mut self, mut stop: StopReceiver) -> Result<Self::Output, Error> {
+        let max_retries = match self.policy {
+            RestartPolicy::Never => 0,
+            RestartPolicy::OnError { max_retries } => max_retries,
+        };
+        let mut retries = 0;
+        loop {
+            let task = self.create();
+            let target = task.log_target().to_string();
+            match task.routine(stop.clone()).await {
+                Ok(output) => {
+                    break Ok(output);
+                }
+                Err(err) if retries < max_retries && stop.is_alive() => {
+                    retries += 1;
+                    log::warn!(target: &target, "Task failed, restarting {} of {}: {}", retries, max_retries, err);
+                    stop.or(crate::compat::delay(self.backoff.delay(retries)))
+                        .await?;
+                }
+                Err(err) => {
+                    break Err(err);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    struct Flapping {
+        attempts: Arc<AtomicUsize>,
+        failures: usize,
+    }
+
+    #[async_trait]
+    impl LiteTask for Flapping {
+        type Output = usize;
+
+        fn log_target(&self) -> &str {
+            "Flapping"
+        }
+
+        async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempt <= self.failures {
+                Err(Error::msg("connection lost"))
+            } else {
+                Ok(attempt)
+            }
+        }
+    }
+
+    struct FlapTag(usize);
+
+    impl Tag for FlapTag {}
+
+    type FlapResults = Arc<Mutex<Vec<(usize, Option<usize>)>>>;
+
+    struct FlapOwner {
+        results: FlapResults,
+    }
+
+    impl Actor for FlapOwner {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "FlapOwner"
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for FlapOwner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let backoff = Backoff::Exponential {
+                initial: Duration::from_millis(1),
+                max: Duration::from_millis(10),
+            };
+            for (tag, max_retries) in [(0, 3), (1, 1)] {
+                let attempts = Arc::new(AtomicUsize::new(0));
+                let factory = move || Flapping {
+                    attempts: attempts.clone(),
+                    failures: 2,
+                };
+                let policy = RestartPolicy::OnError { max_retries };
+                ctx.spawn_task_supervised(factory, policy, backoff.clone(), FlapTag(tag), ());
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for FlapOwner {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl TaskEliminated<Supervised<Flapping>, FlapTag> for FlapOwner {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Supervised<Flapping>>,
+            tag: FlapTag,
+            result: Result<usize, TaskError>,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.results.lock().unwrap().push((tag.0, result.ok()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_task_supervised() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let address = System::spawn(FlapOwner {
+            results: results.clone(),
+        });
+        sleep(Duration::from_millis(200)).await;
+        let mut results = results.lock().unwrap().clone();
+        results.sort_unstable();
+        // The first task succeeded on the third attempt, the second gave up after a retry.
+        assert_eq!(results, vec![(0, Some(3)), (1, None)]);
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+//! Contains a `LiteTask` that limits the time of an activity.
+
+use crate::actor_runtime::Actor;
+use crate::handlers::{Action, ActionHandler};
+use crate::linkage::{ActionRecipient, Address};
+use crate::lite_runtime::LiteTask;
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::future::{self, BoxFuture, Either};
+use futures::{Future, FutureExt};
+use std::fmt;
+use std::time::Duration;
+
+/// The lite task that runs a future no longer than the `duration`.
+///
+/// If the future hasn't completed in time it will be dropped,
+/// the `TimedOut` action will be sent to the recipient
+/// and the task will be finished with an error.
+pub struct Timeout<O> {
+    duration: Duration,
+    future: BoxFuture<'static, O>,
+    recipient: Box<dyn ActionRecipient<TimedOut>>,
+}
+
+impl<O> fmt::Debug for Timeout<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timeout")
+            .field("duration", &self.duration)
+            .field("recipient", &self.recipient)
+            .finish()
+    }
+}
+
+impl<O> Timeout<O> {
+    /// Creates a new `Timeout` lite task.
+    pub fn new<T, F>(duration: Duration, future: F, address: Address<T>) -> Self
+    where
+        T: Actor + ActionHandler<TimedOut>,
+        F: Future<Output = O> + Send + 'static,
+    {
+        Self {
+            duration,
+            future: future.boxed(),
+            recipient: Box::new(address),
+        }
+    }
+}
+
+/// `TimedOut` value that sent by `Timeout` lite task.
+#[derive(Debug)]
+pub struct TimedOut(pub Duration);
+
+impl Action for TimedOut {}
+
+#[async_trait]
+impl<O> LiteTask for Timeout<O>
+where
+    O: Send + 'static,
+{
+    type Output = O;
+
+    fn log_target(&self) -> &str {
+        "Timeout"
+    }
+
+    async fn interruptable_routine(mut self) -> Result<Self::Output, Error> {
+        let delay = crate::compat::delay(self.duration).boxed();
+        match future::select(self.future, delay).await {
+            Either::Left((output, _)) => Ok(output),
+            Either::Right(((), _)) => {
+                self.recipient.act(TimedOut(self.duration))?;
+                Err(Error::msg("deadline has elapsed"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use anyhow::Error;
+    use async_trait::async_trait;
+    use futures::channel::oneshot;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::{sleep, timeout};
+
+    type Outcome = (Vec<Option<u8>>, Vec<Duration>);
+
+    struct Watcher {
+        outputs: Vec<Option<u8>>,
+        timed_out: Vec<Duration>,
+        finished: Arc<AtomicBool>,
+        done: Option<oneshot::Sender<Outcome>>,
+    }
+
+    impl Actor for Watcher {
+        type GroupBy = ();
+
+        fn log_target(&self) -> &str {
+            "Watcher"
+        }
+    }
+
+    impl Watcher {
+        fn check_done(&mut self) {
+            if self.outputs.len() == 2 && !self.timed_out.is_empty() {
+                if let Some(done) = self.done.take() {
+                    done.send((self.outputs.clone(), self.timed_out.clone()))
+                        .ok();
+                }
+            }
+        }
+    }
+
+    #[async_trait]
+    impl StartedBy<System> for Watcher {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            let address = ctx.address().clone();
+            let fast = Timeout::new(Duration::from_secs(1), async { 1 }, address.clone());
+            ctx.spawn_task(fast, (), ());
+            let finished = self.finished.clone();
+            let slow_future = async move {
+                sleep(Duration::from_millis(300)).await;
+                finished.store(true, Ordering::SeqCst);
+                2
+            };
+            let slow = Timeout::new(Duration::from_millis(50), slow_future, address);
+            ctx.spawn_task(slow, (), ());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl InterruptedBy<System> for Watcher {
+        async fn handle(&mut self, ctx: &mut Context<Self>) -> Result<(), Error> {
+            ctx.shutdown();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl TaskEliminated<Timeout<u8>, ()> for Watcher {
+        async fn handle(
+            &mut self,
+            _id: IdOf<Timeout<u8>>,
+            _tag: (),
+            result: Result<u8, TaskError>,
+            _ctx: &mut Context<Self>,
+        ) -> Result<(), Error> {
+            self.outputs.push(result.ok());
+            self.check_done();
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler<TimedOut> for Watcher {
+        async fn handle(&mut self, msg: TimedOut, _ctx: &mut Context<Self>) -> Result<(), Error> {
+            self.timed_out.push(msg.0);
+            self.check_done();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout() -> Result<(), Error> {
+        env_logger::try_init().ok();
+        let (tx, rx) = oneshot::channel();
+        let finished = Arc::new(AtomicBool::new(false));
+        let address = System::spawn(Watcher {
+            outputs: Vec::new(),
+            timed_out: Vec::new(),
+            finished: finished.clone(),
+            done: Some(tx),
+        });
+        let (outputs, timed_out) = timeout(Duration::from_secs(5), rx).await??;
+        assert_eq!(outputs, vec![Some(1), None]);
+        assert_eq!(timed_out, vec![Duration::from_millis(50)]);
+        // The future that timed out is dropped and never completes.
+        sleep(Duration::from_millis(400)).await;
+        assert!(!finished.load(Ordering::SeqCst));
+        System::interrupt(&address)?;
+        address.join().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+//! Tools to test handlers of an `Actor` without spawning it.
+
+use crate::actor_runtime::{Actor, Context};
+use crate::handlers::{
+    Action, ActionHandler, Envelope, InstantAction, InstantActionHandler, Interaction,
+    InteractionHandler, InterruptedBy, Operation, StartedBy,
+};
+use crate::ids::Id;
+use crate::lifecycle::{Awake, Interrupt, InterruptReason};
+use crate::linkage::{Address, AddressJoint, AddressPair};
+use anyhow::Error;
+
+/// Calls handlers of an `Actor` directly and in order.
+///
+/// The `Actor` is not spawned: every call runs the handler to completion
+/// with its own `Context`. Messages the `Actor` sends to itself are kept
+/// in its mailbox until `run_pending` is called.
+pub struct TestProbe<A: Actor> {
+    actor: A,
+    context: Context<A>,
+    joint: AddressJoint<A>,
+}
+
+impl<A: Actor> TestProbe<A> {
+    /// Creates a probe for the `actor`.
+    pub fn new(actor: A) -> Self {
+        let AddressPair { joint, address } = AddressPair::for_actor(&actor);
+        let id: Id = address.id().into();
+        let context = Context::new(id, address, actor.log_target());
+        Self {
+            actor,
+            context,
+            joint,
+        }
+    }
+
+    /// Returns a reference to the `Actor`.
+    pub fn actor(&self) -> &A {
+        &self.actor
+    }
+
+    /// Returns a mutable reference to the `Actor`.
+    pub fn actor_mut(&mut self) -> &mut A {
+        &mut self.actor
+    }
+
+    /// Returns the `Context` of the `Actor`.
+    pub fn context(&mut self) -> &mut Context<A> {
+        &mut self.context
+    }
+
+    /// Returns the `Address` of the `Actor`.
+    ///
+    /// Messages sent to it are handled by `run_pending`.
+    pub fn address(&mut self) -> &mut Address<A> {
+        self.context.address()
+    }
+
+    /// Returns `true` if the `Actor` wasn't stopped by its handlers.
+    pub fn is_alive(&self) -> bool {
+        self.context.is_alive()
+    }
+
+    /// Simulates the start of the `Actor` by the supervisor of type `S`.
+    pub async fn start<S>(&mut self) -> Result<(), Error>
+    where
+        A: StartedBy<S>,
+        S: Actor,
+    {
+        self.instant(Awake::<S>::new(None)).await
+    }
+
+    /// Simulates the interruption of the `Actor` by the supervisor of type `S`.
+    pub async fn interrupt<S>(&mut self) -> Result<(), Error>
+    where
+        A: InterruptedBy<S>,
+        S: Actor,
+    {
+        self.instant(Interrupt::<S>::new(InterruptReason::default()))
+            .await
+    }
+
+    /// Handles an `Action`.
+    pub async fn act<M>(&mut self, msg: M) -> Result<(), Error>
+    where
+        M: Action,
+        A: ActionHandler<M>,
+    {
+        Envelope::new(msg)
+            .handle(&mut self.actor, &mut self.context)
+            .await
+    }
+
+    /// Handles an `InstantAction`.
+    pub async fn instant<M>(&mut self, msg: M) -> Result<(), Error>
+    where
+        M: InstantAction,
+        A: InstantActionHandler<M>,
+    {
+        Envelope::instant(msg)
+            .handle(&mut self.actor, &mut self.context)
+            .await
+    }
+
+    /// Handles an `Interaction` and returns its output.
+    pub async fn interact<I>(&mut self, request: I) -> Result<I::Output, Error>
+    where
+        I: Interaction,
+        A: InteractionHandler<I>,
+    {
+        InteractionHandler::handle(&mut self.actor, request, &mut self.context).await
+    }
+
+    /// Handles messages that the `Actor` sent to itself.
+    ///
+    /// High-priority messages are handled first, then unstashed messages.
+    /// Scheduled messages are handled immediately. Returns the number
+    /// of handled messages.
+    pub async fn run_pending(&mut self) -> Result<usize, Error> {
+        let mut handled = 0;
+        loop {
+            let mut envelope = match self.joint.hp_msg_rx.try_recv() {
+                Ok(parcel) => {
+                    if let Operation::Done { id } = parcel.operation {
+                        self.context.lifetime_tracker.remove(&id);
+                    }
+                    parcel.envelope
+                }
+                Err(_) => match self.context.unstashed.pop_front() {
+                    Some(envelope) => envelope,
+                    None => match self.joint.msg_rx.try_recv() {
+                        Some(envelope) => envelope,
+                        None => break,
+                    },
+                },
+            };
+            envelope.handle(&mut self.actor, &mut self.context).await?;
+            handled += 1;
+        }
+        Ok(handled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::*;
+    use crate::prelude::*;
+    use anyhow::Error;
+
+    #[tokio::test]
+    async fn test_probe() -> Result<(), Error> {
+        use crate::testing::TestProbe;
+        let mut probe = TestProbe::new(FairActor {
+            handled: Vec::new(),
+            done: None,
+        });
+        probe.start::<System>().await?;
+        assert!(probe.actor().handled.is_empty());
+        assert_eq!(probe.run_pending().await?, 11);
+        assert_eq!(probe.actor().handled.len(), 11);
+        assert_eq!(probe.actor().handled.last(), Some(&"regular"));
+        probe.act(Regular).await?;
+        assert_eq!(probe.actor().handled.len(), 12);
+        probe.interrupt::<System>().await?;
+        assert!(!probe.is_alive());
+        Ok(())
+    }
+}